@@ -0,0 +1,38 @@
+//! Convert command implementation.
+
+use anyhow::Result;
+use clap::Args;
+use std::path::PathBuf;
+
+use super::extract::{self, ConversionOptions, ExtractArgs};
+
+/// Convert TEX files to images
+#[derive(Args, Debug)]
+pub struct ConvertArgs {
+    /// Path to a TEX file, a directory of TEX files, a glob pattern such as
+    /// "materials/**/*.tex", or "-" to read a TEX from stdin
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+
+    #[command(flatten)]
+    pub options: ConversionOptions,
+}
+
+pub fn run(args: ConvertArgs, verbose: bool, quiet: bool) -> Result<()> {
+    let args = ExtractArgs {
+        input: args.input,
+        options: args.options,
+        ignore_exts: None,
+        only_exts: None,
+        entry_types: Vec::new(),
+        tex_directory: true,
+        single_dir: false,
+        no_convert: false,
+        min_width: None,
+        min_height: None,
+        max_width: None,
+        max_height: None,
+        textures_only: false,
+    };
+    extract::run_tex_only(args, verbose, quiet)
+}
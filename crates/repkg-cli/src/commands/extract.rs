@@ -5,30 +5,35 @@ use clap::Args;
 use colored::Colorize;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use rayon::prelude::*;
-use repkg::texture::OutputFormat;
+use repkg::texture::{
+    ConversionResult, DecompressMipmaps, DynamicImage, FilmstripLayout, FilterType,
+    NormalConvention, OutputFormat, MAX_GIF_FPS, MIN_GIF_FPS,
+};
 use repkg::{PackageReader, TexReader, TexToImageConverter};
-use repkg_core::EntryType;
+use repkg_core::{EntryType, PackageEntry, Tex};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::ffi::OsStr;
 use std::fs::{self, File};
-use std::io::{BufReader, Cursor};
+use std::io::{BufReader, BufWriter, Cursor, IsTerminal, Read, Write};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use walkdir::WalkDir;
 
+use super::input::expand_glob;
+use crate::output::format_size;
+
 /// Extract PKG files or convert TEX files to images
 #[derive(Args, Debug)]
 pub struct ExtractArgs {
-    /// Path to PKG/TEX file or directory
+    /// Path to PKG/TEX file or directory, a glob pattern such as
+    /// "materials/**/*.tex", or "-" to read a TEX from stdin
     #[arg(value_name = "INPUT")]
     pub input: PathBuf,
 
-    /// Output directory
-    #[arg(short, long, default_value = "./output")]
-    pub output: PathBuf,
-
-    /// Output image format (png, jpeg, gif, webp, bmp, tiff, tga)
-    #[arg(short, long, default_value = "png")]
-    pub format: String,
+    #[command(flatten)]
+    pub options: ConversionOptions,
 
     /// Skip files with these extensions (comma-separated)
     #[arg(short = 'i', long = "ignore-exts")]
@@ -38,6 +43,12 @@ pub struct ExtractArgs {
     #[arg(short = 'e', long = "only-exts")]
     pub only_exts: Option<String>,
 
+    /// Only extract entries of this type (repeatable). Applied together with
+    /// the extension filters: an entry must match both to be extracted
+    #[arg(long = "type", value_name = "TYPE",
+          value_parser = ["tex", "json", "shader", "model", "audio", "font", "other"])]
+    pub entry_types: Vec<String>,
+
     /// Treat input directory as containing TEX files
     #[arg(short = 't', long = "tex")]
     pub tex_directory: bool,
@@ -46,40 +57,252 @@ pub struct ExtractArgs {
     #[arg(short = 's', long = "single-dir")]
     pub single_dir: bool,
 
-    /// Recursively search subdirectories
-    #[arg(short = 'r', long)]
-    pub recursive: bool,
-
     /// Don't convert TEX files to images
     #[arg(long = "no-convert")]
     pub no_convert: bool,
 
+    /// Skip TEX entries narrower than this many pixels
+    #[arg(long = "min-width", value_name = "PX")]
+    pub min_width: Option<u32>,
+
+    /// Skip TEX entries shorter than this many pixels
+    #[arg(long = "min-height", value_name = "PX")]
+    pub min_height: Option<u32>,
+
+    /// Skip TEX entries wider than this many pixels
+    #[arg(long = "max-width", value_name = "PX")]
+    pub max_width: Option<u32>,
+
+    /// Skip TEX entries taller than this many pixels
+    #[arg(long = "max-height", value_name = "PX")]
+    pub max_height: Option<u32>,
+
+    /// Only extract TEX entries (same as --type tex)
+    #[arg(long = "textures-only", conflicts_with = "entry_types")]
+    pub textures_only: bool,
+}
+
+/// Output and conversion options shared by `extract` and `convert`.
+#[derive(Args, Debug)]
+pub struct ConversionOptions {
+    /// Output directory [default: ./output], or "-" to write the converted
+    /// texture to stdout (the default when reading from stdin)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Write the converted texture to stdout (single TEX input only)
+    #[arg(long)]
+    pub stdout: bool,
+
+    /// Output image format (png, jpeg, gif, webp, bmp, tiff, tga, qoi, dds),
+    /// or "all" to write every format
+    #[arg(short, long, default_value = "png")]
+    pub format: String,
+
+    /// Recursively search subdirectories
+    #[arg(short = 'r', long)]
+    pub recursive: bool,
+
     /// Overwrite existing files
     #[arg(long)]
     pub overwrite: bool,
 
-    /// Show what would be extracted without writing files
+    /// Skip inputs whose outputs already exist without reading them: a TEX
+    /// file is skipped when its converted image exists, a PKG when a previous
+    /// --skip-existing run recorded its outputs and they all still exist
+    #[arg(long = "skip-existing", conflicts_with = "overwrite")]
+    pub skip_existing: bool,
+
+    /// Show what would be written, with estimated sizes, without writing files
     #[arg(long = "dry-run")]
     pub dry_run: bool,
 
     /// Number of parallel jobs (0 = auto)
     #[arg(short = 'j', long, default_value = "0")]
     pub jobs: usize,
+
+    /// Composite converted images over a gray checkerboard to show transparency
+    /// (optional checker size in pixels, default 8)
+    #[arg(
+        long,
+        value_name = "SIZE",
+        num_args = 0..=1,
+        default_missing_value = "8"
+    )]
+    pub checkerboard: Option<u32>,
+
+    /// Gamma adjustment for converted images (>1 brightens midtones)
+    #[arg(long)]
+    pub gamma: Option<f32>,
+
+    /// Brightness offset for converted images (-255 to 255)
+    #[arg(long, allow_hyphen_values = true)]
+    pub brightness: Option<i16>,
+
+    /// Play animated output at a constant frame rate (0.01-100) instead of the
+    /// stored per-frame timings
+    #[arg(long = "gif-fps", value_name = "FPS")]
+    pub gif_fps: Option<f32>,
+
+    /// GIF encoder speed (1-30, higher is faster with lower quality) [default: 10]
+    #[arg(long = "gif-speed", value_name = "SPEED", value_parser = clap::value_parser!(i32).range(1..=30))]
+    pub gif_speed: Option<i32>,
+
+    /// Draw each animation frame over the previous one, for animations
+    /// stored as partially transparent deltas
+    #[arg(long = "composite-frames")]
+    pub composite_frames: bool,
+
+    /// Keep the full padded texture instead of cropping to the image size
+    #[arg(long = "no-crop")]
+    pub no_crop: bool,
+
+    /// Resampling filter for resized animation frames and filmstrip cells
+    /// (nearest keeps pixel art sharp) [default: lanczos]
+    #[arg(long, value_name = "FILTER", value_parser = ["nearest", "triangle", "lanczos"])]
+    pub filter: Option<String>,
+
+    /// Write every mipmap level of the first image as <name>.mip<N>.<ext>
+    /// instead of only the full-size image
+    #[arg(long)]
+    pub mipmaps: bool,
+
+    /// Export animated textures as a single filmstrip image plus JSON metadata
+    /// instead of a GIF (horizontal or vertical)
+    #[arg(
+        long,
+        value_name = "LAYOUT",
+        num_args = 0..=1,
+        default_missing_value = "horizontal"
+    )]
+    pub filmstrip: Option<String>,
+
+    /// Transparent padding between filmstrip frames in pixels
+    #[arg(long = "filmstrip-padding", value_name = "PIXELS", default_value = "0")]
+    pub filmstrip_padding: u32,
+
+    /// Export animated textures as a single contact sheet image with this many
+    /// frames per row instead of a GIF
+    #[arg(
+        long = "contact-sheet",
+        value_name = "COLUMNS",
+        conflicts_with = "filmstrip",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub contact_sheet: Option<u32>,
+
+    /// Treat RG88 textures as normal maps, reconstruct Z and output in the
+    /// given green channel convention (dx as stored by Wallpaper Engine, or gl)
+    #[arg(long = "normal-convention", value_name = "CONVENTION")]
+    pub normal_convention: Option<String>,
+
+    /// Write a <name>.decode.json next to each converted texture describing
+    /// the decode decisions (format inference, crop, passthrough, ...)
+    #[arg(long = "decode-trace")]
+    pub decode_trace: bool,
+
+    /// Write a JSON manifest listing every extracted entry and its conversion
+    #[arg(long, value_name = "FILE")]
+    pub manifest: Option<PathBuf>,
+
+    /// Write a SHA-256 manifest of all written files (sha256sum format)
+    #[arg(long, value_name = "FILE")]
+    pub checksums: Option<PathBuf>,
+}
+
+impl ExtractArgs {
+    /// Output directory for extracted files.
+    fn output_dir(&self) -> &Path {
+        self.options
+            .output
+            .as_deref()
+            .unwrap_or(Path::new("./output"))
+    }
+
+    /// Whether the converted texture should be written to stdout.
+    fn writes_stdout(&self) -> bool {
+        match &self.options.output {
+            Some(output) => self.options.stdout || is_stdio(output),
+            None => self.options.stdout || is_stdio(&self.input),
+        }
+    }
 }
 
 pub fn run(args: ExtractArgs, verbose: bool, quiet: bool) -> Result<()> {
+    execute(args, false, verbose, quiet)
+}
+
+/// Run an extraction that only converts TEX files, skipping PKG inputs.
+pub(super) fn run_tex_only(args: ExtractArgs, verbose: bool, quiet: bool) -> Result<()> {
+    execute(args, true, verbose, quiet)
+}
+
+fn execute(args: ExtractArgs, tex_only: bool, verbose: bool, quiet: bool) -> Result<()> {
     // Validate output format
-    let output_format = OutputFormat::parse(&args.format).ok_or_else(|| {
-        anyhow::anyhow!(
-            "Invalid output format '{}'. Valid formats: {}",
-            args.format,
-            OutputFormat::all()
-                .iter()
-                .map(|f| f.extension())
-                .collect::<Vec<_>>()
-                .join(", ")
-        )
-    })?;
+    let all_formats = args.options.format.eq_ignore_ascii_case("all");
+    let output_format = if all_formats {
+        OutputFormat::Png
+    } else {
+        OutputFormat::parse(&args.options.format).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid output format '{}'. Valid formats: {}, all",
+                args.options.format,
+                OutputFormat::all()
+                    .iter()
+                    .map(|f| f.extension())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?
+    };
+
+    let filmstrip = args
+        .options
+        .filmstrip
+        .as_deref()
+        .map(|layout| {
+            FilmstripLayout::parse(layout).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Invalid filmstrip layout '{}'. Valid layouts: horizontal, vertical",
+                    layout
+                )
+            })
+        })
+        .transpose()?;
+    let sheet = filmstrip
+        .map(SheetExport::Filmstrip)
+        .or(args.options.contact_sheet.map(SheetExport::ContactSheet));
+
+    let normal_convention = args
+        .options
+        .normal_convention
+        .as_deref()
+        .map(|convention| {
+            NormalConvention::parse(convention).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Invalid normal convention '{}'. Valid conventions: dx, gl",
+                    convention
+                )
+            })
+        })
+        .transpose()?;
+
+    if let Some(gamma) = args.options.gamma {
+        if !(gamma.is_finite() && gamma > 0.0) {
+            anyhow::bail!("Invalid gamma '{}': must be a positive number", gamma);
+        }
+    }
+
+    if let Some(fps) = args.options.gif_fps {
+        if !(MIN_GIF_FPS..=MAX_GIF_FPS).contains(&fps) {
+            anyhow::bail!(
+                "Invalid GIF frame rate '{}': must be between {} and {}",
+                fps,
+                MIN_GIF_FPS,
+                MAX_GIF_FPS
+            );
+        }
+    }
 
     // Parse extension filters
     let ignore_exts: Vec<String> = args
@@ -94,34 +317,115 @@ pub fn run(args: ExtractArgs, verbose: bool, quiet: bool) -> Result<()> {
         .map(|s| normalize_extensions(s))
         .unwrap_or_default();
 
+    let entry_types: Vec<EntryType> = if args.textures_only {
+        vec![EntryType::Tex]
+    } else {
+        args.entry_types.iter().map(|t| entry_type(t)).collect()
+    };
+
+    for (axis, min, max) in [
+        ("width", args.min_width, args.max_width),
+        ("height", args.min_height, args.max_height),
+    ] {
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                anyhow::bail!(
+                    "Invalid {} range: --min-{} {} is larger than --max-{} {}",
+                    axis,
+                    axis,
+                    min,
+                    axis,
+                    max
+                );
+            }
+        }
+    }
+
     // Configure thread pool
-    if args.jobs > 0 {
+    if args.options.jobs > 0 {
         rayon::ThreadPoolBuilder::new()
-            .num_threads(args.jobs)
+            .num_threads(args.options.jobs)
             .build_global()
             .ok();
     }
 
     // Determine input type
     let input_path = &args.input;
-    let metadata = fs::metadata(input_path)
-        .with_context(|| format!("Failed to access input: {}", input_path.display()))?;
-
     let context = ExtractContext {
         args: &args,
         output_format,
+        all_formats,
+        sheet,
+        normal_convention,
         ignore_exts,
         only_exts,
+        entry_types,
+        tex_only,
         verbose,
         quiet,
+        checksums: Mutex::new(Vec::new()),
+        manifest: Mutex::new(Vec::new()),
+        estimated_bytes: AtomicU64::new(0),
+        bytes_written: AtomicU64::new(0),
+        textures_converted: AtomicUsize::new(0),
     };
 
-    if metadata.is_file() {
-        extract_file(&context, input_path)?;
-    } else if metadata.is_dir() {
-        extract_directory(&context, input_path)?;
+    if args.writes_stdout() {
+        // Status output would corrupt the piped image, so return right away
+        return convert_to_stdout(&context, input_path);
+    }
+
+    if is_stdio(input_path) {
+        let bytes = read_stdin()?;
+        extract_tex_bytes(&context, Path::new("stdin"), &bytes)?;
+    } else if let Some(files) = expand_glob(input_path, quiet)? {
+        if !files.is_empty() {
+            extract_files(&context, &files, "matching", extract_file)?;
+        }
     } else {
-        anyhow::bail!("Input is neither a file nor directory");
+        let metadata = fs::metadata(input_path)
+            .with_context(|| format!("Failed to access input: {}", input_path.display()))?;
+
+        if metadata.is_file() {
+            extract_file(&context, input_path)?;
+        } else if metadata.is_dir() {
+            extract_directory(&context, input_path)?;
+        } else {
+            anyhow::bail!("Input is neither a file nor directory");
+        }
+    }
+
+    if let Some(manifest_path) = &args.options.manifest {
+        if !args.options.dry_run {
+            let count = write_extraction_manifest(&context, manifest_path)?;
+            if !quiet {
+                println!(
+                    "Wrote {} manifest entries to {}",
+                    count.to_string().cyan(),
+                    manifest_path.display()
+                );
+            }
+        }
+    }
+
+    if let Some(manifest_path) = &args.options.checksums {
+        if !args.options.dry_run {
+            let count = write_checksum_manifest(&context, manifest_path)?;
+            if !quiet {
+                println!(
+                    "Wrote {} checksums to {}",
+                    count.to_string().cyan(),
+                    manifest_path.display()
+                );
+            }
+        }
+    }
+
+    if args.options.dry_run && !quiet {
+        println!(
+            "Estimated output size: ~{}",
+            format_size(context.estimated_bytes.load(Ordering::Relaxed)).cyan()
+        );
     }
 
     if !quiet {
@@ -131,16 +435,730 @@ pub fn run(args: ExtractArgs, verbose: bool, quiet: bool) -> Result<()> {
     Ok(())
 }
 
+/// Whether a path argument is `-`, meaning stdin or stdout.
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Read all of stdin.
+fn read_stdin() -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    std::io::stdin()
+        .lock()
+        .read_to_end(&mut bytes)
+        .context("Failed to read stdin")?;
+    Ok(bytes)
+}
+
+/// Convert a single TEX (a file, or `-` for stdin) and write the converted
+/// bytes to stdout, bypassing the output directory and progress reporting.
+fn convert_to_stdout(ctx: &ExtractContext, input: &Path) -> Result<()> {
+    if ctx.all_formats {
+        anyhow::bail!("--format all cannot be written to stdout");
+    }
+    if matches!(ctx.sheet, Some(SheetExport::Filmstrip(_))) {
+        anyhow::bail!(
+            "--filmstrip cannot be written to stdout, as its metadata needs its own file"
+        );
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    if stdout.is_terminal() {
+        anyhow::bail!(
+            "Refusing to write binary image data to a terminal; redirect stdout to a file or pipe"
+        );
+    }
+
+    let bytes = if is_stdio(input) {
+        read_stdin()?
+    } else if input.is_file() {
+        fs::read(input).with_context(|| format!("Failed to read {}", input.display()))?
+    } else {
+        anyhow::bail!("Writing to stdout requires a single TEX file or '-' as input");
+    };
+
+    let tex = ctx
+        .read_tex(&bytes)
+        .with_context(|| format!("Failed to parse TEX: {}", input.display()))?;
+    let converter = ctx.converter();
+    let result = match ctx.sheet_for(&tex) {
+        Some(SheetExport::ContactSheet(columns)) => {
+            converter.to_contact_sheet(&tex, columns, ctx.still_format())
+        }
+        _ => converter.convert(&tex, ctx.formats_for(&tex, &converter)[0]),
+    }
+    .with_context(|| format!("Failed to convert {}", input.display()))?;
+
+    // A closed pipe (e.g. `| head`) is not an error
+    match stdout
+        .write_all(&result.bytes)
+        .and_then(|()| stdout.flush())
+    {
+        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
 struct ExtractContext<'a> {
     args: &'a ExtractArgs,
     output_format: OutputFormat,
+    /// Convert textures to every format (`--format all`)
+    all_formats: bool,
+    /// Single-image export of animated textures (`--filmstrip`, `--contact-sheet`)
+    sheet: Option<SheetExport>,
+    normal_convention: Option<NormalConvention>,
     ignore_exts: Vec<String>,
     only_exts: Vec<String>,
+    /// Entry types to extract (`--type`); empty means all
+    entry_types: Vec<EntryType>,
+    /// Only convert TEX files (`convert`); PKG inputs are skipped
+    tex_only: bool,
     verbose: bool,
     quiet: bool,
+    /// `(path, sha256)` of every file written, shared across worker threads
+    checksums: Mutex<Vec<(PathBuf, String)>>,
+    /// Manifest records of extracted entries, shared across worker threads
+    manifest: Mutex<Vec<ManifestEntry>>,
+    /// Estimated bytes a dry run would have written
+    estimated_bytes: AtomicU64,
+    /// Total bytes of every file written
+    bytes_written: AtomicU64,
+    /// Number of textures converted to at least one output
+    textures_converted: AtomicUsize,
+}
+
+/// Directory under the output directory holding `--skip-existing` markers.
+const MARKER_DIR: &str = ".repkg-extracted";
+
+impl ExtractContext<'_> {
+    /// Read a TEX, keeping DXT data block-compressed when DDS is among the
+    /// output formats.
+    ///
+    /// The converter decompresses block data on demand for the other formats,
+    /// but embedded images and videos are used as stored, so those are LZ4
+    /// decompressed here.
+    fn read_tex(&self, bytes: &[u8]) -> repkg::Result<Tex> {
+        if self.output_format != OutputFormat::Dds && !self.all_formats {
+            return TexReader::new().read_from(&mut Cursor::new(bytes));
+        }
+
+        let mut tex = TexReader::without_decompression().read_from(&mut Cursor::new(bytes))?;
+        for image in &mut tex.images_container.images {
+            if !image.mipmaps.iter().any(|m| m.format.is_compressed()) {
+                image.decompress_all()?;
+            }
+        }
+        Ok(tex)
+    }
+
+    /// Build a texture converter configured from the command-line options.
+    fn converter(&self) -> TexToImageConverter<'static> {
+        let mut converter = TexToImageConverter::new();
+        if let Some(checker_size) = self.args.options.checkerboard {
+            converter = converter.with_checkerboard(checker_size);
+        }
+        if let Some(gamma) = self.args.options.gamma {
+            converter = converter.with_gamma(gamma);
+        }
+        if let Some(brightness) = self.args.options.brightness {
+            converter = converter.with_brightness(brightness);
+        }
+        if let Some(fps) = self.args.options.gif_fps {
+            converter = converter.with_gif_fps(fps);
+        }
+        if let Some(speed) = self.args.options.gif_speed {
+            converter = converter.with_gif_speed(speed);
+        }
+        if self.args.options.composite_frames {
+            converter = converter.with_frame_compositing(true);
+        }
+        if self.args.options.no_crop {
+            converter = converter.with_crop(false);
+        }
+        if let Some(filter) = self.args.options.filter.as_deref() {
+            converter = converter.with_filter(resize_filter(filter));
+        }
+        if let Some(convention) = self.normal_convention {
+            converter = converter
+                .with_normal_reconstruction(true)
+                .with_normal_convention(convention);
+        }
+        converter
+    }
+
+    /// Sheet to export this texture as, if filmstrip or contact sheet export
+    /// applies.
+    fn sheet_for(&self, tex: &Tex) -> Option<SheetExport> {
+        self.sheet
+            .filter(|_| tex.is_gif() && self.mipmap_count(tex).is_none())
+    }
+
+    /// Number of mipmap levels to write separately with `--mipmaps`.
+    ///
+    /// `None` when the flag is unset or the texture is a video.
+    fn mipmap_count(&self, tex: &Tex) -> Option<usize> {
+        if !self.args.options.mipmaps || tex.is_video() {
+            return None;
+        }
+        tex.first_image().map(|image| image.mipmap_count())
+    }
+
+    /// Estimated total size of a texture's converted outputs, for dry runs.
+    fn estimate_conversions(&self, tex: &Tex, converter: &TexToImageConverter) -> u64 {
+        self.conversions_for(tex, converter)
+            .into_iter()
+            .map(|(level, format)| estimate_conversion(converter, tex, level, format))
+            .sum()
+    }
+
+    /// Wrap a texture for conversion, decoding it once up front when
+    /// `--format all` will encode the same image to every format.
+    ///
+    /// A failed decode is left to the individual conversions to report.
+    fn source<'t>(&self, tex: &'t Tex, converter: &TexToImageConverter) -> SourceTex<'t> {
+        let reuse = self.all_formats
+            && !self.args.options.decode_trace
+            && self.mipmap_count(tex).is_none()
+            && !tex.is_gif()
+            && !tex.is_video();
+        SourceTex {
+            tex,
+            decoded: reuse.then(|| converter.decode(tex).ok()).flatten(),
+        }
+    }
+
+    /// Conversions to perform for a texture, as `(mipmap level, format)`.
+    ///
+    /// A `None` level converts the texture as a whole. With `--mipmaps` each
+    /// level of the first image is converted to still formats instead.
+    fn conversions_for(
+        &self,
+        tex: &Tex,
+        converter: &TexToImageConverter,
+    ) -> Vec<(Option<usize>, OutputFormat)> {
+        let Some(count) = self.mipmap_count(tex) else {
+            return self
+                .formats_for(tex, converter)
+                .into_iter()
+                .map(|format| (None, format))
+                .collect();
+        };
+
+        let formats = if self.all_formats {
+            OutputFormat::all().to_vec()
+        } else {
+            vec![self.still_format()]
+        };
+        (0..count)
+            .flat_map(|level| formats.iter().map(move |&format| (Some(level), format)))
+            .collect()
+    }
+
+    /// Output formats to convert a texture to.
+    ///
+    /// Video textures are always copied as MP4; with `--format all` every
+    /// other texture is written in each image format. Animated textures are
+    /// written as animated WebP with `--format webp` and as GIF otherwise.
+    fn formats_for(&self, tex: &Tex, converter: &TexToImageConverter) -> Vec<OutputFormat> {
+        if self.sheet_for(tex).is_some() {
+            vec![self.still_format()]
+        } else if tex.is_video() {
+            vec![converter.recommended_format(tex)]
+        } else if self.all_formats {
+            OutputFormat::all().to_vec()
+        } else if tex.is_gif() && self.output_format == OutputFormat::WebP {
+            vec![OutputFormat::WebP]
+        } else if tex.is_gif() {
+            vec![converter.recommended_format(tex)]
+        } else {
+            vec![self.output_format]
+        }
+    }
+
+    /// Still image format for filmstrips and contact sheets (animated formats
+    /// fall back to PNG).
+    fn still_format(&self) -> OutputFormat {
+        match self.output_format {
+            OutputFormat::Gif | OutputFormat::Mp4 => OutputFormat::Png,
+            format => format,
+        }
+    }
+
+    /// Whether a standalone TEX file's converted image already exists.
+    ///
+    /// Judged from the file name alone so the TEX need not be read. Animated
+    /// and video textures may be written as GIF or MP4 instead of the
+    /// requested format, so those outputs count too. Always false with `--format all` or
+    /// `--mipmaps`, which write several files per texture.
+    fn tex_output_exists(&self, path: &Path) -> bool {
+        if self.all_formats || self.args.options.mipmaps {
+            return false;
+        }
+        let file_name = path.file_name().unwrap_or(OsStr::new("output"));
+        let base = self.args.output_dir().join(file_name);
+        [
+            self.output_format,
+            self.still_format(),
+            OutputFormat::Gif,
+            OutputFormat::Mp4,
+        ]
+        .into_iter()
+        .any(|format| converted_path(&base, None, format).exists())
+    }
+
+    /// Path of the `--skip-existing` marker listing a PKG's outputs.
+    ///
+    /// Named after the package and a hash of its full path, as workshop
+    /// items commonly share the file name `scene.pkg`.
+    fn pkg_marker_path(&self, pkg: &Path) -> PathBuf {
+        let source = fs::canonicalize(pkg).unwrap_or_else(|_| pkg.to_path_buf());
+        let digest = Sha256::digest(source.to_string_lossy().as_bytes());
+        let hash: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+        let stem = pkg
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("package");
+        self.args
+            .output_dir()
+            .join(MARKER_DIR)
+            .join(format!("{}-{}.txt", stem, hash))
+    }
+
+    /// Options that change which files a PKG extraction writes, recorded in
+    /// markers so a run with different settings does not skip the package.
+    fn output_settings(&self) -> String {
+        let bound = |value: Option<u32>| value.map_or(String::new(), |v| v.to_string());
+        format!(
+            "format={} convert={} mipmaps={} filmstrip={} single_dir={} only={} ignore={} types={} \
+             size={}-{}x{}-{}",
+            self.args.options.format.to_lowercase(),
+            !self.args.no_convert,
+            self.args.options.mipmaps,
+            self.sheet.map_or("none".to_string(), |sheet| sheet.setting()),
+            self.args.single_dir,
+            self.only_exts.join(","),
+            self.ignore_exts.join(","),
+            self.entry_types
+                .iter()
+                .map(|t| t.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+            bound(self.args.min_width),
+            bound(self.args.max_width),
+            bound(self.args.min_height),
+            bound(self.args.max_height),
+        )
+    }
+
+    /// Whether the TEX in `bytes` lies within the `--min-*`/`--max-*`
+    /// dimension bounds, returning its size when it does not.
+    ///
+    /// Only the headers are parsed. Textures whose headers cannot be read are
+    /// let through so that the conversion reports the error.
+    fn outside_dimensions(&self, bytes: &[u8]) -> Option<(u32, u32)> {
+        let args = self.args;
+        if args.min_width.is_none()
+            && args.min_height.is_none()
+            && args.max_width.is_none()
+            && args.max_height.is_none()
+        {
+            return None;
+        }
+
+        let tex = TexReader::headers_only()
+            .read_from(&mut Cursor::new(bytes))
+            .ok()?;
+        let (width, height) = (tex.header.image_width, tex.header.image_height);
+        let widths = args.min_width.unwrap_or(0)..=args.max_width.unwrap_or(u32::MAX);
+        let heights = args.min_height.unwrap_or(0)..=args.max_height.unwrap_or(u32::MAX);
+        let fits = widths.contains(&width) && heights.contains(&height);
+        (!fits).then_some((width, height))
+    }
+
+    /// Whether an earlier `--skip-existing` run extracted `pkg` with the same
+    /// settings and every output it recorded still exists.
+    ///
+    /// Only metadata of the PKG is read; a package modified after its marker
+    /// was written is extracted again.
+    fn pkg_already_extracted(&self, pkg: &Path) -> bool {
+        let marker = self.pkg_marker_path(pkg);
+        let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+        match (modified(&marker), modified(pkg)) {
+            (Some(marked), Some(changed)) if marked >= changed => {}
+            _ => return false,
+        }
+
+        let Ok(contents) = fs::read_to_string(&marker) else {
+            return false;
+        };
+        let output_dir = self.args.output_dir();
+        let mut lines = contents.lines();
+        lines.next() == Some(self.output_settings().as_str())
+            && lines.all(|line| output_dir.join(line).exists())
+    }
+
+    /// Record the outputs of an extracted PKG for later `--skip-existing` runs.
+    fn write_pkg_marker(&self, pkg: &Path, outputs: &[PathBuf]) -> Result<()> {
+        let marker = self.pkg_marker_path(pkg);
+        if let Some(parent) = marker.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let output_dir = self.args.output_dir();
+        let mut contents = self.output_settings();
+        contents.push('\n');
+        for output in outputs {
+            let relative = output.strip_prefix(output_dir).unwrap_or(output);
+            contents.push_str(&relative.display().to_string());
+            contents.push('\n');
+        }
+        fs::write(&marker, contents)
+            .with_context(|| format!("Failed to write {}", marker.display()))
+    }
+
+    /// Add an entry to the extraction manifest, if one was requested.
+    fn record(&self, entry: ManifestEntry) -> Result<()> {
+        if self.args.options.manifest.is_some() {
+            self.manifest
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Manifest collector poisoned"))?
+                .push(entry);
+        }
+        Ok(())
+    }
+
+    /// Write an output file, hashing its contents on the way out when a
+    /// checksum manifest was requested.
+    fn write_output(&self, path: &Path, bytes: &[u8]) -> Result<()> {
+        self.bytes_written
+            .fetch_add(bytes.len() as u64, Ordering::Relaxed);
+        if self.args.options.checksums.is_none() {
+            fs::write(path, bytes)?;
+            return Ok(());
+        }
+
+        let file =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut writer = HashingWriter::new(BufWriter::new(file));
+        writer.write_all(bytes)?;
+        let hash = writer.finish()?;
+
+        self.checksums
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Checksum collector poisoned"))?
+            .push((path.to_path_buf(), hash));
+        Ok(())
+    }
+}
+
+/// A record of one extracted entry in the `--manifest` JSON.
+#[derive(Serialize)]
+struct ManifestEntry {
+    /// Package the entry came from (absent for standalone TEX files)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    package: Option<String>,
+    /// Path inside the package, or the input TEX file
+    source: String,
+    /// Path the entry was written to
+    output: String,
+    /// Size of the original entry in bytes
+    size: u64,
+    entry_type: &'static str,
+    converted: bool,
+    /// Converted files written for the entry
+    conversions: Vec<ManifestConversion>,
+}
+
+/// A converted file in the `--manifest` JSON.
+#[derive(Serialize)]
+struct ManifestConversion {
+    format: &'static str,
+    output: String,
+}
+
+impl ManifestConversion {
+    fn new(path: &Path, format: OutputFormat) -> Self {
+        Self {
+            format: format.extension(),
+            output: path.display().to_string(),
+        }
+    }
+}
+
+/// Write the collected manifest entries as a JSON array, sorted by source.
+fn write_extraction_manifest(ctx: &ExtractContext, manifest_path: &Path) -> Result<usize> {
+    let mut entries = ctx
+        .manifest
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Manifest collector poisoned"))?;
+    entries.sort_by(|a, b| (&a.package, &a.source).cmp(&(&b.package, &b.source)));
+
+    if let Some(parent) = manifest_path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+    let json = serde_json::to_string_pretty(&*entries)?;
+    fs::write(manifest_path, json)
+        .with_context(|| format!("Failed to write {}", manifest_path.display()))?;
+
+    Ok(entries.len())
+}
+
+/// Single-image export of an animated texture, replacing its GIF.
+#[derive(Debug, Clone, Copy)]
+enum SheetExport {
+    /// Frames in one row or column, with `.filmstrip.json` metadata
+    Filmstrip(FilmstripLayout),
+    /// Frames in a grid with this many columns
+    ContactSheet(u32),
+}
+
+impl SheetExport {
+    /// Name used in progress messages.
+    fn label(&self) -> &'static str {
+        match self {
+            SheetExport::Filmstrip(_) => "Filmstrip",
+            SheetExport::ContactSheet(_) => "Contact sheet",
+        }
+    }
+
+    /// Layout description used in progress messages and `--skip-existing`
+    /// settings.
+    fn setting(&self) -> String {
+        match self {
+            SheetExport::Filmstrip(layout) => layout.as_str().to_string(),
+            SheetExport::ContactSheet(columns) => format!("contact-{}", columns),
+        }
+    }
+}
+
+/// Write a texture's filmstrip or contact sheet image.
+///
+/// Returns the path of the written image.
+fn write_sheet(
+    ctx: &ExtractContext,
+    converter: &TexToImageConverter,
+    tex: &Tex,
+    sheet: SheetExport,
+    base_path: &Path,
+) -> Result<PathBuf> {
+    match sheet {
+        SheetExport::Filmstrip(layout) => write_filmstrip(ctx, converter, tex, layout, base_path),
+        SheetExport::ContactSheet(columns) => {
+            let result = converter.to_contact_sheet(tex, columns, ctx.still_format())?;
+            let image_path = base_path.with_extension(result.format.extension());
+            ctx.write_output(&image_path, &result.bytes)?;
+            Ok(image_path)
+        }
+    }
+}
+
+/// Filmstrip metadata written next to the filmstrip image.
+#[derive(Serialize)]
+struct FilmstripManifest {
+    image: String,
+    layout: &'static str,
+    frame_count: usize,
+    cell_width: u32,
+    cell_height: u32,
+    padding: u32,
+    frame_durations_ms: Vec<u32>,
+}
+
+/// Write a texture's filmstrip image and its `.filmstrip.json` metadata.
+///
+/// Returns the path of the written image.
+fn write_filmstrip(
+    ctx: &ExtractContext,
+    converter: &TexToImageConverter,
+    tex: &Tex,
+    layout: FilmstripLayout,
+    base_path: &Path,
+) -> Result<PathBuf> {
+    let filmstrip = converter.to_filmstrip(
+        tex,
+        layout,
+        ctx.args.options.filmstrip_padding,
+        ctx.still_format(),
+    )?;
+
+    let image_path = base_path.with_extension(filmstrip.result.format.extension());
+    ctx.write_output(&image_path, &filmstrip.result.bytes)?;
+
+    let manifest = FilmstripManifest {
+        image: image_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        layout: filmstrip.layout.as_str(),
+        frame_count: filmstrip.frame_count,
+        cell_width: filmstrip.cell_width,
+        cell_height: filmstrip.cell_height,
+        padding: filmstrip.padding,
+        frame_durations_ms: filmstrip.frame_durations_ms,
+    };
+    let json = serde_json::to_string_pretty(&manifest)?;
+    ctx.write_output(&base_path.with_extension("filmstrip.json"), json.as_bytes())?;
+
+    Ok(image_path)
+}
+
+/// Decode trace written next to a converted texture.
+#[derive(Serialize)]
+struct DecodeTraceManifest {
+    container_magic: String,
+    downgraded_to_v3: bool,
+    kind: &'static str,
+    declared_format: String,
+    inferred_format: String,
+    lz4_compressed: bool,
+    dxt_decompressed: bool,
+    cropped: bool,
+    passthrough: bool,
+    post_processed: bool,
+    output_format: &'static str,
+    output_width: Option<u32>,
+    output_height: Option<u32>,
+    notes: Vec<String>,
+}
+
+/// A texture to convert, with its first image decoded up front when
+/// `--format all` encodes the same image several times.
+struct SourceTex<'a> {
+    tex: &'a Tex,
+    decoded: Option<DynamicImage>,
+}
+
+/// Convert a texture, writing its `.decode.json` trace when requested.
+///
+/// Formats that would not pass stored data through are encoded from the
+/// source's decoded image, if it has one.
+fn convert_texture(
+    ctx: &ExtractContext,
+    converter: &TexToImageConverter,
+    source: &SourceTex,
+    format: OutputFormat,
+    base_path: &Path,
+) -> Result<ConversionResult> {
+    let tex = source.tex;
+    if let Some(image) = &source.decoded {
+        if format != OutputFormat::Dds && !converter.would_passthrough(tex, format) {
+            return Ok(converter.convert_decoded(image, format)?);
+        }
+    }
+
+    if !ctx.args.options.decode_trace {
+        return Ok(converter.convert(tex, format)?);
+    }
+
+    let (result, trace) = converter.convert_traced(tex, format)?;
+    let manifest = DecodeTraceManifest {
+        container_magic: trace.container_magic,
+        downgraded_to_v3: trace.downgraded_to_v3,
+        kind: trace.kind.as_str(),
+        declared_format: format!("{:?}", trace.declared_format),
+        inferred_format: format!("{:?}", trace.inferred_format),
+        lz4_compressed: trace.lz4_compressed,
+        dxt_decompressed: trace.dxt_decompressed,
+        cropped: trace.cropped,
+        passthrough: trace.passthrough,
+        post_processed: trace.post_processed,
+        output_format: trace.output_format.extension(),
+        output_width: trace.output_dimensions.map(|(width, _)| width),
+        output_height: trace.output_dimensions.map(|(_, height)| height),
+        notes: trace.notes,
+    };
+    let json = serde_json::to_string_pretty(&manifest)?;
+    ctx.write_output(&base_path.with_extension("decode.json"), json.as_bytes())?;
+
+    Ok(result)
+}
+
+/// Writer adapter that computes a SHA-256 digest of everything written through it.
+struct HashingWriter<W: Write> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> HashingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    /// Flush the inner writer and return the lowercase hex digest.
+    fn finish(mut self) -> Result<String> {
+        self.inner.flush()?;
+        Ok(format!("{:x}", self.hasher.finalize()))
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Write collected checksums as `<hash>  <path>` lines, sorted by path.
+///
+/// Paths are recorded as written, so `sha256sum -c` must be run from the same
+/// working directory as the extraction.
+fn write_checksum_manifest(ctx: &ExtractContext, manifest_path: &Path) -> Result<usize> {
+    let mut checksums = ctx
+        .checksums
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Checksum collector poisoned"))?;
+    checksums.sort_by(|a, b| a.0.cmp(&b.0));
+
+    if let Some(parent) = manifest_path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+
+    let file = File::create(manifest_path)
+        .with_context(|| format!("Failed to create {}", manifest_path.display()))?;
+    let mut writer = BufWriter::new(file);
+    for (path, hash) in checksums.iter() {
+        writeln!(writer, "{}  {}", hash, path.display())?;
+    }
+    writer.flush()?;
+
+    Ok(checksums.len())
+}
+
+/// Map a `--filter` value (already validated by clap) to a resampling filter.
+fn resize_filter(name: &str) -> FilterType {
+    match name {
+        "nearest" => FilterType::Nearest,
+        "triangle" => FilterType::Triangle,
+        _ => FilterType::Lanczos3,
+    }
+}
+
+/// Map a `--type` value (already validated by clap) to an entry type.
+fn entry_type(name: &str) -> EntryType {
+    match name {
+        "tex" => EntryType::Tex,
+        "json" => EntryType::Json,
+        "shader" => EntryType::Shader,
+        "model" => EntryType::Model,
+        "audio" => EntryType::Audio,
+        "font" => EntryType::Font,
+        _ => EntryType::Other,
+    }
 }
 
-fn normalize_extensions(s: &str) -> Vec<String> {
+pub(super) fn normalize_extensions(s: &str) -> Vec<String> {
     s.split(',')
         .map(|ext| {
             let ext = ext.trim().to_lowercase();
@@ -161,6 +1179,16 @@ fn extract_file(ctx: &ExtractContext, path: &Path) -> Result<()> {
         .unwrap_or_default();
 
     match ext.as_str() {
+        "pkg" if ctx.tex_only => {
+            if !ctx.quiet {
+                println!(
+                    "{} Skipping PKG file {} (use extract to unpack packages)",
+                    "warning:".yellow(),
+                    path.display()
+                );
+            }
+            Ok(())
+        }
         "pkg" => extract_pkg(ctx, path),
         "tex" => extract_tex(ctx, path),
         _ => {
@@ -180,7 +1208,7 @@ fn extract_directory(ctx: &ExtractContext, dir: &Path) -> Result<()> {
     let pattern = if ctx.args.tex_directory { "tex" } else { "pkg" };
 
     // Collect matching files
-    let files: Vec<PathBuf> = if ctx.args.recursive {
+    let files: Vec<PathBuf> = if ctx.args.options.recursive {
         WalkDir::new(dir)
             .into_iter()
             .filter_map(|e| e.ok())
@@ -221,12 +1249,23 @@ fn extract_directory(ctx: &ExtractContext, dir: &Path) -> Result<()> {
         return Ok(());
     }
 
+    let process = if ctx.args.tex_directory {
+        extract_tex
+    } else {
+        extract_pkg
+    };
+    extract_files(ctx, &files, &pattern.to_uppercase(), process)
+}
+
+/// Process files in parallel with a progress bar, reporting a summary.
+fn extract_files(
+    ctx: &ExtractContext,
+    files: &[PathBuf],
+    label: &str,
+    process: fn(&ExtractContext, &Path) -> Result<()>,
+) -> Result<()> {
     if !ctx.quiet {
-        println!(
-            "Found {} {} files",
-            files.len().to_string().cyan(),
-            pattern.to_uppercase()
-        );
+        println!("Found {} {} files", files.len().to_string().cyan(), label);
     }
 
     // Process files in parallel with progress
@@ -242,11 +1281,7 @@ fn extract_directory(ctx: &ExtractContext, dir: &Path) -> Result<()> {
     let error_count = Arc::new(AtomicUsize::new(0));
 
     files.par_iter().for_each(|file| {
-        let result = if ctx.args.tex_directory {
-            extract_tex(ctx, file)
-        } else {
-            extract_pkg(ctx, file)
-        };
+        let result = process(ctx, file);
 
         match result {
             Ok(()) => {
@@ -279,12 +1314,33 @@ fn extract_directory(ctx: &ExtractContext, dir: &Path) -> Result<()> {
                 errors.to_string().normal()
             }
         );
+        if !ctx.args.options.dry_run {
+            println!(
+                "Wrote {}, converted {} textures",
+                format_size(ctx.bytes_written.load(Ordering::Relaxed)).cyan(),
+                ctx.textures_converted
+                    .load(Ordering::Relaxed)
+                    .to_string()
+                    .cyan()
+            );
+        }
     }
 
     Ok(())
 }
 
 fn extract_pkg(ctx: &ExtractContext, path: &Path) -> Result<()> {
+    if ctx.args.options.skip_existing && ctx.pkg_already_extracted(path) {
+        if ctx.verbose && !ctx.quiet {
+            println!(
+                "{} Skipping (already extracted): {}",
+                "-".dimmed(),
+                path.display()
+            );
+        }
+        return Ok(());
+    }
+
     if !ctx.quiet && ctx.verbose {
         println!("\n{} Extracting: {}", ">>>".cyan(), path.display());
     }
@@ -307,11 +1363,8 @@ fn extract_pkg(ctx: &ExtractContext, path: &Path) -> Result<()> {
     }
 
     // Filter entries
-    let entries: Vec<_> = package
-        .entries
-        .iter()
-        .filter(|e| should_extract(e.extension(), &ctx.ignore_exts, &ctx.only_exts))
-        .collect();
+    let entries = package
+        .find_entries(|e| should_extract(e, &ctx.ignore_exts, &ctx.only_exts, &ctx.entry_types));
 
     if entries.is_empty() {
         if !ctx.quiet {
@@ -321,20 +1374,34 @@ fn extract_pkg(ctx: &ExtractContext, path: &Path) -> Result<()> {
     }
 
     // Create output directory
-    let output_dir = &ctx.args.output;
-    if !ctx.args.dry_run {
+    let output_dir = ctx.args.output_dir();
+    if !ctx.args.options.dry_run {
         fs::create_dir_all(output_dir)?;
     }
 
-    let tex_reader = TexReader::new();
-    let converter = TexToImageConverter::new();
+    let converter = ctx.converter();
+    let mut outputs = Vec::new();
 
     for entry in entries {
         let bytes = entry
-            .bytes
-            .as_ref()
+            .bytes_slice()
             .ok_or_else(|| anyhow::anyhow!("Entry has no data"))?;
 
+        if entry.entry_type == EntryType::Tex {
+            if let Some((width, height)) = ctx.outside_dimensions(bytes) {
+                if ctx.verbose && !ctx.quiet {
+                    println!(
+                        "  {} Skipping ({}x{}): {}",
+                        "-".dimmed(),
+                        width,
+                        height,
+                        entry.full_path
+                    );
+                }
+                continue;
+            }
+        }
+
         // Determine output path
         let output_path = if ctx.args.single_dir {
             output_dir.join(format!("{}{}", entry.name(), entry.extension()))
@@ -343,18 +1410,27 @@ fn extract_pkg(ctx: &ExtractContext, path: &Path) -> Result<()> {
         };
 
         // Check if exists
-        if !ctx.args.overwrite && output_path.exists() {
+        if !ctx.args.options.overwrite && output_path.exists() {
             if ctx.verbose && !ctx.quiet {
                 println!("  {} Skipping (exists): {}", "-".dimmed(), entry.full_path);
             }
+            outputs.push(output_path);
             continue;
         }
 
-        if ctx.args.dry_run {
+        if ctx.args.options.dry_run {
+            let mut estimate = bytes.len() as u64;
+            if entry.entry_type == EntryType::Tex && !ctx.args.no_convert {
+                if let Ok(tex) = TexReader::headers_only().read_from(&mut Cursor::new(bytes)) {
+                    estimate += ctx.estimate_conversions(&tex, &converter);
+                }
+            }
+            ctx.estimated_bytes.fetch_add(estimate, Ordering::Relaxed);
             println!(
-                "  Would extract: {} -> {}",
+                "  Would extract: {} -> {} (~{})",
                 entry.full_path,
-                output_path.display()
+                output_path.display(),
+                format_size(estimate)
             );
             continue;
         }
@@ -365,64 +1441,134 @@ fn extract_pkg(ctx: &ExtractContext, path: &Path) -> Result<()> {
         }
 
         // Write raw file
-        fs::write(&output_path, bytes)?;
+        ctx.write_output(&output_path, bytes)?;
 
         if ctx.verbose && !ctx.quiet {
             println!("  {} Extracted: {}", "+".green(), entry.full_path);
         }
 
         // Convert TEX if requested
-        if entry.entry_type == EntryType::Tex && !ctx.args.no_convert {
-            let tex_result = tex_reader.read_from(&mut Cursor::new(bytes));
-
-            match tex_result {
-                Ok(tex) => {
-                    let format = if tex.is_gif() || tex.is_video() {
-                        converter.recommended_format(&tex)
-                    } else {
-                        ctx.output_format
-                    };
-
-                    match converter.convert(&tex, format) {
-                        Ok(result) => {
-                            let img_path = output_path.with_extension(result.format.extension());
-                            fs::write(&img_path, &result.bytes)?;
-                            if ctx.verbose && !ctx.quiet {
-                                println!(
-                                    "  {} Converted: {} -> {}",
-                                    "+".green(),
-                                    entry.full_path,
-                                    result.format.extension()
-                                );
-                            }
-                        }
-                        Err(e) => {
-                            if !ctx.quiet {
-                                eprintln!(
-                                    "  {} Failed to convert {}: {}",
-                                    "!".yellow(),
-                                    entry.full_path,
-                                    e
-                                );
-                            }
-                        }
-                    }
+        let converted = if entry.entry_type == EntryType::Tex && !ctx.args.no_convert {
+            convert_pkg_entry(ctx, &converter, &entry.full_path, bytes, &output_path)?
+        } else {
+            Vec::new()
+        };
+        if !converted.is_empty() {
+            ctx.textures_converted.fetch_add(1, Ordering::Relaxed);
+        }
+        outputs.push(output_path.clone());
+        outputs.extend(converted.iter().map(|(path, _)| path.clone()));
+
+        ctx.record(ManifestEntry {
+            package: Some(path.display().to_string()),
+            source: entry.full_path.clone(),
+            output: output_path.display().to_string(),
+            size: entry.length as u64,
+            entry_type: entry.entry_type.as_str(),
+            converted: !converted.is_empty(),
+            conversions: converted
+                .iter()
+                .map(|(path, format)| ManifestConversion::new(path, *format))
+                .collect(),
+        })?;
+    }
+
+    if ctx.args.options.skip_existing && !ctx.args.options.dry_run {
+        ctx.write_pkg_marker(path, &outputs)?;
+    }
+
+    Ok(())
+}
+
+/// Convert an extracted TEX entry next to its raw output.
+///
+/// Conversion failures are reported and skipped. Returns the path and
+/// format of each converted file written.
+fn convert_pkg_entry(
+    ctx: &ExtractContext,
+    converter: &TexToImageConverter,
+    entry_path: &str,
+    bytes: &[u8],
+    output_path: &Path,
+) -> Result<Vec<(PathBuf, OutputFormat)>> {
+    let tex = match ctx.read_tex(bytes) {
+        Ok(tex) => tex,
+        Err(e) => {
+            if !ctx.quiet {
+                eprintln!(
+                    "  {} Failed to read TEX {}: {}",
+                    "!".yellow(),
+                    entry_path,
+                    e
+                );
+            }
+            return Ok(Vec::new());
+        }
+    };
+
+    if let Some(sheet) = ctx.sheet_for(&tex) {
+        return match write_sheet(ctx, converter, &tex, sheet, output_path) {
+            Ok(image_path) => {
+                if ctx.verbose && !ctx.quiet {
+                    println!(
+                        "  {} {}: {} ({})",
+                        "+".green(),
+                        sheet.label(),
+                        entry_path,
+                        sheet.setting()
+                    );
+                }
+                Ok(vec![(image_path, ctx.still_format())])
+            }
+            Err(e) => {
+                if !ctx.quiet {
+                    eprintln!(
+                        "  {} Failed to build {} {}: {}",
+                        "!".yellow(),
+                        sheet.label().to_lowercase(),
+                        entry_path,
+                        e
+                    );
+                }
+                Ok(Vec::new())
+            }
+        };
+    }
+
+    let source = ctx.source(&tex, converter);
+    let mut converted = Vec::new();
+    for (level, format) in ctx.conversions_for(&tex, converter) {
+        let passthrough = level.is_none() && converter.would_passthrough(&tex, format);
+        match convert_level(ctx, converter, &source, level, format, output_path) {
+            Ok(result) => {
+                let img_path = converted_path(output_path, level, result.format);
+                ctx.write_output(&img_path, &result.bytes)?;
+                if ctx.verbose && !ctx.quiet {
+                    println!(
+                        "  {} {}: {} -> {}",
+                        "+".green(),
+                        conversion_verb(passthrough),
+                        entry_path,
+                        img_path.display()
+                    );
                 }
-                Err(e) => {
-                    if !ctx.quiet {
-                        eprintln!(
-                            "  {} Failed to read TEX {}: {}",
-                            "!".yellow(),
-                            entry.full_path,
-                            e
-                        );
-                    }
+                converted.push((img_path, result.format));
+            }
+            Err(e) => {
+                if !ctx.quiet {
+                    eprintln!(
+                        "  {} Failed to convert {} to {}: {}",
+                        "!".yellow(),
+                        entry_path,
+                        format,
+                        e
+                    );
                 }
             }
         }
     }
 
-    Ok(())
+    Ok(converted)
 }
 
 fn extract_tex(ctx: &ExtractContext, path: &Path) -> Result<()> {
@@ -430,32 +1576,96 @@ fn extract_tex(ctx: &ExtractContext, path: &Path) -> Result<()> {
         println!("\n{} Converting: {}", ">>>".cyan(), path.display());
     }
 
+    if ctx.args.options.skip_existing && ctx.tex_output_exists(path) {
+        if ctx.verbose && !ctx.quiet {
+            println!("  {} Skipping (exists): {}", "-".dimmed(), path.display());
+        }
+        return Ok(());
+    }
+
     let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if let Some((width, height)) = ctx.outside_dimensions(&bytes) {
+        if ctx.verbose && !ctx.quiet {
+            println!(
+                "  {} Skipping ({}x{}): {}",
+                "-".dimmed(),
+                width,
+                height,
+                path.display()
+            );
+        }
+        return Ok(());
+    }
+    extract_tex_bytes(ctx, path, &bytes)
+}
 
-    let tex_reader = TexReader::new();
-    let tex = tex_reader
-        .read_from(&mut Cursor::new(&bytes))
+/// Convert TEX bytes read from `path` (named `stdin` for piped input).
+fn extract_tex_bytes(ctx: &ExtractContext, path: &Path, bytes: &[u8]) -> Result<()> {
+    let tex = ctx
+        .read_tex(bytes)
         .with_context(|| format!("Failed to parse TEX: {}", path.display()))?;
 
-    let converter = TexToImageConverter::new();
-    let format = if tex.is_gif() || tex.is_video() {
-        converter.recommended_format(&tex)
-    } else {
-        ctx.output_format
-    };
+    let converter = ctx.converter();
+    let file_name = path.file_name().unwrap_or(OsStr::new("output"));
+    let base_path = ctx.args.output_dir().join(file_name);
+
+    let source = ctx.source(&tex, &converter);
+    let mut conversions = Vec::new();
+    for (level, format) in ctx.conversions_for(&tex, &converter) {
+        let output_path = converted_path(&base_path, level, format);
+        if let Some(written) =
+            convert_tex_file(ctx, &converter, &source, path, level, format, &output_path)?
+        {
+            conversions.push(ManifestConversion::new(&written, format));
+        }
+    }
 
-    // Determine output path
-    let file_stem = path
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("output");
-    let output_path = ctx
-        .args
-        .output
-        .join(format!("{}.{}", file_stem, format.extension()));
+    if let Some(first) = conversions.first() {
+        ctx.textures_converted.fetch_add(1, Ordering::Relaxed);
+        ctx.record(ManifestEntry {
+            package: None,
+            source: path.display().to_string(),
+            output: first.output.clone(),
+            size: bytes.len() as u64,
+            entry_type: EntryType::Tex.as_str(),
+            converted: true,
+            conversions,
+        })?;
+    }
 
+    Ok(())
+}
+
+/// Estimated output size of converting a texture (or one mipmap level of it).
+fn estimate_conversion(
+    converter: &TexToImageConverter,
+    tex: &Tex,
+    level: Option<usize>,
+    format: OutputFormat,
+) -> u64 {
+    let estimate = converter.estimated_output_bytes(tex, format) as u64;
+    // Each mipmap level has a quarter of the previous one's pixels
+    let shift = 2 * level.unwrap_or(0).min(31) as u32;
+    estimate.checked_shr(shift).unwrap_or(0)
+}
+
+/// Convert a standalone texture (or one mipmap level of it) to one format
+/// and write it to `output_path`.
+///
+/// Returns the written path, or `None` if the file was skipped because it
+/// exists or this is a dry run.
+fn convert_tex_file(
+    ctx: &ExtractContext,
+    converter: &TexToImageConverter,
+    source: &SourceTex,
+    path: &Path,
+    level: Option<usize>,
+    format: OutputFormat,
+    output_path: &Path,
+) -> Result<Option<PathBuf>> {
+    let tex = source.tex;
     // Check if exists
-    if !ctx.args.overwrite && output_path.exists() {
+    if !ctx.args.options.overwrite && output_path.exists() {
         if ctx.verbose && !ctx.quiet {
             println!(
                 "  {} Skipping (exists): {}",
@@ -463,16 +1673,19 @@ fn extract_tex(ctx: &ExtractContext, path: &Path) -> Result<()> {
                 output_path.display()
             );
         }
-        return Ok(());
+        return Ok(None);
     }
 
-    if ctx.args.dry_run {
+    if ctx.args.options.dry_run {
+        let estimate = estimate_conversion(converter, tex, level, format);
+        ctx.estimated_bytes.fetch_add(estimate, Ordering::Relaxed);
         println!(
-            "  Would convert: {} -> {}",
+            "  Would convert: {} -> {} (~{})",
             path.display(),
-            output_path.display()
+            output_path.display(),
+            format_size(estimate)
         );
-        return Ok(());
+        return Ok(None);
     }
 
     // Create output directory
@@ -481,23 +1694,81 @@ fn extract_tex(ctx: &ExtractContext, path: &Path) -> Result<()> {
     }
 
     // Convert and write
-    let result = converter.convert(&tex, format)?;
-    fs::write(&output_path, &result.bytes)?;
+    let sheet = ctx.sheet_for(tex);
+    let written_path = if let Some(sheet) = sheet {
+        write_sheet(ctx, converter, tex, sheet, output_path)?
+    } else {
+        let result = convert_level(ctx, converter, source, level, format, output_path)?;
+        ctx.write_output(output_path, &result.bytes)?;
+        output_path.to_path_buf()
+    };
 
     if !ctx.quiet {
+        let passthrough =
+            sheet.is_none() && level.is_none() && converter.would_passthrough(tex, format);
         println!(
-            "  {} Converted: {} -> {}",
+            "  {} {}: {} -> {}",
             "+".green(),
+            conversion_verb(passthrough),
             path.display(),
             output_path.display()
         );
     }
 
-    Ok(())
+    Ok(Some(written_path))
 }
 
-fn should_extract(ext: &str, ignore: &[String], only: &[String]) -> bool {
-    let ext_lower = ext.to_lowercase();
+/// Path of a converted file next to `base`, replacing its extension:
+/// `<name>.<ext>`, or `<name>.mip<N>.<ext>` for a single mipmap level.
+fn converted_path(base: &Path, level: Option<usize>, format: OutputFormat) -> PathBuf {
+    match level {
+        Some(level) => base.with_extension(format!("mip{}.{}", level, format.extension())),
+        None => base.with_extension(format.extension()),
+    }
+}
+
+/// Convert a whole texture, or only mipmap `level` of its first image.
+///
+/// Compressed (DXT) and LZ4 levels are decompressed like level 0.
+fn convert_level(
+    ctx: &ExtractContext,
+    converter: &TexToImageConverter,
+    source: &SourceTex,
+    level: Option<usize>,
+    format: OutputFormat,
+    base_path: &Path,
+) -> Result<ConversionResult> {
+    match level {
+        Some(level) => Ok(converter.convert_mipmap(source.tex, level, format)?),
+        None => convert_texture(ctx, converter, source, format, base_path),
+    }
+}
+
+/// Describe a conversion for verbose output.
+fn conversion_verb(passthrough: bool) -> &'static str {
+    if passthrough {
+        "Copied (no re-encode)"
+    } else {
+        "Converted"
+    }
+}
+
+/// Whether a package entry passes the `--type` and extension filters.
+///
+/// The type filter and the extension filters are combined with AND. Between
+/// the extension filters, `--only-exts` takes precedence and `--ignore-exts`
+/// is only consulted when no `--only-exts` list was given.
+pub(super) fn should_extract(
+    entry: &PackageEntry,
+    ignore: &[String],
+    only: &[String],
+    types: &[EntryType],
+) -> bool {
+    if !types.is_empty() && !types.contains(&entry.entry_type) {
+        return false;
+    }
+
+    let ext_lower = entry.extension().to_lowercase();
 
     if !only.is_empty() {
         return only.iter().any(|e| ext_lower == e.as_str());
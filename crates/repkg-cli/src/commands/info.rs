@@ -3,18 +3,25 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use colored::Colorize;
+use rayon::prelude::*;
 use repkg::{PackageReader, TexReader};
-use repkg_core::{EntryType, Package, Tex};
+use repkg_core::{EntryType, Package, SortKey, Tex};
 use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
 use std::fs::{self, File};
 use std::io::{BufReader, Cursor};
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use super::input::expand_glob;
+use crate::output::format_size;
+
 /// Display information about PKG/TEX files
 #[derive(Args, Debug)]
 pub struct InfoArgs {
-    /// Path to PKG/TEX file or directory
+    /// Path to PKG/TEX file or directory, or a glob pattern such as
+    /// "materials/**/*.tex"
     #[arg(value_name = "INPUT")]
     pub input: PathBuf,
 
@@ -30,21 +37,52 @@ pub struct InfoArgs {
     #[arg(short = 's', long)]
     pub sort: bool,
 
-    /// Sort by field (name, extension, size)
+    /// Sort by field (name, extension, size, type, directory)
     #[arg(long = "sort-by", default_value = "name")]
     pub sort_by: String,
 
     /// Output as JSON
-    #[arg(long)]
+    #[arg(long, group = "json_output")]
     pub json: bool,
 
+    /// Output newline-delimited JSON: one compact object per file and line
+    #[arg(long, group = "json_output")]
+    pub ndjson: bool,
+
+    /// Include CRC-32 and SHA-256 checksums of each package entry in the
+    /// JSON output (reads all entry data)
+    #[arg(long, requires = "json_output")]
+    pub checksums: bool,
+
     /// Recursively search directories
     #[arg(short = 'r', long)]
     pub recursive: bool,
 }
 
+impl InfoArgs {
+    /// Whether output is JSON, pretty-printed or one object per line.
+    fn json_output(&self) -> bool {
+        self.json || self.ndjson
+    }
+
+    /// Append `value` to `out` as a JSON object and line.
+    fn write_json<T: Serialize>(&self, out: &mut String, value: &T) -> Result<()> {
+        let json = if self.ndjson {
+            serde_json::to_string(value)?
+        } else {
+            serde_json::to_string_pretty(value)?
+        };
+        writeln!(out, "{}", json)?;
+        Ok(())
+    }
+}
+
 pub fn run(args: InfoArgs, verbose: bool, quiet: bool) -> Result<()> {
     let input_path = &args.input;
+    if let Some(files) = expand_glob(input_path, quiet)? {
+        return info_files(&args, &files, verbose, quiet);
+    }
+
     let metadata = fs::metadata(input_path)
         .with_context(|| format!("Failed to access input: {}", input_path.display()))?;
 
@@ -60,6 +98,12 @@ pub fn run(args: InfoArgs, verbose: bool, quiet: bool) -> Result<()> {
 }
 
 fn info_file(args: &InfoArgs, path: &Path, verbose: bool, quiet: bool) -> Result<()> {
+    print!("{}", render_file(args, path, verbose, quiet)?);
+    Ok(())
+}
+
+/// Render the info block for a single file, dispatching on its extension.
+fn render_file(args: &InfoArgs, path: &Path, verbose: bool, quiet: bool) -> Result<String> {
     let ext = path
         .extension()
         .and_then(|s| s.to_str())
@@ -67,18 +111,29 @@ fn info_file(args: &InfoArgs, path: &Path, verbose: bool, quiet: bool) -> Result
         .unwrap_or_default();
 
     if args.tex || ext == "tex" {
-        info_tex(args, path, verbose, quiet)
+        render_tex(args, path, verbose, quiet)
     } else if ext == "pkg" {
-        info_pkg(args, path, verbose, quiet)
+        render_pkg(args, path, verbose, quiet)
     } else {
-        if !quiet {
-            println!(
+        let mut out = String::new();
+        if args.json_output() {
+            // Keep stdout parseable
+            if !quiet {
+                eprintln!(
+                    "{} Unrecognized file extension: {}",
+                    "warning:".yellow(),
+                    ext
+                );
+            }
+        } else if !quiet {
+            writeln!(
+                out,
                 "{} Unrecognized file extension: {}",
                 "warning:".yellow(),
                 ext
-            );
+            )?;
         }
-        Ok(())
+        Ok(out)
     }
 }
 
@@ -126,194 +181,253 @@ fn info_directory(args: &InfoArgs, dir: &Path, verbose: bool, quiet: bool) -> Re
         return Ok(());
     }
 
-    for file in files {
-        if args.tex {
-            info_tex(args, &file, verbose, quiet)?;
-        } else {
-            info_pkg(args, &file, verbose, quiet)?;
-        }
+    info_files(args, &files, verbose, quiet)
+}
+
+/// Render info for many files in parallel, printing the blocks in input order.
+///
+/// Output stops at the first file that fails, returning its error.
+fn info_files(args: &InfoArgs, files: &[PathBuf], verbose: bool, quiet: bool) -> Result<()> {
+    let blocks: Vec<Result<String>> = files
+        .par_iter()
+        .map(|file| {
+            if args.tex {
+                render_tex(args, file, verbose, quiet)
+            } else {
+                render_file(args, file, verbose, quiet)
+            }
+        })
+        .collect();
+
+    for block in blocks {
+        print!("{}", block?);
     }
 
     Ok(())
 }
 
-fn info_pkg(args: &InfoArgs, path: &Path, _verbose: bool, quiet: bool) -> Result<()> {
+fn render_pkg(args: &InfoArgs, path: &Path, _verbose: bool, quiet: bool) -> Result<String> {
     let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
     let mut reader = BufReader::new(file);
 
-    let pkg_reader = PackageReader::info_only();
+    let pkg_reader = if args.checksums {
+        PackageReader::new()
+    } else {
+        PackageReader::info_only()
+    };
     let package = pkg_reader
         .read_from(&mut reader)
         .with_context(|| format!("Failed to read PKG: {}", path.display()))?;
 
-    if args.json {
-        let info = PkgInfo::from_package(&package, path, args);
-        println!("{}", serde_json::to_string_pretty(&info)?);
+    let mut out = String::new();
+    if args.json_output() {
+        args.write_json(&mut out, &PkgInfo::from_package(&package, path, args))?;
     } else {
-        print_pkg_info(&package, path, args, quiet);
+        write_pkg_info(&mut out, &package, path, args, quiet)?;
     }
 
-    Ok(())
+    Ok(out)
 }
 
-fn info_tex(args: &InfoArgs, path: &Path, _verbose: bool, quiet: bool) -> Result<()> {
+fn render_tex(args: &InfoArgs, path: &Path, _verbose: bool, quiet: bool) -> Result<String> {
     let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
 
-    let tex_reader = TexReader::without_decompression();
+    let tex_reader = TexReader::without_decompression().with_unsupported_container_fallback(true);
     let tex = tex_reader
         .read_from(&mut Cursor::new(&bytes))
         .with_context(|| format!("Failed to parse TEX: {}", path.display()))?;
 
-    if args.json {
-        let info = TexInfo::from_tex(&tex, path);
-        println!("{}", serde_json::to_string_pretty(&info)?);
+    let mut out = String::new();
+    if args.json_output() {
+        args.write_json(&mut out, &TexInfo::from_tex(&tex, path))?;
     } else {
-        print_tex_info(&tex, path, quiet);
+        write_tex_info(&mut out, &tex, path, quiet)?;
     }
 
-    Ok(())
+    Ok(out)
 }
 
-fn print_pkg_info(pkg: &Package, path: &Path, args: &InfoArgs, quiet: bool) {
+fn write_pkg_info(
+    out: &mut String,
+    pkg: &Package,
+    path: &Path,
+    args: &InfoArgs,
+    quiet: bool,
+) -> Result<()> {
     if quiet {
-        return;
+        return Ok(());
     }
 
-    println!("\n{} {}", "Package:".cyan().bold(), path.display());
-    println!("  Magic: {}", pkg.magic.yellow());
-    println!("  Header size: {} bytes", pkg.header_size);
-    println!("  Entry count: {}", pkg.entry_count());
-    println!(
-        "  Total data size: {} bytes",
+    writeln!(out, "\n{} {}", "Package:".cyan().bold(), path.display())?;
+    if pkg.unrecognized_magic {
+        writeln!(
+            out,
+            "  Magic: {} ({})",
+            pkg.magic.yellow(),
+            "unrecognized, parsed as PKGV".yellow()
+        )?;
+    } else {
+        writeln!(out, "  Magic: {}", pkg.magic.yellow())?;
+    }
+    writeln!(out, "  Version: {}", pkg.version)?;
+    writeln!(out, "  Header size: {} bytes", pkg.header_size)?;
+    writeln!(out, "  Entry count: {}", pkg.entry_count())?;
+    writeln!(
+        out,
+        "  Total data size: {}",
         format_size(pkg.total_data_size())
-    );
+    )?;
 
     // Count entries by type
-    let tex_count = pkg
-        .entries
-        .iter()
-        .filter(|e| e.entry_type == EntryType::Tex)
-        .count();
-    let json_count = pkg
-        .entries
-        .iter()
-        .filter(|e| e.entry_type == EntryType::Json)
-        .count();
-    let shader_count = pkg
-        .entries
-        .iter()
-        .filter(|e| e.entry_type == EntryType::Shader)
-        .count();
-    let other_count = pkg
-        .entries
-        .iter()
-        .filter(|e| e.entry_type == EntryType::Other)
-        .count();
-
-    println!("  Entry types:");
-    if tex_count > 0 {
-        println!("    Textures: {}", tex_count.to_string().green());
-    }
-    if json_count > 0 {
-        println!("    JSON: {}", json_count);
-    }
-    if shader_count > 0 {
-        println!("    Shaders: {}", shader_count);
+    writeln!(out, "  Entry types:")?;
+    for &entry_type in EntryType::all() {
+        let count = pkg.find_entries(|e| e.entry_type == entry_type).len();
+        if count == 0 {
+            continue;
+        }
+        let count = if entry_type == EntryType::Tex {
+            count.to_string().green().to_string()
+        } else {
+            count.to_string()
+        };
+        writeln!(out, "    {}: {}", entry_type_label(entry_type), count)?;
     }
-    if other_count > 0 {
-        println!("    Other: {}", other_count);
+
+    writeln!(out, "  Extensions:")?;
+    for (extension, (count, bytes)) in pkg.extension_histogram() {
+        let extension = if extension.is_empty() {
+            "(none)".to_string()
+        } else {
+            extension
+        };
+        writeln!(
+            out,
+            "    {:<10} {:>6}  {:>10}",
+            extension,
+            count,
+            format_size(bytes)
+        )?;
     }
 
     if args.entries {
-        println!("\n  {}:", "Entries".cyan());
+        writeln!(out, "\n  {}:", "Entries".cyan())?;
 
         let mut entries: Vec<_> = pkg.entries.iter().collect();
 
         if args.sort {
-            match args.sort_by.as_str() {
-                "extension" => entries.sort_by(|a, b| a.extension().cmp(b.extension())),
-                "size" => entries.sort_by(|a, b| a.length.cmp(&b.length)),
-                _ => entries.sort_by(|a, b| a.full_path.cmp(&b.full_path)),
-            }
+            let key = SortKey::parse(&args.sort_by).unwrap_or(SortKey::Name);
+            entries.sort_by(|a, b| key.compare(a, b));
         }
 
         for entry in entries {
-            println!(
-                "    {} ({} bytes)",
+            writeln!(
+                out,
+                "    {} ({})",
                 entry.full_path,
                 format_size(entry.length as u64).dimmed()
-            );
+            )?;
         }
     }
+
+    Ok(())
+}
+
+/// Plural label for an entry type in the type counts.
+fn entry_type_label(entry_type: EntryType) -> &'static str {
+    match entry_type {
+        EntryType::Tex => "Textures",
+        EntryType::Json => "JSON",
+        EntryType::Shader => "Shaders",
+        EntryType::Model => "Models",
+        EntryType::Audio => "Audio",
+        EntryType::Font => "Fonts",
+        EntryType::Other => "Other",
+    }
 }
 
-fn print_tex_info(tex: &Tex, path: &Path, quiet: bool) {
+fn write_tex_info(out: &mut String, tex: &Tex, path: &Path, quiet: bool) -> Result<()> {
     if quiet {
-        return;
+        return Ok(());
     }
 
-    println!("\n{} {}", "Texture:".cyan().bold(), path.display());
-    println!("  Magic: {} / {}", tex.magic1.yellow(), tex.magic2.yellow());
-    println!("  Format: {:?}", tex.header.format);
-    println!("  Flags: {:?}", tex.header.flags);
-    println!(
+    writeln!(out, "\n{} {}", "Texture:".cyan().bold(), path.display())?;
+    writeln!(
+        out,
+        "  Magic: {} / {}",
+        tex.magic1.yellow(),
+        tex.magic2.yellow()
+    )?;
+    writeln!(out, "  Format: {:?}", tex.header.format)?;
+    writeln!(out, "  Flags: {:?}", tex.header.flags)?;
+    writeln!(
+        out,
         "  Texture size: {}x{}",
         tex.header.texture_width, tex.header.texture_height
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "  Image size: {}x{}",
         tex.header.image_width, tex.header.image_height
-    );
-    println!("  Container version: {:?}", tex.images_container.version);
-    println!("  Image format: {:?}", tex.images_container.image_format);
-    println!("  Image count: {}", tex.image_count());
+    )?;
+    let (output_width, output_height) = tex.output_dimensions();
+    writeln!(out, "  Output size: {}x{}", output_width, output_height)?;
+    if let Some(magic) = &tex.unsupported_container {
+        writeln!(
+            out,
+            "  Container version: {} ({})",
+            magic.yellow(),
+            "unsupported, images not read".yellow()
+        )?;
+    } else {
+        let container = &tex.images_container;
+        if container.is_downgraded() {
+            writeln!(
+                out,
+                "  Container version: {} (parsed as {:?})",
+                container.magic.yellow(),
+                container.version
+            )?;
+        } else {
+            writeln!(out, "  Container version: {:?}", container.version)?;
+        }
+        writeln!(out, "  Image format: {:?}", container.image_format)?;
+    }
+    writeln!(out, "  Image count: {}", tex.image_count())?;
 
     if tex.is_gif() {
-        println!("  Type: {} (animated)", "GIF".green());
+        writeln!(out, "  Type: {} (animated)", "GIF".green())?;
         if let Some(frame_info) = &tex.frame_info_container {
-            println!(
+            writeln!(
+                out,
                 "  GIF dimensions: {}x{}",
                 frame_info.gif_width, frame_info.gif_height
-            );
-            println!("  Frame count: {}", frame_info.frame_count());
-            println!("  Total duration: {:.2}s", frame_info.total_duration());
+            )?;
+            writeln!(out, "  Frame count: {}", frame_info.frame_count())?;
+            writeln!(out, "  Total duration: {:.2}s", frame_info.total_duration())?;
         }
     } else if tex.is_video() {
-        println!("  Type: {} (video)", "MP4".blue());
+        writeln!(out, "  Type: {} (video)", "MP4".blue())?;
     } else {
-        println!("  Type: Static");
+        writeln!(out, "  Type: Static")?;
     }
 
     // Show mipmap info
     if let Some(first_image) = tex.first_image() {
-        println!("  Mipmaps: {}", first_image.mipmap_count());
+        writeln!(out, "  Mipmaps: {}", first_image.mipmap_count())?;
         for (i, mipmap) in first_image.mipmaps.iter().enumerate() {
-            println!(
-                "    [{}] {}x{}, {:?}, {} bytes",
+            writeln!(
+                out,
+                "    [{}] {}x{}, {:?}, {}",
                 i,
                 mipmap.width,
                 mipmap.height,
                 mipmap.format,
                 format_size(mipmap.byte_count() as u64)
-            );
+            )?;
         }
     }
-}
 
-fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} B", bytes)
-    }
+    Ok(())
 }
 
 // JSON output structures
@@ -322,24 +436,38 @@ fn format_size(bytes: u64) -> String {
 struct PkgInfo {
     path: String,
     magic: String,
+    version: u32,
+    unrecognized_magic: bool,
     header_size: u32,
     entry_count: usize,
     total_data_size: u64,
+    /// Entry count and total size per lowercased extension
+    extensions: BTreeMap<String, ExtensionInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     entries: Option<Vec<PkgEntryInfo>>,
 }
 
+#[derive(Serialize)]
+struct ExtensionInfo {
+    count: usize,
+    total_bytes: u64,
+}
+
 #[derive(Serialize)]
 struct PkgEntryInfo {
     path: String,
     offset: u32,
     length: u32,
     entry_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    crc32: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sha256: Option<String>,
 }
 
 impl PkgInfo {
     fn from_package(pkg: &Package, path: &Path, args: &InfoArgs) -> Self {
-        let entries = if args.entries {
+        let entries = if args.entries || args.checksums {
             Some(
                 pkg.entries
                     .iter()
@@ -348,6 +476,8 @@ impl PkgInfo {
                         offset: e.offset,
                         length: e.length,
                         entry_type: e.entry_type.as_str().to_string(),
+                        crc32: e.crc32().map(|crc| format!("{:08x}", crc)),
+                        sha256: e.sha256(),
                     })
                     .collect(),
             )
@@ -358,9 +488,18 @@ impl PkgInfo {
         Self {
             path: path.display().to_string(),
             magic: pkg.magic.clone(),
+            version: pkg.version,
+            unrecognized_magic: pkg.unrecognized_magic,
             header_size: pkg.header_size,
             entry_count: pkg.entry_count(),
             total_data_size: pkg.total_data_size(),
+            extensions: pkg
+                .extension_histogram()
+                .into_iter()
+                .map(|(extension, (count, total_bytes))| {
+                    (extension, ExtensionInfo { count, total_bytes })
+                })
+                .collect(),
             entries,
         }
     }
@@ -377,11 +516,16 @@ struct TexInfo {
     texture_height: u32,
     image_width: u32,
     image_height: u32,
+    output_width: u32,
+    output_height: u32,
     is_gif: bool,
     is_video: bool,
     image_count: usize,
+    container_magic: String,
     container_version: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    unsupported_container: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     frame_count: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     total_duration: Option<f32>,
@@ -394,6 +538,7 @@ impl TexInfo {
         } else {
             (None, None)
         };
+        let (output_width, output_height) = tex.output_dimensions();
 
         Self {
             path: path.display().to_string(),
@@ -405,10 +550,14 @@ impl TexInfo {
             texture_height: tex.header.texture_height,
             image_width: tex.header.image_width,
             image_height: tex.header.image_height,
+            output_width,
+            output_height,
             is_gif: tex.is_gif(),
             is_video: tex.is_video(),
             image_count: tex.image_count(),
+            container_magic: tex.images_container.magic.clone(),
             container_version: format!("{:?}", tex.images_container.version),
+            unsupported_container: tex.unsupported_container.clone(),
             frame_count,
             total_duration,
         }
@@ -0,0 +1,34 @@
+//! Input path handling shared by commands.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+/// Expand `input` as a glob pattern if it is one.
+///
+/// Returns `None` when `input` exists or has no glob metacharacters, so it
+/// should be handled as a plain file or directory. Otherwise returns the
+/// matching files in sorted order, warning if there are none.
+pub fn expand_glob(input: &Path, quiet: bool) -> Result<Option<Vec<PathBuf>>> {
+    let pattern = input.to_string_lossy();
+    if input.exists() || !pattern.contains(['*', '?', '[']) {
+        return Ok(None);
+    }
+
+    let mut files: Vec<PathBuf> = glob::glob(&pattern)
+        .with_context(|| format!("Invalid glob pattern: {}", pattern))?
+        .filter_map(|entry| entry.ok())
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+
+    if files.is_empty() && !quiet {
+        println!(
+            "{} Pattern {} matched no files",
+            "warning:".yellow(),
+            pattern
+        );
+    }
+
+    Ok(Some(files))
+}
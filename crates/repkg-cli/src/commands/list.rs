@@ -0,0 +1,50 @@
+//! List command implementation.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use repkg::PackageReader;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use super::extract::{normalize_extensions, should_extract};
+
+/// Print the entry paths of a PKG file, one per line
+#[derive(Args, Debug)]
+pub struct ListArgs {
+    /// Path to the PKG file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+
+    /// Only list entries with these extensions (comma-separated)
+    #[arg(short = 'e', long = "only-exts")]
+    pub only_exts: Option<String>,
+}
+
+pub fn run(args: ListArgs) -> Result<()> {
+    let file = File::open(&args.input)
+        .with_context(|| format!("Failed to open {}", args.input.display()))?;
+    let package = PackageReader::info_only()
+        .read_from(&mut BufReader::new(file))
+        .with_context(|| format!("Failed to read PKG: {}", args.input.display()))?;
+
+    let only_exts: Vec<String> = args
+        .only_exts
+        .as_deref()
+        .map(normalize_extensions)
+        .unwrap_or_default();
+
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    let written = package
+        .entries
+        .iter()
+        .filter(|e| should_extract(e, &[], &only_exts, &[]))
+        .try_for_each(|e| writeln!(out, "{}", e.full_path))
+        .and_then(|()| out.flush());
+
+    // A closed pipe (e.g. `| head`) is not an error
+    match written {
+        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(e.into()),
+        _ => Ok(()),
+    }
+}
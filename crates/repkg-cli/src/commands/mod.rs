@@ -1,7 +1,12 @@
 //! CLI commands implementation.
 
+pub mod convert;
 pub mod extract;
 pub mod info;
+mod input;
+pub mod list;
 
+pub use convert::ConvertArgs;
 pub use extract::ExtractArgs;
 pub use info::InfoArgs;
+pub use list::ListArgs;
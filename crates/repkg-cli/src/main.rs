@@ -26,17 +26,23 @@ pub struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Extract PKG files or convert TEX files to images
-    Extract(commands::ExtractArgs),
+    Extract(Box<commands::ExtractArgs>),
+    /// Convert TEX files to images
+    Convert(Box<commands::ConvertArgs>),
     /// Display information about PKG/TEX files
     Info(commands::InfoArgs),
+    /// Print the entry paths of a PKG file, one per line
+    List(commands::ListArgs),
 }
 
 fn main() {
     let cli = Cli::parse();
 
     let result = match cli.command {
-        Commands::Extract(args) => commands::extract::run(args, cli.verbose, cli.quiet),
+        Commands::Extract(args) => commands::extract::run(*args, cli.verbose, cli.quiet),
+        Commands::Convert(args) => commands::convert::run(*args, cli.verbose, cli.quiet),
         Commands::Info(args) => commands::info::run(args, cli.verbose, cli.quiet),
+        Commands::List(args) => commands::list::run(args),
     };
 
     if let Err(err) = result {
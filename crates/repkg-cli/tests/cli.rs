@@ -0,0 +1,274 @@
+//! Command-level tests for the repkg-rs binary.
+//!
+//! Packages are built in a temporary directory with `PackageWriter`, and the
+//! files each command writes are checked on disk.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use repkg::PackageWriter;
+use repkg_core::PackageEntry;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Build an uncompressed RGBA8888 TEX of the given size.
+fn rgba_tex(width: u32, height: u32) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"TEXV0005\0TEXI0001\0");
+    for value in [0u32, 0, width, height, width, height, 0] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(b"TEXB0003\0");
+    for value in [1i32, -1] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    let pixels = vec![0x7Fu8; (width * height * 4) as usize];
+    for value in [1u32, width, height, 0, 0, pixels.len() as u32] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(&pixels);
+    data
+}
+
+/// Write `scene.pkg` into `dir` holding the given entries.
+fn write_pkg(dir: &Path, entries: &[(&str, Vec<u8>)]) {
+    let entries: Vec<PackageEntry> = entries
+        .iter()
+        .map(|(path, bytes)| {
+            let mut entry = PackageEntry::new(path.to_string(), 0, 0);
+            entry.bytes = Some(bytes.clone());
+            entry
+        })
+        .collect();
+    let mut file = fs::File::create(dir.join("scene.pkg")).unwrap();
+    PackageWriter::new("PKGV0019")
+        .write_to(&mut file, &entries)
+        .unwrap();
+}
+
+/// A temporary directory holding a package with a texture and a JSON file.
+fn scene() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    write_pkg(
+        dir.path(),
+        &[
+            ("materials/a.tex", rgba_tex(4, 2)),
+            ("scene.json", b"{}".to_vec()),
+        ],
+    );
+    dir
+}
+
+/// Run the binary from `dir`.
+fn repkg(dir: &Path) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_repkg-rs"));
+    cmd.current_dir(dir).env("NO_COLOR", "1");
+    cmd
+}
+
+#[test]
+fn test_extract_writes_entries_and_conversions() {
+    let dir = scene();
+    repkg(dir.path())
+        .args(["extract", "scene.pkg", "-o", "out"])
+        .assert()
+        .success();
+
+    let out = dir.path().join("out");
+    assert_eq!(fs::read(out.join("scene.json")).unwrap(), b"{}");
+    assert_eq!(
+        fs::read(out.join("materials/a.tex")).unwrap(),
+        rgba_tex(4, 2)
+    );
+    assert!(fs::read(out.join("materials/a.png"))
+        .unwrap()
+        .starts_with(PNG_MAGIC));
+}
+
+#[test]
+fn test_extract_no_convert() {
+    let dir = scene();
+    repkg(dir.path())
+        .args(["extract", "scene.pkg", "-o", "out", "--no-convert"])
+        .assert()
+        .success();
+
+    let out = dir.path().join("out");
+    assert!(out.join("materials/a.tex").exists());
+    assert!(!out.join("materials/a.png").exists());
+}
+
+#[test]
+fn test_extract_type_and_extension_filters() {
+    let dir = scene();
+    repkg(dir.path())
+        .args(["extract", "scene.pkg", "-o", "by-type", "--type", "json"])
+        .assert()
+        .success();
+    assert!(dir.path().join("by-type/scene.json").exists());
+    assert!(!dir.path().join("by-type/materials").exists());
+
+    repkg(dir.path())
+        .args(["extract", "scene.pkg", "-o", "textures", "--textures-only"])
+        .assert()
+        .success();
+    assert!(dir.path().join("textures/materials/a.tex").exists());
+    assert!(!dir.path().join("textures/scene.json").exists());
+
+    repkg(dir.path())
+        .args(["extract", "scene.pkg", "-o", "ignored", "-i", "tex"])
+        .assert()
+        .success();
+    assert!(dir.path().join("ignored/scene.json").exists());
+    assert!(!dir.path().join("ignored/materials").exists());
+}
+
+#[test]
+fn test_extract_dimension_filters() {
+    let dir = tempfile::tempdir().unwrap();
+    write_pkg(
+        dir.path(),
+        &[("small.tex", rgba_tex(2, 2)), ("large.tex", rgba_tex(8, 4))],
+    );
+    repkg(dir.path())
+        .args(["extract", "scene.pkg", "-o", "out", "--min-width", "4"])
+        .assert()
+        .success();
+
+    let out = dir.path().join("out");
+    assert!(out.join("large.png").exists());
+    assert!(!out.join("small.tex").exists());
+    assert!(!out.join("small.png").exists());
+}
+
+#[test]
+fn test_extract_manifest_and_checksums() {
+    let dir = scene();
+    repkg(dir.path())
+        .args([
+            "extract",
+            "scene.pkg",
+            "-o",
+            "out",
+            "--manifest",
+            "manifest.json",
+            "--checksums",
+            "sums.txt",
+        ])
+        .assert()
+        .success();
+
+    let manifest: serde_json::Value =
+        serde_json::from_slice(&fs::read(dir.path().join("manifest.json")).unwrap()).unwrap();
+    let entries = manifest.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["source"], "materials/a.tex");
+    assert_eq!(entries[0]["package"], "scene.pkg");
+    assert_eq!(entries[0]["converted"], true);
+    assert_eq!(entries[0]["conversions"][0]["format"], "png");
+    assert_eq!(
+        entries[0]["conversions"][0]["output"],
+        "out/materials/a.png"
+    );
+    assert_eq!(entries[1]["source"], "scene.json");
+    assert_eq!(entries[1]["size"], 2);
+    assert_eq!(entries[1]["converted"], false);
+
+    // Every written file is listed once with its SHA-256, sorted by path
+    let sums = fs::read_to_string(dir.path().join("sums.txt")).unwrap();
+    let listed: Vec<(&str, &str)> = sums
+        .lines()
+        .map(|line| line.split_once("  ").unwrap())
+        .collect();
+    let paths: Vec<&str> = listed.iter().map(|(_, path)| *path).collect();
+    assert_eq!(
+        paths,
+        [
+            "out/materials/a.png",
+            "out/materials/a.tex",
+            "out/scene.json"
+        ]
+    );
+    for (hash, path) in listed {
+        let digest = Sha256::digest(fs::read(dir.path().join(path)).unwrap());
+        let expected: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(hash, expected, "{}", path);
+    }
+}
+
+#[test]
+fn test_extract_skip_existing() {
+    let dir = scene();
+    let extract = |dir: &Path| {
+        repkg(dir)
+            .args(["extract", "scene.pkg", "-o", "out", "--skip-existing"])
+            .assert()
+            .success();
+    };
+    extract(dir.path());
+
+    // All recorded outputs exist, so the package isn't extracted again
+    let json = dir.path().join("out/scene.json");
+    fs::write(&json, "edited").unwrap();
+    extract(dir.path());
+    assert_eq!(fs::read_to_string(&json).unwrap(), "edited");
+
+    // A missing output makes the package count as not extracted
+    fs::remove_file(&json).unwrap();
+    extract(dir.path());
+    assert_eq!(fs::read(&json).unwrap(), b"{}");
+}
+
+#[test]
+fn test_convert_glob_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let textures = dir.path().join("textures");
+    fs::create_dir(&textures).unwrap();
+    fs::write(textures.join("a.tex"), rgba_tex(2, 2)).unwrap();
+    fs::write(textures.join("b.tex"), rgba_tex(4, 4)).unwrap();
+    fs::write(textures.join("notes.txt"), "not a texture").unwrap();
+
+    repkg(dir.path())
+        .args(["convert", "textures/*.tex", "-o", "out"])
+        .assert()
+        .success();
+
+    let out = dir.path().join("out");
+    assert!(fs::read(out.join("a.png")).unwrap().starts_with(PNG_MAGIC));
+    assert!(fs::read(out.join("b.png")).unwrap().starts_with(PNG_MAGIC));
+    assert!(!out.join("notes.png").exists());
+}
+
+#[test]
+fn test_convert_to_stdout() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("a.tex"), rgba_tex(2, 2)).unwrap();
+
+    repkg(dir.path())
+        .args(["convert", "a.tex", "--stdout"])
+        .assert()
+        .success()
+        .stdout(predicate::function(|out: &[u8]| out.starts_with(PNG_MAGIC)));
+
+    repkg(dir.path())
+        .args(["convert", "a.tex", "--stdout", "--filmstrip"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--filmstrip cannot be written to stdout",
+        ));
+}
+
+#[test]
+fn test_info_entry_sizes() {
+    let dir = scene();
+    repkg(dir.path())
+        .args(["info", "scene.pkg", "--entries"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("scene.json (2 B)"))
+        .stdout(predicate::str::contains("bytes)").not());
+}
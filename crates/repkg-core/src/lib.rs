@@ -6,8 +6,10 @@
 pub mod package;
 pub mod texture;
 
-pub use package::{EntryType, Package, PackageEntry};
+pub use package::{
+    EntryChecksum, EntryType, Package, PackageEntry, PackageMagic, SortKey, KNOWN_MAGIC_PREFIXES,
+};
 pub use texture::{
     FreeImageFormat, MipmapFormat, Tex, TexFlags, TexFormat, TexFrameInfo, TexFrameInfoContainer,
-    TexHeader, TexImage, TexImageContainer, TexImageContainerVersion, TexMipmap,
+    TexHeader, TexImage, TexImageContainer, TexImageContainerVersion, TexMipmap, V4MipmapParams,
 };
@@ -1,12 +1,55 @@
 //! Package types for Wallpaper Engine PKG files.
 
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::path::Path;
 
+/// Magic prefixes of the package formats known to share the PKGV layout.
+pub const KNOWN_MAGIC_PREFIXES: &[&str] = &["PKGV"];
+
+/// A parsed package magic string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackageMagic {
+    /// Version number following the prefix (e.g., 19 for "PKGV0019")
+    pub version: u32,
+    /// Whether the prefix is one of [`KNOWN_MAGIC_PREFIXES`]
+    pub recognized: bool,
+}
+
+impl PackageMagic {
+    /// Parse a magic of four uppercase ASCII letters followed by a decimal
+    /// version number, e.g. "PKGV0019".
+    ///
+    /// Returns `None` if the magic doesn't have that shape. Unknown prefixes
+    /// still parse, with `recognized` set to `false`.
+    pub fn parse(magic: &str) -> Option<Self> {
+        if magic.len() <= 4 || !magic.is_char_boundary(4) {
+            return None;
+        }
+        let (prefix, digits) = magic.split_at(4);
+        if !prefix.bytes().all(|b| b.is_ascii_uppercase())
+            || !digits.bytes().all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+        Some(Self {
+            version: digits.parse().ok()?,
+            recognized: KNOWN_MAGIC_PREFIXES.contains(&prefix),
+        })
+    }
+}
+
 /// A Wallpaper Engine PKG package containing multiple files.
 #[derive(Debug, Clone)]
 pub struct Package {
     /// Magic string identifying the package format (e.g., "PKGV0019")
     pub magic: String,
+    /// Format version parsed from the magic (e.g., 19 for "PKGV0019")
+    pub version: u32,
+    /// Whether the magic has a prefix other than the known ones in
+    /// [`KNOWN_MAGIC_PREFIXES`]. Such packages are parsed with the known
+    /// layout, which may not match.
+    pub unrecognized_magic: bool,
     /// Size of the header in bytes
     pub header_size: u32,
     /// List of entries in the package
@@ -15,8 +58,14 @@ pub struct Package {
 
 impl Package {
     /// Create a new empty package with the given magic string.
+    ///
+    /// The version is parsed from the magic; a magic that doesn't parse gets
+    /// version 0 and is flagged as unrecognized.
     pub fn new(magic: String) -> Self {
+        let parsed = PackageMagic::parse(&magic);
         Self {
+            version: parsed.map_or(0, |m| m.version),
+            unrecognized_magic: !parsed.is_some_and(|m| m.recognized),
             magic,
             header_size: 0,
             entries: Vec::new(),
@@ -32,6 +81,169 @@ impl Package {
     pub fn total_data_size(&self) -> u64 {
         self.entries.iter().map(|e| e.length as u64).sum()
     }
+
+    /// Count entries and sum their sizes per extension.
+    ///
+    /// Keys are lowercased extensions including the dot (as returned by
+    /// [`PackageEntry::extension`]), with `""` for entries without one.
+    /// Values are `(entry count, total bytes)`.
+    pub fn extension_histogram(&self) -> BTreeMap<String, (usize, u64)> {
+        let mut histogram = BTreeMap::new();
+        for entry in &self.entries {
+            let (count, bytes) = histogram
+                .entry(entry.extension().to_lowercase())
+                .or_insert((0, 0));
+            *count += 1;
+            *bytes += entry.length as u64;
+        }
+        histogram
+    }
+
+    /// Get the entries matching `predicate`, in package order.
+    pub fn find_entries<P>(&self, mut predicate: P) -> Vec<&PackageEntry>
+    where
+        P: FnMut(&PackageEntry) -> bool,
+    {
+        self.entries.iter().filter(|e| predicate(e)).collect()
+    }
+
+    /// Get the entries whose path matches a glob pattern, in package order.
+    ///
+    /// `*` matches any characters within a path segment, `**` matches across
+    /// segments (`**/` also matches no directory at all) and `?` matches a
+    /// single character. Matching is case-sensitive, e.g.
+    /// `materials/**/*.tex` finds every texture under `materials/`.
+    pub fn glob(&self, pattern: &str) -> Vec<&PackageEntry> {
+        let pattern: Vec<char> = pattern.chars().collect();
+        self.find_entries(|e| {
+            let path: Vec<char> = e.full_path.chars().collect();
+            glob_match(&pattern, &path)
+        })
+    }
+
+    /// Borrow the loaded bytes of the entry at `path`.
+    ///
+    /// Returns `None` if no entry has that path or its bytes weren't loaded.
+    pub fn entry_bytes(&self, path: &str) -> Option<&[u8]> {
+        self.entries
+            .iter()
+            .find(|e| e.full_path == path)
+            .and_then(PackageEntry::bytes_slice)
+    }
+
+    /// Compute checksums of every entry's data.
+    ///
+    /// PKG files don't store checksums, so these are for callers to persist
+    /// and compare across extractions. Entries whose bytes weren't loaded
+    /// have no checksums.
+    pub fn verify(&self) -> Vec<EntryChecksum> {
+        self.entries.iter().map(EntryChecksum::from_entry).collect()
+    }
+
+    /// Sort the entries in place by the given key.
+    ///
+    /// The sort is stable, so entries that compare equal keep their relative
+    /// order. Entry offsets are not updated; writers recompute them from the
+    /// new order.
+    pub fn sort_entries(&mut self, by: SortKey) {
+        self.entries.sort_by(|a, b| by.compare(a, b));
+    }
+}
+
+/// Match a path against a glob pattern (see [`Package::glob`]).
+fn glob_match(pattern: &[char], path: &[char]) -> bool {
+    let mut memo = vec![None; (pattern.len() + 1) * (path.len() + 1)];
+    GlobMatcher {
+        width: path.len() + 1,
+        memo: &mut memo,
+    }
+    .matches(pattern, path)
+}
+
+/// Backtracking glob matcher, memoized on the remaining pattern and path so
+/// patterns with many stars stay polynomial.
+struct GlobMatcher<'a> {
+    /// Row length of `memo`: one more than the full path length
+    width: usize,
+    /// Results indexed by the remaining pattern and path lengths
+    memo: &'a mut [Option<bool>],
+}
+
+impl GlobMatcher<'_> {
+    fn matches(&mut self, pattern: &[char], path: &[char]) -> bool {
+        let key = pattern.len() * self.width + path.len();
+        if let Some(matched) = self.memo[key] {
+            return matched;
+        }
+
+        let matched = match pattern {
+            [] => path.is_empty(),
+            ['*', '*', rest @ ..] => {
+                // "**/" may also match no directory at all
+                let skips_directory = match rest {
+                    ['/', after @ ..] => self.matches(after, path),
+                    _ => false,
+                };
+                skips_directory || (0..=path.len()).any(|i| self.matches(rest, &path[i..]))
+            }
+            ['*', rest @ ..] => {
+                // A single star stops at the end of the segment
+                let segment_len = path.iter().position(|&c| c == '/').unwrap_or(path.len());
+                (0..=segment_len).any(|i| self.matches(rest, &path[i..]))
+            }
+            ['?', rest @ ..] => match path {
+                [c, remaining @ ..] if *c != '/' => self.matches(rest, remaining),
+                _ => false,
+            },
+            [p, rest @ ..] => match path {
+                [c, remaining @ ..] if c == p => self.matches(rest, remaining),
+                _ => false,
+            },
+        };
+
+        self.memo[key] = Some(matched);
+        matched
+    }
+}
+
+/// Key used to order package entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortKey {
+    /// Full path within the package
+    Name,
+    /// File extension
+    Extension,
+    /// Entry data length
+    Size,
+    /// Entry type
+    Type,
+    /// Directory path, grouping entries from the same directory
+    Directory,
+}
+
+impl SortKey {
+    /// Parse from a string, returning None for unknown keys.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "name" | "path" => Some(SortKey::Name),
+            "extension" | "ext" => Some(SortKey::Extension),
+            "size" => Some(SortKey::Size),
+            "type" => Some(SortKey::Type),
+            "directory" | "dir" => Some(SortKey::Directory),
+            _ => None,
+        }
+    }
+
+    /// Compare two entries by this key.
+    pub fn compare(&self, a: &PackageEntry, b: &PackageEntry) -> Ordering {
+        match self {
+            SortKey::Name => a.full_path.cmp(&b.full_path),
+            SortKey::Extension => a.extension().cmp(b.extension()),
+            SortKey::Size => a.length.cmp(&b.length),
+            SortKey::Type => a.entry_type.cmp(&b.entry_type),
+            SortKey::Directory => a.directory_path().cmp(b.directory_path()),
+        }
+    }
 }
 
 /// An entry (file) within a PKG package.
@@ -91,17 +303,74 @@ impl PackageEntry {
     pub fn has_bytes(&self) -> bool {
         self.bytes.is_some()
     }
+
+    /// Borrow the loaded bytes without copying them.
+    pub fn bytes_slice(&self) -> Option<&[u8]> {
+        self.bytes.as_deref()
+    }
+
+    /// Move the loaded bytes out of the entry, leaving it without bytes.
+    pub fn take_bytes(&mut self) -> Option<Vec<u8>> {
+        self.bytes.take()
+    }
+
+    /// CRC-32 of the entry data, if its bytes are loaded.
+    pub fn crc32(&self) -> Option<u32> {
+        self.bytes.as_deref().map(crc32fast::hash)
+    }
+
+    /// Lowercase hex SHA-256 of the entry data, if its bytes are loaded.
+    #[cfg(feature = "sha256")]
+    pub fn sha256(&self) -> Option<String> {
+        use sha2::{Digest, Sha256};
+        self.bytes
+            .as_deref()
+            .map(|bytes| format!("{:x}", Sha256::digest(bytes)))
+    }
+}
+
+/// Checksums of a package entry's data, as returned by [`Package::verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryChecksum {
+    /// Full path of the entry within the package
+    pub full_path: String,
+    /// Length of the entry data in bytes
+    pub length: u32,
+    /// CRC-32 of the entry data (None if bytes weren't loaded)
+    pub crc32: Option<u32>,
+    /// Lowercase hex SHA-256 of the entry data (None if bytes weren't loaded)
+    #[cfg(feature = "sha256")]
+    pub sha256: Option<String>,
+}
+
+impl EntryChecksum {
+    /// Compute the checksums of an entry.
+    pub fn from_entry(entry: &PackageEntry) -> Self {
+        Self {
+            full_path: entry.full_path.clone(),
+            length: entry.length,
+            crc32: entry.crc32(),
+            #[cfg(feature = "sha256")]
+            sha256: entry.sha256(),
+        }
+    }
 }
 
 /// Type of package entry determined by file extension.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum EntryType {
     /// TEX texture file
     Tex,
     /// JSON configuration file
     Json,
-    /// Shader file (vertex or fragment)
+    /// Shader file (GLSL/HLSL stages and shader headers)
     Shader,
+    /// 3D model file
+    Model,
+    /// Audio file
+    Audio,
+    /// Font file
+    Font,
     /// Other/unknown file type
     Other,
 }
@@ -109,24 +378,45 @@ pub enum EntryType {
 impl EntryType {
     /// Determine entry type from a file path.
     pub fn from_path(path: &str) -> Self {
-        let lower = path.to_lowercase();
-        if lower.ends_with(".tex") {
-            EntryType::Tex
-        } else if lower.ends_with(".json") {
-            EntryType::Json
-        } else if lower.ends_with(".vert") || lower.ends_with(".frag") {
-            EntryType::Shader
-        } else {
-            EntryType::Other
+        let extension = path
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "tex" => EntryType::Tex,
+            "json" => EntryType::Json,
+            "vert" | "frag" | "geom" | "comp" | "tesc" | "tese" | "glsl" | "hlsl" | "h" => {
+                EntryType::Shader
+            }
+            "mdl" => EntryType::Model,
+            "mp3" | "ogg" | "wav" => EntryType::Audio,
+            "ttf" | "otf" => EntryType::Font,
+            _ => EntryType::Other,
         }
     }
 
+    /// All entry types, in sort order.
+    pub fn all() -> &'static [EntryType] {
+        &[
+            EntryType::Tex,
+            EntryType::Json,
+            EntryType::Shader,
+            EntryType::Model,
+            EntryType::Audio,
+            EntryType::Font,
+            EntryType::Other,
+        ]
+    }
+
     /// Get a human-readable name for the entry type.
     pub fn as_str(&self) -> &'static str {
         match self {
             EntryType::Tex => "texture",
             EntryType::Json => "json",
             EntryType::Shader => "shader",
+            EntryType::Model => "model",
+            EntryType::Audio => "audio",
+            EntryType::Font => "font",
             EntryType::Other => "other",
         }
     }
@@ -150,6 +440,35 @@ mod tests {
         );
         assert_eq!(EntryType::from_path("readme.txt"), EntryType::Other);
         assert_eq!(EntryType::from_path("MATERIALS/TEST.TEX"), EntryType::Tex);
+        assert_eq!(EntryType::from_path("no_extension"), EntryType::Other);
+        assert_eq!(EntryType::from_path("dir.v/file"), EntryType::Other);
+    }
+
+    #[test]
+    fn test_entry_type_extended_extensions() {
+        for path in [
+            "shaders/a.glsl",
+            "shaders/a.hlsl",
+            "shaders/a.geom",
+            "shaders/a.comp",
+            "shaders/a.tesc",
+            "shaders/a.tese",
+            "shaders/common.h",
+            "shaders/A.GLSL",
+        ] {
+            assert_eq!(EntryType::from_path(path), EntryType::Shader, "{}", path);
+        }
+        assert_eq!(EntryType::from_path("models/tree.mdl"), EntryType::Model);
+        assert_eq!(EntryType::from_path("sounds/loop.mp3"), EntryType::Audio);
+        assert_eq!(EntryType::from_path("sounds/loop.ogg"), EntryType::Audio);
+        assert_eq!(EntryType::from_path("sounds/click.wav"), EntryType::Audio);
+        assert_eq!(EntryType::from_path("fonts/clock.ttf"), EntryType::Font);
+        assert_eq!(EntryType::from_path("fonts/clock.otf"), EntryType::Font);
+
+        assert_eq!(EntryType::Model.as_str(), "model");
+        assert_eq!(EntryType::Audio.as_str(), "audio");
+        assert_eq!(EntryType::Font.as_str(), "font");
+        assert_eq!(EntryType::all().len(), 7);
     }
 
     #[test]
@@ -160,6 +479,194 @@ mod tests {
         assert_eq!(entry.directory_path(), "materials");
     }
 
+    #[test]
+    fn test_sort_entries() {
+        let mut package = Package::new("PKGV0019".to_string());
+        for (path, length) in [
+            ("shaders/b.frag", 30),
+            ("materials/b.tex", 10),
+            ("scene.json", 20),
+            ("materials/a.tex", 10),
+        ] {
+            package
+                .entries
+                .push(PackageEntry::new(path.to_string(), 0, length));
+        }
+
+        package.sort_entries(SortKey::Size);
+        let paths: Vec<_> = package
+            .entries
+            .iter()
+            .map(|e| e.full_path.as_str())
+            .collect();
+        // Stable: equal sizes keep their original order
+        assert_eq!(
+            paths,
+            [
+                "materials/b.tex",
+                "materials/a.tex",
+                "scene.json",
+                "shaders/b.frag"
+            ]
+        );
+
+        package.sort_entries(SortKey::Directory);
+        assert_eq!(package.entries[0].full_path, "scene.json");
+        assert_eq!(package.entries[1].directory_path(), "materials");
+        assert_eq!(package.entries[3].full_path, "shaders/b.frag");
+
+        package.sort_entries(SortKey::Type);
+        assert_eq!(package.entries[0].entry_type, EntryType::Tex);
+        assert_eq!(package.entries[3].entry_type, EntryType::Shader);
+
+        package.sort_entries(SortKey::Name);
+        assert_eq!(package.entries[0].full_path, "materials/a.tex");
+    }
+
+    #[test]
+    fn test_glob_entries() {
+        let mut package = Package::new("PKGV0019".to_string());
+        for path in [
+            "scene.json",
+            "materials/Reze poster.tex",
+            "materials/masks/waterwaves_mask_b95b17e8.tex",
+            "materials/masks/waterwaves.json",
+            "shaders/effects/waterwaves.vert",
+            "effects/materials/other.tex",
+        ] {
+            package
+                .entries
+                .push(PackageEntry::new(path.to_string(), 0, 1));
+        }
+        let paths = |entries: Vec<&PackageEntry>| -> Vec<String> {
+            entries.iter().map(|e| e.full_path.clone()).collect()
+        };
+
+        assert_eq!(
+            paths(package.glob("materials/**/*.tex")),
+            [
+                "materials/Reze poster.tex",
+                "materials/masks/waterwaves_mask_b95b17e8.tex"
+            ]
+        );
+        assert_eq!(
+            paths(package.glob("materials/*.tex")),
+            ["materials/Reze poster.tex"]
+        );
+        assert_eq!(package.glob("**/*.tex").len(), 3);
+        assert_eq!(package.glob("*.json").len(), 1);
+        assert_eq!(package.glob("**/waterwaves.????").len(), 2);
+        assert!(package.glob("materials/*.TEX").is_empty());
+
+        // Many stars against a long non-matching path must not backtrack
+        // exponentially
+        let mut long = Package::new("PKGV0019".to_string());
+        long.entries
+            .push(PackageEntry::new(format!("{}b", "a/".repeat(200)), 0, 1));
+        assert!(long.glob(&"**/a*".repeat(12)).is_empty());
+
+        let shaders = package.find_entries(|e| e.entry_type == EntryType::Shader);
+        assert_eq!(paths(shaders), ["shaders/effects/waterwaves.vert"]);
+    }
+
+    #[test]
+    fn test_entry_checksums() {
+        let mut package = Package::new("PKGV0019".to_string());
+        let mut entry = PackageEntry::new("scene.json".to_string(), 0, 9);
+        entry.bytes = Some(b"123456789".to_vec());
+        package.entries.push(entry);
+        package
+            .entries
+            .push(PackageEntry::new("unloaded.tex".to_string(), 9, 4));
+
+        let checksums = package.verify();
+        assert_eq!(checksums.len(), 2);
+        assert_eq!(checksums[0].full_path, "scene.json");
+        // Standard CRC-32 check value
+        assert_eq!(checksums[0].crc32, Some(0xCBF4_3926));
+        assert_eq!(checksums[1].crc32, None);
+
+        #[cfg(feature = "sha256")]
+        assert_eq!(
+            checksums[0].sha256.as_deref(),
+            Some("15e2b0d3c33891ebb0f1ef609ec419420c20e320ce94c65fbc8c3312448eb225")
+        );
+    }
+
+    #[test]
+    fn test_parse_magic() {
+        assert_eq!(
+            PackageMagic::parse("PKGV0019"),
+            Some(PackageMagic {
+                version: 19,
+                recognized: true
+            })
+        );
+        assert_eq!(
+            PackageMagic::parse("PKGV0042"),
+            Some(PackageMagic {
+                version: 42,
+                recognized: true
+            })
+        );
+        assert_eq!(
+            PackageMagic::parse("PKGX0001"),
+            Some(PackageMagic {
+                version: 1,
+                recognized: false
+            })
+        );
+        assert_eq!(PackageMagic::parse("PKGV"), None);
+        assert_eq!(PackageMagic::parse("PKGVabcd"), None);
+        assert_eq!(PackageMagic::parse("pkgv0019"), None);
+        assert_eq!(PackageMagic::parse("NOTAPKG"), None);
+
+        let package = Package::new("PKGV0019".to_string());
+        assert_eq!(package.version, 19);
+        assert!(!package.unrecognized_magic);
+    }
+
+    #[test]
+    fn test_entry_bytes_accessors() {
+        let mut package = Package::new("PKGV0019".to_string());
+        let mut entry = PackageEntry::new("scene.json".to_string(), 0, 2);
+        entry.bytes = Some(b"{}".to_vec());
+        package.entries.push(entry);
+        package
+            .entries
+            .push(PackageEntry::new("unloaded.tex".to_string(), 2, 4));
+
+        assert_eq!(package.entry_bytes("scene.json"), Some(&b"{}"[..]));
+        assert_eq!(package.entry_bytes("unloaded.tex"), None);
+        assert_eq!(package.entry_bytes("missing.json"), None);
+
+        let entry = &mut package.entries[0];
+        assert_eq!(entry.take_bytes(), Some(b"{}".to_vec()));
+        assert!(!entry.has_bytes());
+        assert_eq!(entry.bytes_slice(), None);
+    }
+
+    #[test]
+    fn test_extension_histogram() {
+        let mut package = Package::new("PKGV0019".to_string());
+        for (path, length) in [
+            ("scene.json", 10),
+            ("materials/a.tex", 100),
+            ("materials/b.TEX", 50),
+            ("LICENSE", 5),
+        ] {
+            package
+                .entries
+                .push(PackageEntry::new(path.to_string(), 0, length));
+        }
+
+        let histogram = package.extension_histogram();
+        assert_eq!(histogram.len(), 3);
+        assert_eq!(histogram[".tex"], (2, 150));
+        assert_eq!(histogram[".json"], (1, 10));
+        assert_eq!(histogram[""], (1, 5));
+    }
+
     #[test]
     fn test_package_entry_root_file() {
         let entry = PackageEntry::new("scene.json".to_string(), 0, 100);
@@ -79,6 +79,51 @@ impl TexFormat {
             TexFormat::Unknown(v) => *v,
         }
     }
+
+    /// Get bytes per pixel for raw formats (None for compressed/unknown).
+    pub fn bytes_per_pixel(&self) -> Option<u32> {
+        match self {
+            TexFormat::RGBA8888 => Some(4),
+            TexFormat::R8 => Some(1),
+            TexFormat::RG88 => Some(2),
+            _ => None,
+        }
+    }
+
+    /// Check if this is a block-compressed format (DXT).
+    pub fn is_block_compressed(&self) -> bool {
+        matches!(self, TexFormat::DXT1 | TexFormat::DXT3 | TexFormat::DXT5)
+    }
+
+    /// Get bytes per 4x4 block for compressed formats.
+    pub fn block_size(&self) -> Option<u32> {
+        self.to_mipmap_format().bytes_per_block()
+    }
+
+    /// Get the mipmap format raw data in this format is stored as.
+    pub fn to_mipmap_format(&self) -> MipmapFormat {
+        match self {
+            TexFormat::RGBA8888 => MipmapFormat::RGBA8888,
+            TexFormat::DXT1 => MipmapFormat::CompressedDXT1,
+            TexFormat::DXT3 => MipmapFormat::CompressedDXT3,
+            TexFormat::DXT5 => MipmapFormat::CompressedDXT5,
+            TexFormat::R8 => MipmapFormat::R8,
+            TexFormat::RG88 => MipmapFormat::RG88,
+            TexFormat::Unknown(_) => MipmapFormat::Invalid,
+        }
+    }
+
+    /// Calculate the data size of a `width` x `height` image in this format.
+    ///
+    /// Returns None for unknown formats.
+    pub fn expected_size(&self, width: u32, height: u32) -> Option<usize> {
+        let (width, height) = (width as usize, height as usize);
+        if let Some(block_size) = self.block_size() {
+            return Some(width.div_ceil(4) * height.div_ceil(4) * block_size as usize);
+        }
+        self.bytes_per_pixel()
+            .map(|bpp| width * height * bpp as usize)
+    }
 }
 
 /// Format of mipmap data after decompression.
@@ -124,6 +169,8 @@ pub enum MipmapFormat {
     ImageTIFF,
     /// WebP image
     ImageWEBP,
+    /// ICO/CUR icon image
+    ImageICO,
 }
 
 impl MipmapFormat {
@@ -157,6 +204,7 @@ impl MipmapFormat {
                 | MipmapFormat::ImageDDS
                 | MipmapFormat::ImageTIFF
                 | MipmapFormat::ImageWEBP
+                | MipmapFormat::ImageICO
         )
     }
 
@@ -177,6 +225,7 @@ impl MipmapFormat {
             MipmapFormat::ImageDDS => ".dds",
             MipmapFormat::ImageTIFF => ".tiff",
             MipmapFormat::ImageWEBP => ".webp",
+            MipmapFormat::ImageICO => ".ico",
         }
     }
 
@@ -189,6 +238,41 @@ impl MipmapFormat {
             _ => None,
         }
     }
+
+    /// Infer the actual raw format of `data_size` bytes covering `pixel_count` pixels.
+    ///
+    /// Some TEX headers declare the wrong format (e.g. RG88 when the data is
+    /// actually R8). Returns `self` if it already matches the data size or
+    /// no raw format does.
+    pub fn infer_from_size(self, pixel_count: usize, data_size: usize) -> MipmapFormat {
+        // Check if the declared format matches the data size
+        if let Some(bpp) = self.bytes_per_pixel() {
+            if data_size == pixel_count * (bpp as usize) {
+                return self;
+            }
+        }
+
+        // Infer format from actual data size
+        if data_size == pixel_count * 4 {
+            MipmapFormat::RGBA8888
+        } else if data_size == pixel_count * 2 {
+            MipmapFormat::RG88
+        } else if data_size == pixel_count {
+            MipmapFormat::R8
+        } else {
+            // Can't determine, return original
+            self
+        }
+    }
+
+    /// Get bytes per 4x4 block for compressed formats.
+    pub fn bytes_per_block(&self) -> Option<u32> {
+        match self {
+            MipmapFormat::CompressedDXT1 => Some(8),
+            MipmapFormat::CompressedDXT3 | MipmapFormat::CompressedDXT5 => Some(16),
+            _ => None,
+        }
+    }
 }
 
 /// Version of the TEX image container format.
@@ -202,6 +286,8 @@ pub enum TexImageContainerVersion {
     Version3,
     /// Version 4 (TEXB0004)
     Version4,
+    /// Version 5 (TEXB0005), read with the V4 layout
+    Version5,
     /// Unknown version
     Unknown(String),
 }
@@ -214,6 +300,7 @@ impl TexImageContainerVersion {
             "TEXB0002" => TexImageContainerVersion::Version2,
             "TEXB0003" => TexImageContainerVersion::Version3,
             "TEXB0004" => TexImageContainerVersion::Version4,
+            "TEXB0005" => TexImageContainerVersion::Version5,
             _ => TexImageContainerVersion::Unknown(magic.to_string()),
         }
     }
@@ -230,6 +317,7 @@ impl TexImageContainerVersion {
             TexImageContainerVersion::Version2 => Some(2),
             TexImageContainerVersion::Version3 => Some(3),
             TexImageContainerVersion::Version4 => Some(4),
+            TexImageContainerVersion::Version5 => Some(5),
             TexImageContainerVersion::Unknown(_) => None,
         }
     }
@@ -370,6 +458,7 @@ impl FreeImageFormat {
     pub fn to_mipmap_format(&self) -> MipmapFormat {
         match self {
             FreeImageFormat::BMP => MipmapFormat::ImageBMP,
+            FreeImageFormat::ICO => MipmapFormat::ImageICO,
             FreeImageFormat::JPEG => MipmapFormat::ImageJPEG,
             FreeImageFormat::PNG => MipmapFormat::ImagePNG,
             FreeImageFormat::GIF => MipmapFormat::ImageGIF,
@@ -407,6 +496,33 @@ mod tests {
         assert_eq!(TexFormat::from(99), TexFormat::Unknown(99));
     }
 
+    #[test]
+    fn test_tex_format_sizes() {
+        assert_eq!(TexFormat::RGBA8888.bytes_per_pixel(), Some(4));
+        assert_eq!(TexFormat::R8.bytes_per_pixel(), Some(1));
+        assert_eq!(TexFormat::RG88.bytes_per_pixel(), Some(2));
+        assert_eq!(TexFormat::DXT1.bytes_per_pixel(), None);
+        assert_eq!(TexFormat::Unknown(3).bytes_per_pixel(), None);
+
+        assert!(!TexFormat::RGBA8888.is_block_compressed());
+        for (format, block_size) in [
+            (TexFormat::DXT1, 8),
+            (TexFormat::DXT3, 16),
+            (TexFormat::DXT5, 16),
+        ] {
+            assert!(format.is_block_compressed());
+            assert_eq!(format.block_size(), Some(block_size));
+        }
+        assert_eq!(TexFormat::R8.block_size(), None);
+
+        assert_eq!(TexFormat::RGBA8888.expected_size(16, 8), Some(512));
+        assert_eq!(TexFormat::RG88.expected_size(16, 8), Some(256));
+        // Partial blocks round up
+        assert_eq!(TexFormat::DXT1.expected_size(6, 6), Some(4 * 8));
+        assert_eq!(TexFormat::DXT5.expected_size(256, 256), Some(64 * 64 * 16));
+        assert_eq!(TexFormat::Unknown(3).expected_size(4, 4), None);
+    }
+
     #[test]
     fn test_mipmap_format_properties() {
         assert!(MipmapFormat::CompressedDXT5.is_compressed());
@@ -415,6 +531,44 @@ mod tests {
         assert!(MipmapFormat::ImagePNG.is_image());
         assert_eq!(MipmapFormat::RGBA8888.bytes_per_pixel(), Some(4));
         assert_eq!(MipmapFormat::R8.bytes_per_pixel(), Some(1));
+        assert!(MipmapFormat::ImageICO.is_image());
+        assert_eq!(MipmapFormat::CompressedDXT1.bytes_per_block(), Some(8));
+        assert_eq!(MipmapFormat::CompressedDXT3.bytes_per_block(), Some(16));
+        assert_eq!(MipmapFormat::RGBA8888.bytes_per_block(), None);
+    }
+
+    #[test]
+    fn test_mipmap_format_infer_from_size() {
+        assert_eq!(MipmapFormat::RG88.infer_from_size(16, 16), MipmapFormat::R8);
+        assert_eq!(
+            MipmapFormat::RGBA8888.infer_from_size(16, 32),
+            MipmapFormat::RG88
+        );
+        assert_eq!(
+            MipmapFormat::RG88.infer_from_size(16, 32),
+            MipmapFormat::RG88
+        );
+        // Sizes that match no raw format keep the declared format
+        assert_eq!(
+            MipmapFormat::RGBA8888.infer_from_size(16, 17),
+            MipmapFormat::RGBA8888
+        );
+    }
+
+    #[test]
+    fn test_free_image_format_to_mipmap_format() {
+        assert_eq!(
+            FreeImageFormat::PNG.to_mipmap_format(),
+            MipmapFormat::ImagePNG
+        );
+        assert_eq!(
+            FreeImageFormat::ICO.to_mipmap_format(),
+            MipmapFormat::ImageICO
+        );
+        assert_eq!(
+            FreeImageFormat::PSD.to_mipmap_format(),
+            MipmapFormat::Invalid
+        );
     }
 
     #[test]
@@ -423,6 +577,11 @@ mod tests {
             TexImageContainerVersion::from_magic("TEXB0003"),
             TexImageContainerVersion::Version3
         );
+        assert_eq!(
+            TexImageContainerVersion::from_magic("TEXB0005"),
+            TexImageContainerVersion::Version5
+        );
+        assert_eq!(TexImageContainerVersion::Version5.version_number(), Some(5));
         assert!(TexImageContainerVersion::Version3.is_supported());
         assert!(!TexImageContainerVersion::Unknown("TEXB9999".to_string()).is_supported());
     }
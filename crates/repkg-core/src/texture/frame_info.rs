@@ -30,6 +30,36 @@ impl TexFrameInfoContainer {
     pub fn total_duration(&self) -> f32 {
         self.frames.iter().map(|f| f.frametime).sum()
     }
+
+    /// Calculate the total animation duration in whole milliseconds.
+    ///
+    /// Equals the sum of the durations from
+    /// [`frame_timings_ms`](Self::frame_timings_ms).
+    pub fn total_duration_ms(&self) -> u64 {
+        self.frame_timings_ms()
+            .last()
+            .map_or(0, |&(start, duration)| start + duration)
+    }
+
+    /// Get the `(start_ms, duration_ms)` of each frame.
+    ///
+    /// Frame boundaries are rounded from the exact cumulative time instead of
+    /// rounding each frametime on its own, so timings don't drift and the
+    /// durations add up to the rounded total. Negative frametimes count as 0.
+    pub fn frame_timings_ms(&self) -> Vec<(u64, u64)> {
+        let mut elapsed = 0.0f64;
+        let mut start_ms = 0u64;
+        self.frames
+            .iter()
+            .map(|frame| {
+                elapsed += frame.frametime.max(0.0) as f64;
+                let end_ms = (elapsed * 1000.0).round() as u64;
+                let timing = (start_ms, end_ms - start_ms);
+                start_ms = end_ms;
+                timing
+            })
+            .collect()
+    }
 }
 
 /// Information about a single animation frame.
@@ -183,4 +213,30 @@ mod tests {
         assert_eq!(frame.crop_rect(), (0, 0, 100, 50));
         assert_eq!(frame.delay_centiseconds(), 10);
     }
+
+    #[test]
+    fn test_frame_timings_ms() {
+        let mut container = TexFrameInfoContainer::new(4, 4);
+        for _ in 0..3 {
+            container.frames.push(TexFrameInfo::new(0, 1.0 / 3.0));
+        }
+        assert_eq!(
+            container.frame_timings_ms(),
+            vec![(0, 333), (333, 334), (667, 333)]
+        );
+        assert_eq!(container.total_duration_ms(), 1000);
+
+        // Many short frames don't accumulate rounding drift
+        let mut container = TexFrameInfoContainer::new(4, 4);
+        for _ in 0..90 {
+            container.frames.push(TexFrameInfo::new(0, 1.0 / 30.0));
+        }
+        let timings = container.frame_timings_ms();
+        let sum: u64 = timings.iter().map(|&(_, duration)| duration).sum();
+        assert_eq!(sum, 3000);
+        assert_eq!(container.total_duration_ms(), 3000);
+        assert_eq!(timings[89].0, 2967);
+
+        assert_eq!(TexFrameInfoContainer::new(4, 4).total_duration_ms(), 0);
+    }
 }
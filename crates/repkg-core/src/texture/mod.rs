@@ -6,4 +6,4 @@ mod tex;
 
 pub use enums::{FreeImageFormat, MipmapFormat, TexFlags, TexFormat, TexImageContainerVersion};
 pub use frame_info::{TexFrameInfo, TexFrameInfoContainer};
-pub use tex::{Tex, TexHeader, TexImage, TexImageContainer, TexMipmap};
+pub use tex::{Tex, TexHeader, TexImage, TexImageContainer, TexMipmap, V4MipmapParams};
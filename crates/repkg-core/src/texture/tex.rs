@@ -16,6 +16,13 @@ pub struct Tex {
     pub images_container: TexImageContainer,
     /// Optional frame info for animated textures
     pub frame_info_container: Option<TexFrameInfoContainer>,
+    /// Magic of an image container that could not be read, if the reader was
+    /// allowed to skip it. The image container is empty when this is set.
+    pub unsupported_container: Option<String>,
+    /// Problems found while reading that did not stop the read, such as an
+    /// unknown texture format. Lenient readers also record recoverable
+    /// issues here that would otherwise fail the read.
+    pub warnings: Vec<String>,
 }
 
 impl Tex {
@@ -27,6 +34,8 @@ impl Tex {
             header,
             images_container: TexImageContainer::new(),
             frame_info_container: None,
+            unsupported_container: None,
+            warnings: Vec::new(),
         }
     }
 
@@ -40,6 +49,16 @@ impl Tex {
         self.header.flags.contains(TexFlags::IS_VIDEO_TEXTURE)
     }
 
+    /// Check if this texture should be sampled without interpolation.
+    pub fn uses_nearest_sampling(&self) -> bool {
+        self.header.flags.contains(TexFlags::NO_INTERPOLATION)
+    }
+
+    /// Check if this texture's UV coordinates are clamped instead of wrapped.
+    pub fn clamps_uvs(&self) -> bool {
+        self.header.flags.contains(TexFlags::CLAMP_UVS)
+    }
+
     /// Get the first image in the container.
     pub fn first_image(&self) -> Option<&TexImage> {
         self.images_container.images.first()
@@ -59,6 +78,24 @@ impl Tex {
     pub fn has_images(&self) -> bool {
         !self.images_container.images.is_empty()
     }
+
+    /// Get the logical size of the texture's converted output (width, height).
+    ///
+    /// This is the GIF container size for animated textures, the first
+    /// mipmap's size for videos, and the cropped image size otherwise.
+    pub fn output_dimensions(&self) -> (u32, u32) {
+        if let Some(frame_info) = self.frame_info_container.as_ref().filter(|_| self.is_gif()) {
+            return (frame_info.gif_width, frame_info.gif_height);
+        }
+
+        if self.is_video() {
+            if let Some(mipmap) = self.first_image().and_then(|img| img.first_mipmap()) {
+                return (mipmap.width, mipmap.height);
+            }
+        }
+
+        self.header.crop_dimensions()
+    }
 }
 
 /// Header containing texture metadata.
@@ -114,7 +151,9 @@ impl Default for TexHeader {
 /// Container for texture images and mipmaps.
 #[derive(Debug, Clone)]
 pub struct TexImageContainer {
-    /// Version of the container format
+    /// Container magic as stored in the file (e.g. "TEXB0004")
+    pub magic: String,
+    /// Version of the container format (V4 without video is read as V3)
     pub version: TexImageContainerVersion,
     /// FreeImage format code
     pub image_format: FreeImageFormat,
@@ -126,6 +165,7 @@ impl TexImageContainer {
     /// Create a new empty container.
     pub fn new() -> Self {
         Self {
+            magic: "TEXB0003".to_string(),
             version: TexImageContainerVersion::Version3,
             image_format: FreeImageFormat::Unknown,
             images: Vec::new(),
@@ -141,15 +181,35 @@ impl TexImageContainer {
         }
 
         // Otherwise derive from tex format
-        match tex_format {
-            TexFormat::RGBA8888 => MipmapFormat::RGBA8888,
-            TexFormat::DXT1 => MipmapFormat::CompressedDXT1,
-            TexFormat::DXT3 => MipmapFormat::CompressedDXT3,
-            TexFormat::DXT5 => MipmapFormat::CompressedDXT5,
-            TexFormat::R8 => MipmapFormat::R8,
-            TexFormat::RG88 => MipmapFormat::RG88,
-            TexFormat::Unknown(_) => MipmapFormat::Invalid,
+        tex_format.to_mipmap_format()
+    }
+
+    /// Get the format for mipmaps, also consulting the texture header's flags.
+    ///
+    /// V1/V2 containers have no image format field, so a video texture is
+    /// only recognizable by its `IS_VIDEO_TEXTURE` flag; its mipmaps are then
+    /// `VideoMp4` rather than the raw format from the header.
+    pub fn mipmap_format_for(&self, header: &TexHeader) -> MipmapFormat {
+        if self.image_format == FreeImageFormat::Unknown
+            && header.flags.contains(TexFlags::IS_VIDEO_TEXTURE)
+        {
+            return MipmapFormat::VideoMp4;
         }
+        self.mipmap_format(header.format)
+    }
+
+    /// The container version named by the on-disk magic.
+    ///
+    /// This differs from `version` when the reader downgraded the container,
+    /// e.g. a TEXB0004 container without video is read as V3.
+    pub fn raw_version(&self) -> TexImageContainerVersion {
+        TexImageContainerVersion::from_magic(&self.magic)
+    }
+
+    /// Check if the container was read with an older layout than its magic
+    /// names.
+    pub fn is_downgraded(&self) -> bool {
+        self.raw_version() != self.version
     }
 }
 
@@ -205,6 +265,9 @@ pub struct TexMipmap {
     pub height: u32,
     /// Format of the pixel data
     pub format: MipmapFormat,
+    /// Format declared by the texture header, before decompression or
+    /// format inference changed `format`
+    pub declared_format: MipmapFormat,
     /// Whether the data is LZ4 compressed
     pub is_lz4_compressed: bool,
     /// Size of data after LZ4 decompression
@@ -213,8 +276,31 @@ pub struct TexMipmap {
     pub bytes: Vec<u8>,
     /// Original byte count in file (useful when bytes are not loaded)
     pub original_byte_count: u32,
-    /// Byte offset in the original file where data starts
+    /// Byte offset in the original file where data starts; together with
+    /// `original_byte_count` this locates the stored (possibly compressed)
+    /// data without reading it
     pub file_offset: u64,
+    /// Extra parameters stored before each mipmap in V4 containers
+    pub v4_params: Option<V4MipmapParams>,
+    /// Exact bytes as stored in the file, before any decompression; only
+    /// kept when the reader was asked to store them
+    pub raw_bytes: Option<Vec<u8>>,
+}
+
+/// Extra per-mipmap parameters of V4 (video) image containers.
+///
+/// Their meaning is not fully known; the condition JSON appears to describe
+/// playback behavior.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct V4MipmapParams {
+    /// First unknown parameter
+    pub param1: u32,
+    /// Second unknown parameter
+    pub param2: u32,
+    /// Condition JSON string (may be empty)
+    pub condition_json: String,
+    /// Third unknown parameter
+    pub param3: u32,
 }
 
 impl TexMipmap {
@@ -224,11 +310,14 @@ impl TexMipmap {
             width,
             height,
             format: MipmapFormat::Invalid,
+            declared_format: MipmapFormat::Invalid,
             is_lz4_compressed: false,
             decompressed_bytes_count: 0,
             bytes: Vec::new(),
             original_byte_count: 0,
             file_offset: 0,
+            v4_params: None,
+            raw_bytes: None,
         }
     }
 
@@ -253,17 +342,14 @@ impl TexMipmap {
             MipmapFormat::RGBA8888 => (self.width as usize) * (self.height as usize) * 4,
             MipmapFormat::R8 => (self.width as usize) * (self.height as usize),
             MipmapFormat::RG88 => (self.width as usize) * (self.height as usize) * 2,
-            MipmapFormat::CompressedDXT1 => {
-                // DXT1: 8 bytes per 4x4 block
+            MipmapFormat::CompressedDXT1
+            | MipmapFormat::CompressedDXT3
+            | MipmapFormat::CompressedDXT5 => {
+                // DXT1: 8 bytes, DXT3/DXT5: 16 bytes per 4x4 block
                 let blocks_x = (self.width as usize).div_ceil(4);
                 let blocks_y = (self.height as usize).div_ceil(4);
-                blocks_x * blocks_y * 8
-            }
-            MipmapFormat::CompressedDXT3 | MipmapFormat::CompressedDXT5 => {
-                // DXT3/DXT5: 16 bytes per 4x4 block
-                let blocks_x = (self.width as usize).div_ceil(4);
-                let blocks_y = (self.height as usize).div_ceil(4);
-                blocks_x * blocks_y * 16
+                let block_size = self.format.bytes_per_block().unwrap_or(0) as usize;
+                blocks_x * blocks_y * block_size
             }
             _ => self.bytes.len(),
         }
@@ -295,6 +381,30 @@ mod tests {
         let tex = Tex::new(header);
         assert!(tex.is_gif());
         assert!(!tex.is_video());
+        assert!(!tex.uses_nearest_sampling());
+        assert!(!tex.clamps_uvs());
+    }
+
+    #[test]
+    fn test_sampling_flags() {
+        let with_flags = |flags| {
+            Tex::new(TexHeader {
+                flags,
+                ..TexHeader::new()
+            })
+        };
+
+        let tex = with_flags(TexFlags::NO_INTERPOLATION);
+        assert!(tex.uses_nearest_sampling());
+        assert!(!tex.clamps_uvs());
+
+        let tex = with_flags(TexFlags::CLAMP_UVS | TexFlags::IS_GIF);
+        assert!(!tex.uses_nearest_sampling());
+        assert!(tex.clamps_uvs());
+
+        let tex = with_flags(TexFlags::NO_INTERPOLATION | TexFlags::CLAMP_UVS);
+        assert!(tex.uses_nearest_sampling());
+        assert!(tex.clamps_uvs());
     }
 
     #[test]
@@ -313,6 +423,64 @@ mod tests {
         assert_eq!(header.crop_dimensions(), (200, 150));
     }
 
+    #[test]
+    fn test_output_dimensions() {
+        let mut header = TexHeader::new();
+        header.texture_width = 256;
+        header.texture_height = 256;
+        header.image_width = 200;
+        header.image_height = 150;
+
+        // Static textures are cropped to the image size
+        let tex = Tex::new(header);
+        assert_eq!(tex.output_dimensions(), (200, 150));
+
+        // Animations use the GIF container size
+        let mut gif = Tex::new(TexHeader {
+            flags: TexFlags::IS_GIF,
+            ..header
+        });
+        gif.frame_info_container = Some(TexFrameInfoContainer::new(64, 48));
+        assert_eq!(gif.output_dimensions(), (64, 48));
+
+        // Videos use the first mipmap's size
+        let mut video = Tex::new(TexHeader {
+            flags: TexFlags::IS_VIDEO_TEXTURE,
+            ..header
+        });
+        let mut image = TexImage::new();
+        image.mipmaps.push(TexMipmap::new(1920, 1080));
+        video.images_container.images.push(image);
+        assert_eq!(video.output_dimensions(), (1920, 1080));
+    }
+
+    #[test]
+    fn test_mipmap_format_for_video_flag() {
+        let mut header = TexHeader::new();
+        header.format = TexFormat::RGBA8888;
+        let mut container = TexImageContainer::new();
+        container.image_format = FreeImageFormat::Unknown;
+        assert_eq!(container.mipmap_format_for(&header), MipmapFormat::RGBA8888);
+
+        header.flags = TexFlags::IS_VIDEO_TEXTURE;
+        assert_eq!(container.mipmap_format_for(&header), MipmapFormat::VideoMp4);
+
+        // A known container format wins over the flag
+        container.image_format = FreeImageFormat::PNG;
+        assert_eq!(container.mipmap_format_for(&header), MipmapFormat::ImagePNG);
+    }
+
+    #[test]
+    fn test_container_raw_version() {
+        let mut container = TexImageContainer::new();
+        assert_eq!(container.raw_version(), TexImageContainerVersion::Version3);
+        assert!(!container.is_downgraded());
+
+        container.magic = "TEXB0004".to_string();
+        assert_eq!(container.raw_version(), TexImageContainerVersion::Version4);
+        assert!(container.is_downgraded());
+    }
+
     #[test]
     fn test_mipmap_expected_size() {
         let mut mipmap = TexMipmap::new(256, 256);
@@ -2,12 +2,17 @@
 //!
 //! This crate provides JavaScript-friendly APIs for parsing and converting
 //! Wallpaper Engine PKG and TEX files in the browser.
+//!
+//! Failures are thrown as JavaScript `Error`s with a `kind` code (e.g.
+//! `invalid_tex_magic`, `unsupported_mipmap_format`) and, when one applies,
+//! a `suggestion` for recovering.
 
 use repkg::package::PackageReader;
-use repkg::texture::{OutputFormat, TexReader, TexToImageConverter};
+use repkg::texture::{CancelFlag, ConversionResult, OutputFormat, TexReader, TexToImageConverter};
 use repkg_core::{Package, Tex};
 use serde::Serialize;
 use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, Ordering};
 use wasm_bindgen::prelude::*;
 
 #[macro_use]
@@ -30,6 +35,10 @@ pub fn init() {
 #[derive(Serialize)]
 pub struct PkgInfo {
     pub magic: String,
+    pub version: u32,
+    pub unrecognized_magic: bool,
+    /// Size of the header and entry table; entry data starts here
+    pub header_size: u32,
     pub entry_count: usize,
     pub entries: Vec<PkgEntryInfo>,
 }
@@ -38,6 +47,9 @@ pub struct PkgInfo {
 #[derive(Serialize)]
 pub struct PkgEntryInfo {
     pub path: String,
+    /// Offset of the entry's data from the end of the header, so the data
+    /// spans `header_size + offset` to `header_size + offset + size`
+    pub offset: u32,
     pub size: u32,
     pub entry_type: String,
 }
@@ -56,10 +68,27 @@ pub struct TexInfo {
     pub height: u32,
     pub texture_width: u32,
     pub texture_height: u32,
+    /// Width of the image produced by conversion (after cropping)
+    pub output_width: u32,
+    /// Height of the image produced by conversion (after cropping)
+    pub output_height: u32,
     pub format: String,
     pub is_gif: bool,
     pub is_video: bool,
     pub mipmap_count: usize,
+    /// Per-level details of the first image's mipmaps, largest first
+    pub mipmaps: Vec<MipmapInfo>,
+}
+
+/// Information about a single mipmap level.
+#[derive(Serialize)]
+pub struct MipmapInfo {
+    pub width: u32,
+    pub height: u32,
+    pub format: String,
+    /// Size of the level's data as stored in the file
+    pub byte_count: u32,
+    pub is_lz4_compressed: bool,
 }
 
 // ============================================================================
@@ -70,7 +99,7 @@ pub struct TexInfo {
 #[derive(Serialize)]
 struct PkgParseLog {
     magic: String,
-    version: String,
+    version: u32,
     header_size_bytes: u32,
     entry_count: usize,
     total_data_bytes: u64,
@@ -118,12 +147,29 @@ struct ExtractLog {
 
 /// Parse a PKG file and return information about its contents.
 #[wasm_bindgen]
-pub fn parse_pkg(bytes: &[u8]) -> Result<JsValue, JsError> {
+pub fn parse_pkg(bytes: &[u8]) -> Result<JsValue, JsValue> {
     let reader = PackageReader::new();
     let package = reader
         .read_from(&mut Cursor::new(bytes))
-        .map_err(|e| JsError::new(&e.to_string()))?;
+        .map_err(js_error)?;
+    package_info(&package)
+}
+
+/// Parse only the header and entry table of a PKG file.
+///
+/// Returns the same structure as `parse_pkg` without copying any entry
+/// data, for listing a package's contents.
+#[wasm_bindgen]
+pub fn parse_pkg_info(bytes: &[u8]) -> Result<JsValue, JsValue> {
+    let reader = PackageReader::info_only();
+    let package = reader
+        .read_from(&mut Cursor::new(bytes))
+        .map_err(js_error)?;
+    package_info(&package)
+}
 
+/// Log and serialize the contents of a parsed package.
+fn package_info(package: &Package) -> Result<JsValue, JsValue> {
     // Log parsing details
     #[cfg(feature = "console-log")]
     {
@@ -145,7 +191,15 @@ pub fn parse_pkg(bytes: &[u8]) -> Result<JsValue, JsError> {
         let other_count = package
             .entries
             .iter()
-            .filter(|e| matches!(e.entry_type, repkg_core::EntryType::Other))
+            .filter(|e| {
+                matches!(
+                    e.entry_type,
+                    repkg_core::EntryType::Model
+                        | repkg_core::EntryType::Audio
+                        | repkg_core::EntryType::Font
+                        | repkg_core::EntryType::Other
+                )
+            })
             .count();
         let total_data: u64 = package.entries.iter().map(|e| e.length as u64).sum();
 
@@ -153,7 +207,7 @@ pub fn parse_pkg(bytes: &[u8]) -> Result<JsValue, JsError> {
             "pkg_parse",
             &PkgParseLog {
                 magic: package.magic.clone(),
-                version: package.magic.chars().skip(4).collect(),
+                version: package.version,
                 header_size_bytes: package.header_size,
                 entry_count: package.entries.len(),
                 total_data_bytes: total_data,
@@ -165,70 +219,84 @@ pub fn parse_pkg(bytes: &[u8]) -> Result<JsValue, JsError> {
         );
     }
 
-    let info = pkg_to_info(&package);
-    serde_wasm_bindgen::to_value(&info).map_err(|e| JsError::new(&e.to_string()))
+    let info = pkg_to_info(package);
+    serde_wasm_bindgen::to_value(&info).map_err(|e| JsError::new(&e.to_string()).into())
 }
 
 /// Extract a single entry from a PKG file by path.
 #[wasm_bindgen]
-pub fn extract_pkg_entry(bytes: &[u8], path: &str) -> Result<Vec<u8>, JsError> {
+pub fn extract_pkg_entry(bytes: &[u8], path: &str) -> Result<Vec<u8>, JsValue> {
     let reader = PackageReader::new();
-    let package = reader
+    let mut package = reader
         .read_from(&mut Cursor::new(bytes))
-        .map_err(|e| JsError::new(&e.to_string()))?;
+        .map_err(js_error)?;
 
     let entry = package
         .entries
-        .iter()
+        .iter_mut()
         .find(|e| e.full_path == path)
-        .ok_or_else(|| JsError::new(&format!("Entry not found: {}", path)))?;
+        .ok_or_else(|| {
+            wasm_error(
+                "entry_not_found",
+                &format!("Entry not found: {}", path),
+                None,
+            )
+        })?;
 
     entry
-        .bytes
-        .clone()
-        .ok_or_else(|| JsError::new("Entry has no data"))
+        .take_bytes()
+        .ok_or_else(|| js_error(repkg::Error::invalid_data("Entry has no data")))
 }
 
 /// Extract all entries from a PKG file.
 /// Returns an array of { path: string, data: Uint8Array } objects.
+///
+/// If `progress` is a function, it is called as `progress(index, total, path)`
+/// before each entry is copied out. Errors thrown by the callback are ignored.
 #[wasm_bindgen]
-pub fn extract_all_pkg(bytes: &[u8]) -> Result<JsValue, JsError> {
+pub fn extract_all_pkg(
+    bytes: &[u8],
+    progress: Option<js_sys::Function>,
+) -> Result<JsValue, JsValue> {
     let reader = PackageReader::new();
     let package = reader
         .read_from(&mut Cursor::new(bytes))
-        .map_err(|e| JsError::new(&e.to_string()))?;
+        .map_err(js_error)?;
 
+    let total = package.entries.len();
     let files: Vec<ExtractedFile> = package
         .entries
-        .iter()
-        .filter_map(|entry| {
-            entry.bytes.as_ref().map(|data| ExtractedFile {
-                path: entry.full_path.clone(),
-                data: data.clone(),
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, mut entry)| {
+            report_progress(progress.as_ref(), index, total, &entry.full_path);
+            entry.take_bytes().map(|data| ExtractedFile {
+                path: entry.full_path,
+                data,
             })
         })
         .collect();
 
-    serde_wasm_bindgen::to_value(&files).map_err(|e| JsError::new(&e.to_string()))
+    serde_wasm_bindgen::to_value(&files).map_err(|e| JsError::new(&e.to_string()).into())
 }
 
 /// Extract selected entries from a PKG file.
 /// `paths` should be a JavaScript array of strings.
 #[wasm_bindgen]
-pub fn extract_selected_pkg(bytes: &[u8], paths: Vec<String>) -> Result<JsValue, JsError> {
+pub fn extract_selected_pkg(bytes: &[u8], paths: Vec<String>) -> Result<JsValue, JsValue> {
     let reader = PackageReader::new();
     let package = reader
         .read_from(&mut Cursor::new(bytes))
-        .map_err(|e| JsError::new(&e.to_string()))?;
+        .map_err(js_error)?;
 
     let files: Vec<ExtractedFile> = package
         .entries
-        .iter()
+        .into_iter()
         .filter(|entry| paths.contains(&entry.full_path))
-        .filter_map(|entry| {
-            entry.bytes.as_ref().map(|data| ExtractedFile {
-                path: entry.full_path.clone(),
-                data: data.clone(),
+        .filter_map(|mut entry| {
+            entry.take_bytes().map(|data| ExtractedFile {
+                path: entry.full_path,
+                data,
             })
         })
         .collect();
@@ -246,7 +314,7 @@ pub fn extract_selected_pkg(bytes: &[u8], paths: Vec<String>) -> Result<JsValue,
         );
     }
 
-    serde_wasm_bindgen::to_value(&files).map_err(|e| JsError::new(&e.to_string()))
+    serde_wasm_bindgen::to_value(&files).map_err(|e| JsError::new(&e.to_string()).into())
 }
 
 // ============================================================================
@@ -255,11 +323,13 @@ pub fn extract_selected_pkg(bytes: &[u8], paths: Vec<String>) -> Result<JsValue,
 
 /// Parse a TEX file and return information about it.
 #[wasm_bindgen]
-pub fn parse_tex(bytes: &[u8]) -> Result<JsValue, JsError> {
-    let reader = TexReader::new();
+pub fn parse_tex(bytes: &[u8]) -> Result<JsValue, JsValue> {
+    // Mipmap data isn't needed, and skipping decompression keeps the
+    // reported LZ4 flags and stored sizes accurate
+    let reader = TexReader::headers_only();
     let tex = reader
         .read_from(&mut Cursor::new(bytes))
-        .map_err(|e| JsError::new(&e.to_string()))?;
+        .map_err(js_error)?;
 
     // Log parsing details
     #[cfg(feature = "console-log")]
@@ -267,7 +337,12 @@ pub fn parse_tex(bytes: &[u8]) -> Result<JsValue, JsError> {
         let mipmap_count = tex.first_image().map(|i| i.mipmap_count()).unwrap_or(0);
         let total_mipmap_bytes: usize = tex
             .first_image()
-            .map(|img| img.mipmaps.iter().map(|m| m.bytes.len()).sum())
+            .map(|img| {
+                img.mipmaps
+                    .iter()
+                    .map(|m| m.original_byte_count as usize)
+                    .sum()
+            })
             .unwrap_or(0);
         let is_lz4 = tex
             .first_image()
@@ -295,28 +370,29 @@ pub fn parse_tex(bytes: &[u8]) -> Result<JsValue, JsError> {
     }
 
     let info = tex_to_info(&tex);
-    serde_wasm_bindgen::to_value(&info).map_err(|e| JsError::new(&e.to_string()))
+    serde_wasm_bindgen::to_value(&info).map_err(|e| JsError::new(&e.to_string()).into())
 }
 
 /// Convert a TEX file to an image format.
-/// Supported formats: "png", "jpg", "jpeg", "gif", "webp", "bmp", "tiff", "tga"
+/// Supported formats: "png", "jpg", "jpeg", "gif", "webp", "bmp", "tiff", "tga", "qoi", "dds"
+///
+/// DDS output keeps DXT textures block-compressed.
+///
+/// `quality` (0-100, default 90) only affects lossy encoding, which is JPEG;
+/// WebP output is encoded losslessly and ignores it.
 #[wasm_bindgen]
-pub fn convert_tex(bytes: &[u8], format: &str) -> Result<Vec<u8>, JsError> {
+pub fn convert_tex(bytes: &[u8], format: &str, quality: Option<u8>) -> Result<Vec<u8>, JsValue> {
     #[cfg(feature = "console-log")]
     let input_len = bytes.len();
 
-    let reader = TexReader::new();
-    let tex = reader
-        .read_from(&mut Cursor::new(bytes))
-        .map_err(|e| JsError::new(&e.to_string()))?;
+    let output_format = parse_output_format(format)?;
 
-    let output_format = OutputFormat::parse(format)
-        .ok_or_else(|| JsError::new(&format!("Unsupported format: {}", format)))?;
+    let tex = reader_for(output_format)
+        .read_from(&mut Cursor::new(bytes))
+        .map_err(js_error)?;
 
-    let converter = TexToImageConverter::new();
-    let result = converter
-        .convert(&tex, output_format)
-        .map_err(|e| JsError::new(&e.to_string()))?;
+    let converter = converter_with_quality(quality);
+    let result = converter.convert(&tex, output_format).map_err(js_error)?;
 
     // Log conversion details
     #[cfg(feature = "console-log")]
@@ -342,6 +418,301 @@ pub fn convert_tex(bytes: &[u8], format: &str) -> Result<Vec<u8>, JsError> {
     Ok(result.bytes)
 }
 
+/// Convert a single mipmap level of a TEX file's first image.
+///
+/// Level 0 is the full-size image. Errors if `level` is out of range or the
+/// texture is a video. Accepts the same formats as `convert_tex`.
+#[wasm_bindgen]
+pub fn convert_tex_mipmap(bytes: &[u8], level: u32, format: &str) -> Result<Vec<u8>, JsValue> {
+    let output_format = parse_output_format(format)?;
+    let tex = reader_for(output_format)
+        .read_from(&mut Cursor::new(bytes))
+        .map_err(js_error)?;
+
+    let result = TexToImageConverter::new()
+        .convert_mipmap(&tex, level as usize, output_format)
+        .map_err(js_error)?;
+
+    Ok(result.bytes)
+}
+
+/// Decode a TEX file to raw RGBA8888 pixels, without an image container.
+///
+/// Returns a `RawRgba` with `width`, `height` and tightly packed `data`
+/// (4 bytes per pixel), e.g. for uploading to WebGL without a PNG round trip.
+#[wasm_bindgen]
+pub fn tex_to_raw_rgba(bytes: &[u8]) -> Result<RawRgba, JsValue> {
+    let tex = TexReader::new()
+        .read_from(&mut Cursor::new(bytes))
+        .map_err(js_error)?;
+
+    let (width, height, data) = TexToImageConverter::new()
+        .to_raw_rgba(&tex)
+        .map_err(js_error)?;
+
+    Ok(RawRgba {
+        width,
+        height,
+        data,
+    })
+}
+
+/// Raw RGBA8888 pixels of a decoded texture.
+#[wasm_bindgen]
+pub struct RawRgba {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl RawRgba {
+    /// Width in pixels.
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height in pixels.
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get a copy of the pixel data.
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+
+    /// Take the pixel data without copying (consumes the data).
+    pub fn take_data(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.data)
+    }
+}
+
+/// Prepare a conversion that can be cancelled before or while it runs.
+///
+/// Returns a `ConversionHandle`; call `run()` to perform the conversion and
+/// `cancel()` to abort it. A cancelled handle makes `run()` throw instead of
+/// converting. The input is copied into the module once, when the handle is
+/// created.
+#[wasm_bindgen]
+pub fn convert_tex_cancellable(bytes: Vec<u8>, format: &str) -> Result<ConversionHandle, JsValue> {
+    let output_format = parse_output_format(format)?;
+
+    Ok(ConversionHandle {
+        bytes,
+        format: output_format,
+        cancelled: AtomicBool::new(false),
+    })
+}
+
+/// A pending TEX conversion that can be cancelled.
+#[wasm_bindgen]
+pub struct ConversionHandle {
+    bytes: Vec<u8>,
+    format: OutputFormat,
+    cancelled: AtomicBool,
+}
+
+#[wasm_bindgen]
+impl ConversionHandle {
+    /// Request cancellation. Safe to call at any time, including after `run()`.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether `cancel()` has been called.
+    #[wasm_bindgen(getter)]
+    pub fn cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Run the conversion and return the encoded bytes (consumes the input).
+    ///
+    /// `run()` converts synchronously, so `cancel()` can't be called while it
+    /// runs. Instead, if `should_cancel` is a function it is called between
+    /// pipeline steps (each image, mipmap level and animation frame), and
+    /// returning a truthy value stops the conversion, e.g. once a deadline
+    /// passes or a flag in shared memory is set. Errors thrown by the
+    /// callback are ignored.
+    pub fn run(&mut self, should_cancel: Option<js_sys::Function>) -> Result<Vec<u8>, JsValue> {
+        let bytes = std::mem::take(&mut self.bytes);
+        let cancel_flag = JsCancelFlag {
+            cancelled: &self.cancelled,
+            should_cancel: should_cancel.as_ref(),
+        };
+        run_cancellable(&bytes, self.format, &cancel_flag).map_err(js_error)
+    }
+}
+
+/// A handle's cancel flag, also polling a JS callback while it is unset.
+#[derive(Debug)]
+struct JsCancelFlag<'h> {
+    cancelled: &'h AtomicBool,
+    should_cancel: Option<&'h js_sys::Function>,
+}
+
+// SAFETY: the module is built without threads, so the JS callback is only
+// ever called from the thread that created it
+unsafe impl Sync for JsCancelFlag<'_> {}
+
+impl CancelFlag for JsCancelFlag<'_> {
+    fn is_cancelled(&self) -> bool {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return true;
+        }
+        let Some(callback) = self.should_cancel else {
+            return false;
+        };
+
+        // Ignore errors from callback
+        let cancel = callback
+            .call0(&JsValue::NULL)
+            .is_ok_and(|value| value.is_truthy());
+        if cancel {
+            self.cancelled.store(true, Ordering::Relaxed);
+        }
+        cancel
+    }
+}
+
+/// Convert many TEX files to one format in a single call.
+///
+/// Returns one object per buffer, in order: `{ ok: true, data: Uint8Array }`
+/// on success or `{ ok: false, error: string, kind: string }` on failure,
+/// so one bad texture doesn't abort the batch. `quality` is as for
+/// `convert_tex`.
+#[wasm_bindgen]
+pub fn convert_tex_batch(
+    buffers: Vec<js_sys::Uint8Array>,
+    format: &str,
+    quality: Option<u8>,
+) -> Result<js_sys::Array, JsValue> {
+    let output_format = parse_output_format(format)?;
+    let converter = converter_with_quality(quality);
+
+    let results = js_sys::Array::new_with_length(buffers.len() as u32);
+    for (index, buffer) in buffers.iter().enumerate() {
+        let item = match convert_batch_item(&buffer.to_vec(), output_format, &converter) {
+            Ok(result) => js_object(&[
+                ("ok", JsValue::TRUE),
+                (
+                    "data",
+                    js_sys::Uint8Array::from(result.bytes.as_slice()).into(),
+                ),
+            ])?,
+            Err(e) => js_object(&[
+                ("ok", JsValue::FALSE),
+                ("error", JsValue::from_str(&e.to_string())),
+                ("kind", JsValue::from_str(e.kind())),
+            ])?,
+        };
+        results.set(index as u32, item.into());
+    }
+
+    Ok(results)
+}
+
+/// Convert every texture in a PKG file in one call.
+///
+/// Returns `{ path, data, format, mime_type }` for each TEX entry, in package
+/// order; other entries are skipped. Video textures are always MP4, so check
+/// `format` per item. A texture that fails to convert is returned as
+/// `{ path, error, kind }` instead of aborting the call.
+#[wasm_bindgen]
+pub fn convert_pkg_textures(bytes: &[u8], format: &str) -> Result<js_sys::Array, JsValue> {
+    let output_format = parse_output_format(format)?;
+    let package = PackageReader::new()
+        .read_from(&mut Cursor::new(bytes))
+        .map_err(js_error)?;
+
+    let results = js_sys::Array::new();
+    for (path, result) in convert_textures(package, output_format, &TexToImageConverter::new()) {
+        let item = match result {
+            Ok(result) => js_object(&[
+                ("path", JsValue::from_str(&path)),
+                (
+                    "data",
+                    js_sys::Uint8Array::from(result.bytes.as_slice()).into(),
+                ),
+                ("format", JsValue::from_str(result.format.extension())),
+                (
+                    "mime_type",
+                    JsValue::from_str(&format_to_mime(result.format)),
+                ),
+            ])?,
+            Err(e) => js_object(&[
+                ("path", JsValue::from_str(&path)),
+                ("error", JsValue::from_str(&e.to_string())),
+                ("kind", JsValue::from_str(e.kind())),
+            ])?,
+        };
+        results.push(&item);
+    }
+
+    Ok(results)
+}
+
+/// Read and convert one TEX file of a batch.
+fn convert_batch_item(
+    bytes: &[u8],
+    format: OutputFormat,
+    converter: &TexToImageConverter,
+) -> repkg::Result<ConversionResult> {
+    let tex = reader_for(format).read_from(&mut Cursor::new(bytes))?;
+    converter.convert(&tex, format)
+}
+
+/// Convert the TEX entries of a package, returning each entry's path and
+/// conversion result in package order.
+fn convert_textures(
+    package: Package,
+    format: OutputFormat,
+    converter: &TexToImageConverter,
+) -> Vec<(String, repkg::Result<ConversionResult>)> {
+    package
+        .entries
+        .into_iter()
+        .filter(|entry| entry.entry_type == repkg_core::EntryType::Tex)
+        .map(|mut entry| {
+            let result = match entry.take_bytes() {
+                Some(bytes) => convert_batch_item(&bytes, format, converter),
+                None => Err(repkg::Error::invalid_data("Entry has no data")),
+            };
+            (entry.full_path, result)
+        })
+        .collect()
+}
+
+/// Build a plain JS object from key/value pairs.
+fn js_object(fields: &[(&str, JsValue)]) -> Result<js_sys::Object, JsValue> {
+    let object = js_sys::Object::new();
+    for (key, value) in fields {
+        js_sys::Reflect::set(&object, &JsValue::from_str(key), value)
+            .map_err(|_| JsError::new("Failed to build result object"))?;
+    }
+    Ok(object)
+}
+
+/// Read and convert a TEX file, stopping once `cancelled` is set.
+fn run_cancellable(
+    bytes: &[u8],
+    format: OutputFormat,
+    cancelled: &dyn CancelFlag,
+) -> repkg::Result<Vec<u8>> {
+    if cancelled.is_cancelled() {
+        return Err(repkg::Error::Cancelled);
+    }
+
+    let tex = reader_for(format).read_from(&mut Cursor::new(bytes))?;
+    let result = TexToImageConverter::new()
+        .with_cancel_flag(cancelled)
+        .convert(&tex, format)?;
+    Ok(result.bytes)
+}
+
 /// Video data location info for zero-copy extraction.
 #[derive(Serialize)]
 pub struct VideoDataInfo {
@@ -355,12 +726,12 @@ pub struct VideoDataInfo {
 /// If is_video is true, you can use bytes.slice(data_offset, data_offset + data_size)
 /// to get the MP4 data directly without WASM memory overhead.
 #[wasm_bindgen]
-pub fn get_video_data_location(bytes: &[u8]) -> Result<JsValue, JsError> {
+pub fn get_video_data_location(bytes: &[u8]) -> Result<JsValue, JsValue> {
     // Create a reader that only reads headers, not mipmap data
     let reader = TexReader::headers_only();
     let tex = reader
         .read_from(&mut Cursor::new(bytes))
-        .map_err(|e| JsError::new(&e.to_string()))?;
+        .map_err(js_error)?;
 
     if !tex.is_video() {
         let info = VideoDataInfo {
@@ -368,40 +739,82 @@ pub fn get_video_data_location(bytes: &[u8]) -> Result<JsValue, JsError> {
             data_offset: 0,
             data_size: 0,
         };
-        return serde_wasm_bindgen::to_value(&info).map_err(|e| JsError::new(&e.to_string()));
+        return serde_wasm_bindgen::to_value(&info)
+            .map_err(|e| JsError::new(&e.to_string()).into());
     }
 
     // Get the video data location from the mipmap metadata
     let mipmap = tex
         .first_image()
         .and_then(|img| img.first_mipmap())
-        .ok_or_else(|| JsError::new("Video texture has no data"))?;
+        .ok_or_else(|| js_error(repkg::Error::invalid_data("Video texture has no data")))?;
 
     let info = VideoDataInfo {
         is_video: true,
         data_offset: mipmap.file_offset,
         data_size: mipmap.original_byte_count,
     };
-    serde_wasm_bindgen::to_value(&info).map_err(|e| JsError::new(&e.to_string()))
+    serde_wasm_bindgen::to_value(&info).map_err(|e| JsError::new(&e.to_string()).into())
+}
+
+/// Data location of a single mipmap for zero-copy extraction.
+#[derive(Serialize)]
+pub struct MipmapDataInfo {
+    pub offset: u64,
+    pub size: u32,
+    pub is_lz4_compressed: bool,
+    pub decompressed_size: u32,
+    pub width: u32,
+    pub height: u32,
+    pub format: String,
+}
+
+/// Get the location of any mipmap's data without reading it.
+/// Returns { offset, size, is_lz4_compressed, decompressed_size, width, height, format }.
+/// Use bytes.slice(offset, offset + size) to get the stored data; if
+/// is_lz4_compressed is true it must be LZ4-decompressed to decompressed_size
+/// bytes first. Errors if either index is out of range.
+#[wasm_bindgen]
+pub fn get_mipmap_data_location(
+    bytes: &[u8],
+    image_index: u32,
+    mipmap_index: u32,
+) -> Result<JsValue, JsValue> {
+    // Create a reader that only reads headers, not mipmap data
+    let reader = TexReader::headers_only();
+    let tex = reader
+        .read_from(&mut Cursor::new(bytes))
+        .map_err(js_error)?;
+
+    let info =
+        mipmap_location(&tex, image_index as usize, mipmap_index as usize).ok_or_else(|| {
+            wasm_error(
+                "mipmap_not_found",
+                &format!("No mipmap {} in image {}", mipmap_index, image_index),
+                None,
+            )
+        })?;
+    serde_wasm_bindgen::to_value(&info).map_err(|e| JsError::new(&e.to_string()).into())
 }
 
 /// Convert a TEX file to its recommended format (PNG for images, GIF for animations, MP4 for video).
+///
+/// `quality` (0-100, default 90) is passed to the converter as in
+/// `convert_tex`; it only affects lossy encoding, which is JPEG.
 #[wasm_bindgen]
-pub fn convert_tex_auto(bytes: &[u8]) -> Result<ConvertResult, JsError> {
+pub fn convert_tex_auto(bytes: &[u8], quality: Option<u8>) -> Result<ConvertResult, JsValue> {
     #[cfg(feature = "console-log")]
     let input_len = bytes.len();
 
     let reader = TexReader::new();
     let tex = reader
         .read_from(&mut Cursor::new(bytes))
-        .map_err(|e| JsError::new(&e.to_string()))?;
+        .map_err(js_error)?;
 
-    let converter = TexToImageConverter::new();
+    let converter = converter_with_quality(quality);
     let format = converter.recommended_format(&tex);
 
-    let result = converter
-        .convert(&tex, format)
-        .map_err(|e| JsError::new(&e.to_string()))?;
+    let result = converter.convert(&tex, format).map_err(js_error)?;
 
     // Log conversion details
     #[cfg(feature = "console-log")]
@@ -428,15 +841,30 @@ pub fn convert_tex_auto(bytes: &[u8]) -> Result<ConvertResult, JsError> {
         data: result.bytes,
         format: result.format.extension().to_string(),
         mime_type: format_to_mime(result.format),
+        width: result.width,
+        height: result.height,
     })
 }
 
+/// Get the extension of the format `convert_tex_auto` would produce ("png",
+/// "gif" or "mp4"), reading only the TEX headers.
+#[wasm_bindgen]
+pub fn recommended_format_for_tex(bytes: &[u8]) -> Result<String, JsValue> {
+    let tex = TexReader::headers_only()
+        .read_from(&mut Cursor::new(bytes))
+        .map_err(js_error)?;
+    let format = TexToImageConverter::new().recommended_format(&tex);
+    Ok(format.extension().to_string())
+}
+
 /// Result of automatic TEX conversion.
 #[wasm_bindgen]
 pub struct ConvertResult {
     data: Vec<u8>,
     format: String,
     mime_type: String,
+    width: u32,
+    height: u32,
 }
 
 #[wasm_bindgen]
@@ -458,6 +886,18 @@ impl ConvertResult {
     pub fn mime_type(&self) -> String {
         self.mime_type.clone()
     }
+
+    /// Get the output width in pixels, after cropping.
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the output height in pixels, after cropping.
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
 }
 
 // ============================================================================
@@ -467,12 +907,16 @@ impl ConvertResult {
 fn pkg_to_info(package: &Package) -> PkgInfo {
     PkgInfo {
         magic: package.magic.clone(),
+        version: package.version,
+        unrecognized_magic: package.unrecognized_magic,
+        header_size: package.header_size,
         entry_count: package.entries.len(),
         entries: package
             .entries
             .iter()
             .map(|e| PkgEntryInfo {
                 path: e.full_path.clone(),
+                offset: e.offset,
                 size: e.length,
                 entry_type: entry_type_string(&e.entry_type),
             })
@@ -480,27 +924,138 @@ fn pkg_to_info(package: &Package) -> PkgInfo {
     }
 }
 
-fn entry_type_string(entry_type: &repkg_core::EntryType) -> String {
-    match entry_type {
-        repkg_core::EntryType::Tex => "texture".to_string(),
-        repkg_core::EntryType::Json => "json".to_string(),
-        repkg_core::EntryType::Shader => "shader".to_string(),
-        repkg_core::EntryType::Other => "other".to_string(),
+/// Invoke a JS progress callback, if one was provided.
+fn report_progress(callback: Option<&js_sys::Function>, index: usize, total: usize, path: &str) {
+    if let Some(callback) = callback {
+        // Ignore errors from callback
+        let _ = callback.call3(
+            &JsValue::NULL,
+            &JsValue::from(index as u32),
+            &JsValue::from(total as u32),
+            &JsValue::from_str(path),
+        );
     }
 }
 
+fn mipmap_location(tex: &Tex, image_index: usize, mipmap_index: usize) -> Option<MipmapDataInfo> {
+    let mipmap = tex
+        .images_container
+        .images
+        .get(image_index)?
+        .mipmaps
+        .get(mipmap_index)?;
+
+    Some(MipmapDataInfo {
+        offset: mipmap.file_offset,
+        size: mipmap.original_byte_count,
+        is_lz4_compressed: mipmap.is_lz4_compressed,
+        decompressed_size: mipmap.decompressed_bytes_count,
+        width: mipmap.width,
+        height: mipmap.height,
+        format: format!("{:?}", mipmap.format),
+    })
+}
+
+fn entry_type_string(entry_type: &repkg_core::EntryType) -> String {
+    entry_type.as_str().to_string()
+}
+
 fn tex_to_info(tex: &Tex) -> TexInfo {
     let mipmap_count = tex.first_image().map(|img| img.mipmap_count()).unwrap_or(0);
+    let (output_width, output_height) = tex.output_dimensions();
 
     TexInfo {
         width: tex.header.image_width,
         height: tex.header.image_height,
         texture_width: tex.header.texture_width,
         texture_height: tex.header.texture_height,
+        output_width,
+        output_height,
         format: format!("{:?}", tex.header.format),
         is_gif: tex.is_gif(),
         is_video: tex.is_video(),
         mipmap_count,
+        mipmaps: tex
+            .first_image()
+            .map(|img| {
+                img.mipmaps
+                    .iter()
+                    .map(|m| MipmapInfo {
+                        width: m.width,
+                        height: m.height,
+                        format: format!("{:?}", m.format),
+                        byte_count: m.original_byte_count,
+                        is_lz4_compressed: m.is_lz4_compressed,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+/// Details of an error thrown to JavaScript.
+#[derive(Debug, PartialEq)]
+struct ErrorInfo {
+    kind: &'static str,
+    message: String,
+    suggestion: Option<&'static str>,
+}
+
+impl From<&repkg::Error> for ErrorInfo {
+    fn from(e: &repkg::Error) -> Self {
+        Self {
+            kind: e.kind(),
+            message: e.to_string(),
+            suggestion: e.suggestion(),
+        }
+    }
+}
+
+/// Convert a repkg error into a JS `Error` with `kind` and `suggestion`
+/// properties, so callers can tell e.g. unsupported input from corrupt data.
+fn js_error(e: repkg::Error) -> JsValue {
+    let info = ErrorInfo::from(&e);
+    wasm_error(info.kind, &info.message, info.suggestion)
+}
+
+/// Build a JS `Error` with `message`, a `kind` code and an optional
+/// `suggestion`.
+fn wasm_error(kind: &str, message: &str, suggestion: Option<&str>) -> JsValue {
+    let error = js_sys::Error::new(message);
+    let _ = js_sys::Reflect::set(&error, &"kind".into(), &kind.into());
+    if let Some(suggestion) = suggestion {
+        let _ = js_sys::Reflect::set(&error, &"suggestion".into(), &suggestion.into());
+    }
+    error.into()
+}
+
+/// Parse an output format name passed from JavaScript.
+fn parse_output_format(format: &str) -> Result<OutputFormat, JsValue> {
+    OutputFormat::parse(format).ok_or_else(|| {
+        wasm_error(
+            "unsupported_output_format",
+            &format!("Unsupported format: {}", format),
+            None,
+        )
+    })
+}
+
+/// Build a texture reader for converting to `format`; DDS output keeps DXT
+/// block data intact.
+fn reader_for(format: OutputFormat) -> TexReader<'static> {
+    if format == OutputFormat::Dds {
+        TexReader::without_decompression()
+    } else {
+        TexReader::new()
+    }
+}
+
+/// Build a converter, using the default quality when JS passes `undefined`.
+fn converter_with_quality(quality: Option<u8>) -> TexToImageConverter<'static> {
+    let converter = TexToImageConverter::new();
+    match quality {
+        Some(quality) => converter.with_quality(quality),
+        None => converter,
     }
 }
 
@@ -513,6 +1068,8 @@ fn format_to_mime(format: OutputFormat) -> String {
         OutputFormat::Bmp => "image/bmp".to_string(),
         OutputFormat::Tiff => "image/tiff".to_string(),
         OutputFormat::Tga => "image/x-targa".to_string(),
+        OutputFormat::Qoi => "image/qoi".to_string(),
+        OutputFormat::Dds => "image/vnd-ms.dds".to_string(),
         OutputFormat::Mp4 => "video/mp4".to_string(),
     }
 }
@@ -530,4 +1087,170 @@ mod tests {
         assert_eq!(format_to_mime(OutputFormat::Png), "image/png");
         assert_eq!(format_to_mime(OutputFormat::Mp4), "video/mp4");
     }
+
+    #[test]
+    fn test_pkg_info_offsets() {
+        let mut package = Package::new("PKGV0019".to_string());
+        package.header_size = 40;
+        package
+            .entries
+            .push(repkg_core::PackageEntry::new("a.json".to_string(), 0, 5));
+        package
+            .entries
+            .push(repkg_core::PackageEntry::new("b.tex".to_string(), 5, 7));
+
+        let info = pkg_to_info(&package);
+        assert_eq!(info.header_size, 40);
+        assert_eq!(info.entries[1].offset, 5);
+        assert_eq!(info.entries[1].size, 7);
+    }
+
+    #[test]
+    fn test_tex_info_output_dimensions() {
+        let mut header = repkg_core::TexHeader::new();
+        header.texture_width = 4096;
+        header.texture_height = 4096;
+        header.image_width = 3840;
+        header.image_height = 2160;
+
+        let info = tex_to_info(&Tex::new(header));
+        assert_eq!((info.output_width, info.output_height), (3840, 2160));
+    }
+
+    #[test]
+    fn test_run_cancellable_after_cancel() {
+        let cancelled = AtomicBool::new(true);
+        assert!(matches!(
+            run_cancellable(&[], OutputFormat::Png, &cancelled),
+            Err(repkg::Error::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn test_tex_info_mipmaps() {
+        let mut tex = Tex::new(repkg_core::TexHeader::new());
+        let mut image = repkg_core::TexImage::new();
+        for (size, compressed) in [(8, true), (4, false)] {
+            let mut mipmap = repkg_core::TexMipmap::new(size, size);
+            mipmap.format = repkg_core::MipmapFormat::RGBA8888;
+            mipmap.is_lz4_compressed = compressed;
+            mipmap.original_byte_count = size * 3;
+            image.mipmaps.push(mipmap);
+        }
+        tex.images_container.images.push(image);
+
+        let info = tex_to_info(&tex);
+        assert_eq!(info.mipmap_count, 2);
+        assert_eq!(info.mipmaps[0].width, 8);
+        assert_eq!(info.mipmaps[0].format, "RGBA8888");
+        assert_eq!(info.mipmaps[0].byte_count, 24);
+        assert!(info.mipmaps[0].is_lz4_compressed);
+        assert!(!info.mipmaps[1].is_lz4_compressed);
+    }
+
+    #[test]
+    fn test_mipmap_location() {
+        let mut tex = Tex::new(repkg_core::TexHeader::new());
+        let mut image = repkg_core::TexImage::new();
+        let mut mipmap = repkg_core::TexMipmap::new(8, 4);
+        mipmap.format = repkg_core::MipmapFormat::CompressedDXT5;
+        mipmap.is_lz4_compressed = true;
+        mipmap.decompressed_bytes_count = 32;
+        mipmap.original_byte_count = 20;
+        mipmap.file_offset = 96;
+        image.mipmaps.push(mipmap);
+        tex.images_container.images.push(image);
+
+        let info = mipmap_location(&tex, 0, 0).unwrap();
+        assert_eq!((info.offset, info.size), (96, 20));
+        assert!(info.is_lz4_compressed);
+        assert_eq!(info.decompressed_size, 32);
+        assert_eq!(info.format, "CompressedDXT5");
+        assert!(mipmap_location(&tex, 0, 1).is_none());
+        assert!(mipmap_location(&tex, 1, 0).is_none());
+    }
+
+    #[test]
+    fn test_converter_with_quality() {
+        assert_eq!(converter_with_quality(None).quality, 90);
+        assert_eq!(converter_with_quality(Some(40)).quality, 40);
+        assert_eq!(converter_with_quality(Some(200)).quality, 100);
+    }
+
+    /// A 2x2 RGBA8888 TEX file.
+    fn tiny_tex() -> Vec<u8> {
+        let mut tex = Vec::new();
+        tex.extend_from_slice(b"TEXV0005\0TEXI0001\0");
+        for value in [0u32, 0, 2, 2, 2, 2, 0] {
+            tex.extend_from_slice(&value.to_le_bytes());
+        }
+        tex.extend_from_slice(b"TEXB0003\0");
+        for value in [1i32, -1] {
+            tex.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [1u32, 2, 2, 0, 0, 16] {
+            tex.extend_from_slice(&value.to_le_bytes());
+        }
+        tex.extend_from_slice(&[255; 16]);
+        tex
+    }
+
+    #[test]
+    fn test_recommended_format_for_tex() {
+        assert_eq!(recommended_format_for_tex(&tiny_tex()).unwrap(), "png");
+    }
+
+    #[test]
+    fn test_convert_batch_item() {
+        let converter = TexToImageConverter::new();
+        let png = convert_batch_item(&tiny_tex(), OutputFormat::Png, &converter).unwrap();
+        assert_eq!(&png.bytes[..4], b"\x89PNG");
+
+        // A bad buffer fails on its own
+        assert!(convert_batch_item(b"not a tex", OutputFormat::Png, &converter).is_err());
+    }
+
+    #[test]
+    fn test_convert_textures() {
+        let entries: Vec<_> = [
+            ("materials/a.tex", tiny_tex()),
+            ("scene.json", b"{}".to_vec()),
+            ("materials/broken.tex", b"oops".to_vec()),
+        ]
+        .into_iter()
+        .map(|(path, bytes)| {
+            let mut entry = repkg_core::PackageEntry::new(path.to_string(), 0, 0);
+            entry.bytes = Some(bytes);
+            entry
+        })
+        .collect();
+        let mut data = Vec::new();
+        repkg::PackageWriter::default()
+            .write_to(&mut data, &entries)
+            .unwrap();
+        let package = PackageReader::new()
+            .read_from(&mut Cursor::new(&data))
+            .unwrap();
+
+        let results = convert_textures(package, OutputFormat::Png, &TexToImageConverter::new());
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "materials/a.tex");
+        assert_eq!(results[0].1.as_ref().unwrap().format, OutputFormat::Png);
+        assert_eq!(results[1].0, "materials/broken.tex");
+        assert!(results[1].1.is_err());
+    }
+
+    #[test]
+    fn test_error_info() {
+        let info = ErrorInfo::from(&repkg::Error::UnsupportedMipmapFormat {
+            format: repkg_core::MipmapFormat::Invalid,
+        });
+        assert_eq!(info.kind, "unsupported_mipmap_format");
+        assert!(info.message.contains("Unsupported mipmap format"));
+        assert!(info.suggestion.is_some());
+
+        let info = ErrorInfo::from(&repkg::Error::invalid_data("truncated"));
+        assert_eq!(info.kind, "invalid_data");
+        assert_eq!(info.suggestion, None);
+    }
 }
@@ -0,0 +1,43 @@
+//! Throughput of repeated conversions with and without a buffer pool.
+//!
+//! Run with `cargo bench -p repkg --features buffer-pool`. Uses the
+//! `tests/fixtures/mask.tex` fixture and skips when it is missing.
+
+use repkg::texture::{BufferPool, OutputFormat, TexReader, TexToImageConverter};
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::time::Instant;
+
+const ITERATIONS: u32 = 50;
+
+fn main() {
+    let tex_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mask.tex");
+    if !tex_path.exists() {
+        eprintln!("Skipping benchmark: fixture not found at {:?}", tex_path);
+        return;
+    }
+    let bytes = fs::read(&tex_path).expect("Failed to read TEX file");
+
+    let run = |reader: TexReader, converter: TexToImageConverter| {
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            let tex = reader
+                .read_from(&mut Cursor::new(&bytes))
+                .expect("Failed to parse TEX");
+            converter
+                .convert(&tex, OutputFormat::Bmp)
+                .expect("Failed to convert");
+        }
+        ITERATIONS as f64 / start.elapsed().as_secs_f64()
+    };
+
+    let pool = BufferPool::new();
+    let allocating = run(TexReader::new(), TexToImageConverter::new());
+    let pooled = run(
+        TexReader::new().with_pool(&pool),
+        TexToImageConverter::new().with_pool(&pool),
+    );
+    println!("allocating: {:.1} conversions/s", allocating);
+    println!("pooled:     {:.1} conversions/s", pooled);
+}
@@ -22,7 +22,7 @@ pub enum Error {
     Io(#[from] std::io::Error),
 
     /// Invalid PKG magic header.
-    #[error("Invalid PKG magic: expected 'PKGV', got '{found}'")]
+    #[error("Invalid PKG magic: expected a prefix such as 'PKGV' followed by a version number, got '{found}'")]
     InvalidPkgMagic { found: String },
 
     /// Invalid TEX magic header.
@@ -40,10 +40,27 @@ pub enum Error {
     #[error("Unsupported mipmap format: {format:?}")]
     UnsupportedMipmapFormat { format: repkg_core::MipmapFormat },
 
+    /// Video track uses a codec that cannot be decoded.
+    #[error("Unsupported video codec: {codec}")]
+    UnsupportedVideoCodec { codec: String },
+
     /// LZ4 decompression failed.
     #[error("LZ4 decompression failed: {message}")]
     Lz4Decompression { message: String },
 
+    /// LZ4 data decompressed to a different size than the mipmap declares.
+    ///
+    /// `actual` is `None` when the data is larger than `expected`, because
+    /// decompression stops once the declared size is exceeded.
+    #[error(
+        "LZ4 decompressed size mismatch: expected {expected} bytes, got {}",
+        describe_actual_size(.actual)
+    )]
+    Lz4SizeMismatch {
+        expected: usize,
+        actual: Option<usize>,
+    },
+
     /// DXT decompression failed.
     #[error("DXT decompression failed: {details}")]
     DxtDecompression { details: String },
@@ -64,12 +81,41 @@ pub enum Error {
     #[error("Unexpected end of stream at position {position}")]
     UnexpectedEof { position: u64 },
 
+    /// The operation was cancelled through its cancel flag.
+    #[error("Operation was cancelled")]
+    Cancelled,
+
     /// String encoding error.
     #[error("String encoding error: {0}")]
     StringEncoding(#[from] std::string::FromUtf8Error),
 }
 
 impl Error {
+    /// Get a stable, machine-readable name for the kind of error.
+    ///
+    /// Bindings expose this so callers can tell e.g. unsupported input from
+    /// corrupt data without parsing messages.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::FileRead { .. } => "file_read",
+            Error::Io(_) => "io",
+            Error::InvalidPkgMagic { .. } => "invalid_pkg_magic",
+            Error::InvalidTexMagic { .. } => "invalid_tex_magic",
+            Error::UnsupportedContainerVersion { .. } => "unsupported_container_version",
+            Error::UnsupportedMipmapFormat { .. } => "unsupported_mipmap_format",
+            Error::UnsupportedVideoCodec { .. } => "unsupported_video_codec",
+            Error::Lz4Decompression { .. } => "lz4_decompression",
+            Error::Lz4SizeMismatch { .. } => "lz4_size_mismatch",
+            Error::DxtDecompression { .. } => "dxt_decompression",
+            Error::ImageConversion(_) => "image_conversion",
+            Error::InvalidData { .. } => "invalid_data",
+            Error::SafetyLimit { .. } => "safety_limit",
+            Error::UnexpectedEof { .. } => "unexpected_eof",
+            Error::Cancelled => "cancelled",
+            Error::StringEncoding(_) => "string_encoding",
+        }
+    }
+
     /// Get a helpful suggestion for recovering from this error.
     pub fn suggestion(&self) -> Option<&'static str> {
         match self {
@@ -85,7 +131,12 @@ impl Error {
             Error::UnsupportedMipmapFormat { .. } => {
                 Some("Try using --format png or --no-convert to extract raw data.")
             }
-            Error::Lz4Decompression { .. } | Error::DxtDecompression { .. } => Some(
+            Error::UnsupportedVideoCodec { .. } => Some(
+                "Only Motion JPEG and PNG video tracks can be decoded. Extract the MP4 and use a video tool such as ffmpeg.",
+            ),
+            Error::Lz4Decompression { .. }
+            | Error::Lz4SizeMismatch { .. }
+            | Error::DxtDecompression { .. } => Some(
                 "The file may be corrupted. Try re-downloading from Wallpaper Engine workshop.",
             ),
             Error::ImageConversion(_) => Some("Try a different output format with --format."),
@@ -108,3 +159,11 @@ impl Error {
         }
     }
 }
+
+/// Describe a decompressed size for [`Error::Lz4SizeMismatch`].
+fn describe_actual_size(actual: &Option<usize>) -> String {
+    match actual {
+        Some(actual) => format!("{} bytes", actual),
+        None => "more".to_string(),
+    }
+}
@@ -8,5 +8,5 @@ pub mod package;
 pub mod texture;
 
 pub use error::{Error, Result};
-pub use package::PackageReader;
+pub use package::{PackageReader, PackageWriter};
 pub use texture::{TexReader, TexToImageConverter};
@@ -1,5 +1,11 @@
-//! PKG package reading functionality.
+//! PKG package reading and writing functionality.
 
 mod reader;
+mod scene;
+#[cfg(feature = "tokio")]
+mod stream;
+mod writer;
 
-pub use reader::PackageReader;
+pub use reader::{PackageEntries, PackageReader};
+pub use scene::SceneReferences;
+pub use writer::PackageWriter;
@@ -1,13 +1,13 @@
 //! PKG package reader implementation.
 
 use byteorder::{LittleEndian, ReadBytesExt};
-use repkg_core::{EntryType, Package, PackageEntry};
+use repkg_core::{EntryType, Package, PackageEntry, PackageMagic};
 use std::io::{Read, Seek, SeekFrom};
 
 use crate::error::{Error, Result};
 
 /// Safety limits to prevent malicious files from causing issues.
-const MAX_MAGIC_LENGTH: u32 = 64;
+pub(super) const MAX_MAGIC_LENGTH: u32 = 64;
 const MAX_PATH_LENGTH: u32 = 4096;
 const MAX_ENTRY_COUNT: u32 = 100_000;
 
@@ -16,6 +16,10 @@ const MAX_ENTRY_COUNT: u32 = 100_000;
 pub struct PackageReader {
     /// Whether to read entry bytes (can be disabled for info-only operations)
     pub read_entry_bytes: bool,
+    /// Maximum number of entries in the entry table
+    pub max_entries: u32,
+    /// Maximum length of an entry path in bytes
+    pub max_path_length: u32,
 }
 
 impl PackageReader {
@@ -23,6 +27,8 @@ impl PackageReader {
     pub fn new() -> Self {
         Self {
             read_entry_bytes: true,
+            max_entries: MAX_ENTRY_COUNT,
+            max_path_length: MAX_PATH_LENGTH,
         }
     }
 
@@ -30,32 +36,125 @@ impl PackageReader {
     pub fn info_only() -> Self {
         Self {
             read_entry_bytes: false,
+            max_entries: MAX_ENTRY_COUNT,
+            max_path_length: MAX_PATH_LENGTH,
         }
     }
 
+    /// Set the maximum number of entries accepted in the entry table.
+    pub fn with_max_entries(mut self, max: u32) -> Self {
+        self.max_entries = max;
+        self
+    }
+
+    /// Set the maximum accepted entry path length in bytes.
+    pub fn with_max_path_length(mut self, max: u32) -> Self {
+        self.max_path_length = max;
+        self
+    }
+
     /// Read a PKG file from a reader.
     pub fn read_from<R: Read + Seek>(&self, reader: &mut R) -> Result<Package> {
+        let (mut package, data_start) = self.read_entry_table(reader)?;
+
+        // Read entry bytes if requested
+        if self.read_entry_bytes {
+            // Report a truncated data section before reading anything
+            let stream_len = stream_len(reader)?;
+            for entry in &package.entries {
+                check_entry_bounds(entry, data_start, stream_len)?;
+            }
+
+            for entry in &mut package.entries {
+                entry.bytes = Some(read_entry_data(reader, data_start, stream_len, entry)?);
+            }
+        }
+
+        Ok(package)
+    }
+
+    /// Read only the package index (magic and entry table).
+    ///
+    /// Entries are returned with `bytes: None` regardless of `read_entry_bytes`;
+    /// use [`load_entry`](Self::load_entry) to fetch individual entries on demand.
+    pub fn read_index<R: Read + Seek>(&self, reader: &mut R) -> Result<Package> {
+        self.read_entry_table(reader).map(|(package, _)| package)
+    }
+
+    /// Load a single entry's bytes from a package previously read with
+    /// [`read_index`](Self::read_index).
+    ///
+    /// `header_size` is the package's `header_size`. The package is assumed to
+    /// start at the beginning of the stream.
+    pub fn load_entry<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        header_size: u32,
+        entry: &mut PackageEntry,
+    ) -> Result<()> {
+        let stream_len = stream_len(reader)?;
+        entry.bytes = Some(read_entry_data(
+            reader,
+            header_size as u64,
+            stream_len,
+            entry,
+        )?);
+        Ok(())
+    }
+
+    /// Stream entries one at a time, each with its bytes loaded.
+    ///
+    /// The entry table is read up front; each call to `next` then seeks to and
+    /// reads a single entry, so only one entry's data is held in memory at a
+    /// time. I/O errors are yielded as `Err` items.
+    pub fn entries_iter<'a, R: Read + Seek>(
+        &self,
+        reader: &'a mut R,
+    ) -> Result<PackageEntries<'a, R>> {
+        let (package, data_start) = self.read_entry_table(reader)?;
+        let stream_len = stream_len(reader)?;
+        Ok(PackageEntries {
+            reader,
+            data_start,
+            stream_len,
+            magic: package.magic,
+            entries: package.entries.into_iter(),
+        })
+    }
+
+    /// Read the magic, entry count and entry table.
+    ///
+    /// Returns the package (without entry bytes) and the absolute stream
+    /// position where the data section starts.
+    fn read_entry_table<R: Read + Seek>(&self, reader: &mut R) -> Result<(Package, u64)> {
         let package_start = reader.stream_position()?;
 
         // Read magic string
         let magic = read_length_prefixed_string(reader, MAX_MAGIC_LENGTH)?;
-        if !magic.starts_with("PKGV") {
+        let Some(parsed_magic) = PackageMagic::parse(&magic) else {
             return Err(Error::InvalidPkgMagic { found: magic });
+        };
+        if !parsed_magic.recognized {
+            log::warn!(
+                "Unrecognized package magic '{}', parsing it as PKGV{:04}",
+                magic,
+                parsed_magic.version
+            );
         }
 
         // Read entry count
         let entry_count = reader.read_u32::<LittleEndian>()?;
-        if entry_count > MAX_ENTRY_COUNT {
+        if entry_count > self.max_entries {
             return Err(Error::safety_limit(format!(
                 "Entry count {} exceeds maximum {}",
-                entry_count, MAX_ENTRY_COUNT
+                entry_count, self.max_entries
             )));
         }
 
         // Read entries
         let mut entries = Vec::with_capacity(entry_count as usize);
         for _ in 0..entry_count {
-            let full_path = read_length_prefixed_string(reader, MAX_PATH_LENGTH)?;
+            let full_path = read_length_prefixed_string(reader, self.max_path_length)?;
             let offset = reader.read_u32::<LittleEndian>()?;
             let length = reader.read_u32::<LittleEndian>()?;
 
@@ -72,21 +171,16 @@ impl PackageReader {
         let data_start = reader.stream_position()?;
         let header_size = (data_start - package_start) as u32;
 
-        // Read entry bytes if requested
-        if self.read_entry_bytes {
-            for entry in &mut entries {
-                reader.seek(SeekFrom::Start(data_start + entry.offset as u64))?;
-                let mut bytes = vec![0u8; entry.length as usize];
-                reader.read_exact(&mut bytes)?;
-                entry.bytes = Some(bytes);
-            }
-        }
-
-        Ok(Package {
-            magic,
-            header_size,
-            entries,
-        })
+        Ok((
+            Package {
+                magic,
+                version: parsed_magic.version,
+                unrecognized_magic: !parsed_magic.recognized,
+                header_size,
+                entries,
+            },
+            data_start,
+        ))
     }
 }
 
@@ -96,6 +190,82 @@ impl Default for PackageReader {
     }
 }
 
+/// Streaming iterator over package entries, created by
+/// [`PackageReader::entries_iter`].
+pub struct PackageEntries<'a, R> {
+    reader: &'a mut R,
+    data_start: u64,
+    stream_len: u64,
+    magic: String,
+    entries: std::vec::IntoIter<PackageEntry>,
+}
+
+impl<R> PackageEntries<'_, R> {
+    /// Magic string of the package being read.
+    pub fn magic(&self) -> &str {
+        &self.magic
+    }
+}
+
+impl<R: Read + Seek> Iterator for PackageEntries<'_, R> {
+    type Item = Result<PackageEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut entry = self.entries.next()?;
+        Some(
+            read_entry_data(self.reader, self.data_start, self.stream_len, &entry).map(|bytes| {
+                entry.bytes = Some(bytes);
+                entry
+            }),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<R: Read + Seek> ExactSizeIterator for PackageEntries<'_, R> {}
+
+/// Read a single entry's data given the absolute start of the data section
+/// and the total stream length.
+fn read_entry_data<R: Read + Seek>(
+    reader: &mut R,
+    data_start: u64,
+    stream_len: u64,
+    entry: &PackageEntry,
+) -> Result<Vec<u8>> {
+    check_entry_bounds(entry, data_start, stream_len)?;
+    reader.seek(SeekFrom::Start(data_start + entry.offset as u64))?;
+    let mut bytes = vec![0u8; entry.length as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Get the total stream length, restoring the current position.
+fn stream_len<R: Seek>(reader: &mut R) -> Result<u64> {
+    let position = reader.stream_position()?;
+    let len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(position))?;
+    Ok(len)
+}
+
+/// Check that an entry's data lies within a stream of `stream_len` bytes.
+fn check_entry_bounds(entry: &PackageEntry, data_start: u64, stream_len: u64) -> Result<()> {
+    let end = data_start + entry.offset as u64 + entry.length as u64;
+    if end > stream_len {
+        return Err(Error::invalid_data(format!(
+            "Entry '{}' extends past the end of the package: its data ends at byte {} \
+             but the file is {} bytes ({} bytes missing)",
+            entry.full_path,
+            end,
+            stream_len,
+            end - stream_len
+        )));
+    }
+    Ok(())
+}
+
 /// Read a length-prefixed string (i32 length + UTF-8 bytes).
 fn read_length_prefixed_string<R: Read>(reader: &mut R, max_length: u32) -> Result<String> {
     let length = reader.read_u32::<LittleEndian>()?;
@@ -132,4 +302,152 @@ mod tests {
         let result = read_length_prefixed_string(&mut cursor, 100);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_entries_iter() {
+        let mut entries = Vec::new();
+        for (path, bytes) in [("a.json", &b"{}"[..]), ("b.tex", &b"tex data"[..])] {
+            let mut entry = PackageEntry::new(path.to_string(), 0, 0);
+            entry.bytes = Some(bytes.to_vec());
+            entries.push(entry);
+        }
+        let mut data = Vec::new();
+        crate::package::PackageWriter::default()
+            .write_to(&mut data, &entries)
+            .unwrap();
+
+        let mut cursor = Cursor::new(&data);
+        let iter = PackageReader::new().entries_iter(&mut cursor).unwrap();
+        assert_eq!(iter.magic(), "PKGV0019");
+        assert_eq!(iter.len(), 2);
+        let streamed: Vec<_> = iter.collect::<Result<_>>().unwrap();
+        for (written, read) in entries.iter().zip(&streamed) {
+            assert_eq!(written.full_path, read.full_path);
+            assert_eq!(written.bytes, read.bytes);
+        }
+
+        // A truncated data section surfaces as an error item
+        let truncated = &data[..data.len() - 4];
+        let mut cursor = Cursor::new(truncated);
+        let results: Vec<_> = PackageReader::new()
+            .entries_iter(&mut cursor)
+            .unwrap()
+            .collect();
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn test_truncated_data_section() {
+        let mut entry = PackageEntry::new("materials/a.tex".to_string(), 0, 0);
+        entry.bytes = Some(vec![1; 16]);
+        let mut data = Vec::new();
+        crate::package::PackageWriter::default()
+            .write_to(&mut data, &[entry])
+            .unwrap();
+        data.truncate(data.len() - 6);
+
+        let err = PackageReader::new()
+            .read_from(&mut Cursor::new(&data))
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidData { .. }));
+        let message = err.to_string();
+        assert!(message.contains("materials/a.tex"), "{}", message);
+        assert!(message.contains("6 bytes missing"), "{}", message);
+
+        // The index alone is still readable
+        let package = PackageReader::new()
+            .read_index(&mut Cursor::new(&data))
+            .unwrap();
+        assert_eq!(package.entries.len(), 1);
+    }
+
+    #[test]
+    fn test_magic_versions() {
+        let write = |magic: &str| {
+            let mut entry = PackageEntry::new("scene.json".to_string(), 0, 0);
+            entry.bytes = Some(b"{}".to_vec());
+            let mut data = Vec::new();
+            crate::package::PackageWriter::new(magic)
+                .write_to(&mut data, &[entry])
+                .unwrap();
+            data
+        };
+        let read = |data: &[u8]| PackageReader::new().read_from(&mut Cursor::new(data));
+
+        let package = read(&write("PKGV0019")).unwrap();
+        assert_eq!(package.version, 19);
+        assert!(!package.unrecognized_magic);
+
+        // A newer version of the known format
+        let package = read(&write("PKGV0027")).unwrap();
+        assert_eq!(package.version, 27);
+        assert!(!package.unrecognized_magic);
+
+        // An unknown but well-formed prefix is flagged, not rejected
+        let package = read(&write("PKGX0003")).unwrap();
+        assert_eq!(package.version, 3);
+        assert!(package.unrecognized_magic);
+        assert_eq!(package.entry_bytes("scene.json"), Some(&b"{}"[..]));
+
+        let result = read(&write("NOTAPKG"));
+        assert!(matches!(result, Err(Error::InvalidPkgMagic { .. })));
+    }
+
+    #[test]
+    fn test_configurable_limits() {
+        let entries: Vec<_> = ["a.json", "materials/long_name.tex"]
+            .into_iter()
+            .map(|path| {
+                let mut entry = PackageEntry::new(path.to_string(), 0, 0);
+                entry.bytes = Some(Vec::new());
+                entry
+            })
+            .collect();
+        let mut data = Vec::new();
+        crate::package::PackageWriter::default()
+            .write_to(&mut data, &entries)
+            .unwrap();
+
+        let result = PackageReader::new()
+            .with_max_entries(1)
+            .read_from(&mut Cursor::new(&data));
+        assert!(matches!(result, Err(Error::SafetyLimit { .. })));
+
+        let result = PackageReader::new()
+            .with_max_path_length(8)
+            .read_from(&mut Cursor::new(&data));
+        assert!(matches!(result, Err(Error::SafetyLimit { .. })));
+
+        let package = PackageReader::new()
+            .with_max_entries(2)
+            .read_from(&mut Cursor::new(&data))
+            .unwrap();
+        assert_eq!(package.entries.len(), 2);
+    }
+
+    #[test]
+    fn test_read_index_and_load_entry() {
+        let mut entries = Vec::new();
+        for (path, bytes) in [("a.json", &b"{}"[..]), ("b.tex", &b"tex data"[..])] {
+            let mut entry = PackageEntry::new(path.to_string(), 0, 0);
+            entry.bytes = Some(bytes.to_vec());
+            entries.push(entry);
+        }
+        let mut data = Vec::new();
+        crate::package::PackageWriter::default()
+            .write_to(&mut data, &entries)
+            .unwrap();
+
+        let reader = PackageReader::new();
+        let mut cursor = Cursor::new(&data);
+        let mut package = reader.read_index(&mut cursor).unwrap();
+        assert!(package.entries.iter().all(|e| e.bytes.is_none()));
+
+        reader
+            .load_entry(&mut cursor, package.header_size, &mut package.entries[1])
+            .unwrap();
+        assert_eq!(package.entries[1].bytes.as_deref(), Some(&b"tex data"[..]));
+        assert!(package.entries[0].bytes.is_none());
+    }
 }
@@ -0,0 +1,116 @@
+//! Cross-checking `scene.json` asset references against package entries.
+
+use repkg_core::{EntryType, Package};
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::error::{Error, Result};
+
+/// Validation of the assets a Wallpaper Engine scene refers to.
+///
+/// Implemented for [`Package`], which lives in `repkg-core` and so cannot
+/// carry JSON parsing itself.
+pub trait SceneReferences {
+    /// Paths referenced by `scene_json` that have no entry in the package.
+    ///
+    /// Every string value in the document that ends in a known entry
+    /// extension (`.tex`, `.json`, shader stages, models, audio, fonts) is
+    /// treated as a path. Paths are compared case-insensitively, with
+    /// backslashes read as separators and a leading `./` or `/` ignored.
+    /// Missing paths are returned as written in the scene, once each, in
+    /// document order.
+    fn missing_references(&self, scene_json: &str) -> Result<Vec<String>>;
+}
+
+impl SceneReferences for Package {
+    fn missing_references(&self, scene_json: &str) -> Result<Vec<String>> {
+        let scene: Value = serde_json::from_str(scene_json)
+            .map_err(|e| Error::invalid_data(format!("Invalid scene JSON: {}", e)))?;
+
+        let entries: HashSet<String> = self
+            .entries
+            .iter()
+            .map(|entry| normalize_path(&entry.full_path))
+            .collect();
+
+        let mut references = Vec::new();
+        collect_references(&scene, &mut references);
+
+        let mut seen = HashSet::new();
+        Ok(references
+            .into_iter()
+            .filter(|path| {
+                let normalized = normalize_path(path);
+                !entries.contains(&normalized) && seen.insert(normalized)
+            })
+            .map(str::to_string)
+            .collect())
+    }
+}
+
+/// Append every path-like string in `value` to `references`.
+fn collect_references<'a>(value: &'a Value, references: &mut Vec<&'a str>) {
+    match value {
+        Value::String(s) if is_asset_path(s) => references.push(s),
+        Value::Array(values) => values
+            .iter()
+            .for_each(|value| collect_references(value, references)),
+        Value::Object(map) => map
+            .values()
+            .for_each(|value| collect_references(value, references)),
+        _ => {}
+    }
+}
+
+/// Whether `s` looks like a path to a package asset.
+fn is_asset_path(s: &str) -> bool {
+    !s.contains("://")
+        && !s.chars().any(char::is_whitespace)
+        && s.rsplit_once('.').is_some_and(|(stem, _)| !stem.is_empty())
+        && EntryType::from_path(s) != EntryType::Other
+}
+
+/// Normalize a path for comparison with entry paths.
+fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let path = path.trim_start_matches("./").trim_start_matches('/');
+    path.to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use repkg_core::PackageEntry;
+
+    fn package(paths: &[&str]) -> Package {
+        let mut package = Package::new("PKGV0019".to_string());
+        package.entries = paths
+            .iter()
+            .map(|path| PackageEntry::new(path.to_string(), 0, 0))
+            .collect();
+        package
+    }
+
+    #[test]
+    fn test_missing_references() {
+        let package = package(&["scene.json", "materials/a.json", "materials/a.tex"]);
+        let scene = r#"{
+            "camera": {"center": "0 0 -1"},
+            "general": {"skybox": "Materials/A.json"},
+            "objects": [
+                {"name": "background.png", "image": "models/background.json"},
+                {"image": "./materials/a.json", "sound": ["sounds\\rain.mp3"]},
+                {"image": "models/background.json", "url": "https://example.com/x.json"},
+                {"image": ".json", "version": 3}
+            ]
+        }"#;
+
+        let missing = package.missing_references(scene).unwrap();
+        assert_eq!(missing, ["models/background.json", "sounds\\rain.mp3"]);
+    }
+
+    #[test]
+    fn test_missing_references_invalid_json() {
+        assert!(package(&[]).missing_references("{").is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! Async streaming of package entries (requires the `tokio` feature).
+
+use repkg_core::{EntryType, Package, PackageEntry, PackageMagic};
+use std::io::SeekFrom;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, Take};
+
+use super::reader::MAX_MAGIC_LENGTH;
+use super::PackageReader;
+use crate::error::{Error, Result};
+
+impl PackageReader {
+    /// Read only the package index from an async source.
+    ///
+    /// Entries are returned with `bytes: None`.
+    pub async fn read_index_async<R: AsyncRead + AsyncSeek + Unpin>(
+        &self,
+        reader: &mut R,
+    ) -> Result<Package> {
+        let package_start = reader.stream_position().await?;
+
+        let magic = read_length_prefixed_string(reader, MAX_MAGIC_LENGTH).await?;
+        let Some(parsed_magic) = PackageMagic::parse(&magic) else {
+            return Err(Error::InvalidPkgMagic { found: magic });
+        };
+        if !parsed_magic.recognized {
+            log::warn!(
+                "Unrecognized package magic '{}', parsing it as PKGV{:04}",
+                magic,
+                parsed_magic.version
+            );
+        }
+
+        let entry_count = reader.read_u32_le().await?;
+        if entry_count > self.max_entries {
+            return Err(Error::safety_limit(format!(
+                "Entry count {} exceeds maximum {}",
+                entry_count, self.max_entries
+            )));
+        }
+
+        let mut entries = Vec::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            let full_path = read_length_prefixed_string(reader, self.max_path_length).await?;
+            let offset = reader.read_u32_le().await?;
+            let length = reader.read_u32_le().await?;
+
+            entries.push(PackageEntry {
+                entry_type: EntryType::from_path(&full_path),
+                full_path,
+                offset,
+                length,
+                bytes: None,
+            });
+        }
+
+        let header_size = (reader.stream_position().await? - package_start) as u32;
+
+        Ok(Package {
+            magic,
+            version: parsed_magic.version,
+            unrecognized_magic: !parsed_magic.recognized,
+            header_size,
+            entries,
+        })
+    }
+
+    /// Stream a single entry's bytes from an async source.
+    ///
+    /// The package header and entry table are parsed eagerly; the entry's data
+    /// is then read lazily from `reader` as the returned stream is polled, so
+    /// nothing beyond the caller's read buffer is held in memory. Backpressure
+    /// is natural: if the consumer stops reading, no further data is pulled
+    /// from the source. The stream ends after exactly the entry's length. The
+    /// package is assumed to start at the beginning of the stream.
+    pub async fn stream_entry<'a, R: AsyncRead + AsyncSeek + Unpin>(
+        &self,
+        reader: &'a mut R,
+        path: &str,
+    ) -> Result<Take<&'a mut R>> {
+        let package = self.read_index_async(reader).await?;
+        let entry = package
+            .entries
+            .iter()
+            .find(|entry| entry.full_path == path)
+            .ok_or_else(|| Error::invalid_data(format!("Entry '{}' not found", path)))?;
+
+        reader
+            .seek(SeekFrom::Start(
+                package.header_size as u64 + entry.offset as u64,
+            ))
+            .await?;
+        Ok(reader.take(entry.length as u64))
+    }
+}
+
+/// Read a length-prefixed string (u32 length + UTF-8 bytes).
+async fn read_length_prefixed_string<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    max_length: u32,
+) -> Result<String> {
+    let length = reader.read_u32_le().await?;
+    if length > max_length {
+        return Err(Error::safety_limit(format!(
+            "String length {} exceeds maximum {}",
+            length, max_length
+        )));
+    }
+
+    let mut bytes = vec![0u8; length as usize];
+    reader.read_exact(&mut bytes).await?;
+
+    String::from_utf8(bytes).map_err(Error::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::PackageWriter;
+    use std::io::Cursor;
+
+    #[tokio::test]
+    async fn test_stream_entry() {
+        let mut entries = Vec::new();
+        for (path, bytes) in [("a.json", &b"{}"[..]), ("b.tex", &b"tex data"[..])] {
+            let mut entry = PackageEntry::new(path.to_string(), 0, 0);
+            entry.bytes = Some(bytes.to_vec());
+            entries.push(entry);
+        }
+        let mut data = Vec::new();
+        PackageWriter::default()
+            .write_to(&mut data, &entries)
+            .unwrap();
+
+        let reader = PackageReader::new();
+        let mut cursor = Cursor::new(data.clone());
+        let mut stream = reader.stream_entry(&mut cursor, "b.tex").await.unwrap();
+        let mut streamed = Vec::new();
+        stream.read_to_end(&mut streamed).await.unwrap();
+        assert_eq!(streamed, b"tex data");
+
+        let mut cursor = Cursor::new(data);
+        assert!(reader.stream_entry(&mut cursor, "missing").await.is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! PKG package writer implementation.
+
+use byteorder::{LittleEndian, WriteBytesExt};
+use repkg_core::PackageEntry;
+use std::io::Write;
+
+use crate::error::{Error, Result};
+
+/// Writer for Wallpaper Engine PKG files.
+#[derive(Debug, Clone)]
+pub struct PackageWriter {
+    /// Magic string written at the start of the package (e.g., "PKGV0019")
+    pub magic: String,
+}
+
+impl PackageWriter {
+    /// Create a new package writer with the given magic string.
+    pub fn new(magic: impl Into<String>) -> Self {
+        Self {
+            magic: magic.into(),
+        }
+    }
+
+    /// Write a PKG file containing the given entries.
+    ///
+    /// Entries are written in the order given. Offsets are recomputed from that
+    /// order relative to the start of the data section, so the `offset` and
+    /// `length` fields stored on the entries are ignored. Every entry must have
+    /// its `bytes` populated.
+    pub fn write_to<W: Write>(&self, writer: &mut W, entries: &[PackageEntry]) -> Result<()> {
+        let entry_count = u32::try_from(entries.len())
+            .map_err(|_| Error::invalid_data("Too many entries for a PKG file"))?;
+
+        // Collect entry data up front so missing bytes fail before anything is written
+        let mut data: Vec<&[u8]> = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let bytes = entry.bytes.as_deref().ok_or_else(|| {
+                Error::invalid_data(format!("Entry '{}' has no data", entry.full_path))
+            })?;
+            data.push(bytes);
+        }
+
+        // Write magic string and entry count
+        write_length_prefixed_string(writer, &self.magic)?;
+        writer.write_u32::<LittleEndian>(entry_count)?;
+
+        // Write entry table with offsets relative to the data section
+        let mut offset: u32 = 0;
+        for (entry, bytes) in entries.iter().zip(&data) {
+            let length = u32::try_from(bytes.len()).map_err(|_| {
+                Error::invalid_data(format!("Entry '{}' is too large", entry.full_path))
+            })?;
+
+            write_length_prefixed_string(writer, &entry.full_path)?;
+            writer.write_u32::<LittleEndian>(offset)?;
+            writer.write_u32::<LittleEndian>(length)?;
+
+            offset = offset
+                .checked_add(length)
+                .ok_or_else(|| Error::invalid_data("Package data section exceeds 4 GiB"))?;
+        }
+
+        // Write data section
+        for bytes in data {
+            writer.write_all(bytes)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for PackageWriter {
+    fn default() -> Self {
+        Self::new("PKGV0019")
+    }
+}
+
+/// Write a length-prefixed string (u32 length + UTF-8 bytes).
+fn write_length_prefixed_string<W: Write>(writer: &mut W, s: &str) -> Result<()> {
+    let length =
+        u32::try_from(s.len()).map_err(|_| Error::invalid_data("String too long to write"))?;
+    writer.write_u32::<LittleEndian>(length)?;
+    writer.write_all(s.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::PackageReader;
+    use std::io::Cursor;
+
+    fn entry(path: &str, bytes: &[u8]) -> PackageEntry {
+        let mut entry = PackageEntry::new(path.to_string(), 0, 0);
+        entry.bytes = Some(bytes.to_vec());
+        entry
+    }
+
+    #[test]
+    fn test_write_then_read() {
+        let entries = vec![
+            entry("scene.json", b"{}"),
+            entry("materials/a.tex", &[1, 2, 3, 4, 5]),
+            entry("empty.txt", b""),
+        ];
+
+        let mut output = Vec::new();
+        PackageWriter::default()
+            .write_to(&mut output, &entries)
+            .unwrap();
+
+        let package = PackageReader::new()
+            .read_from(&mut Cursor::new(&output))
+            .unwrap();
+
+        assert_eq!(package.magic, "PKGV0019");
+        assert_eq!(package.entries.len(), 3);
+        assert_eq!(package.entries[1].offset, 2);
+        for (written, read) in entries.iter().zip(&package.entries) {
+            assert_eq!(written.full_path, read.full_path);
+            assert_eq!(written.bytes, read.bytes);
+        }
+    }
+
+    #[test]
+    fn test_sort_then_write_preserves_entries() {
+        let mut package = repkg_core::Package::new("PKGV0019".to_string());
+        package.entries = vec![
+            entry("shaders/effect.frag", b"frag"),
+            entry("scene.json", b"{}"),
+            entry("materials/a.tex", &[9; 16]),
+        ];
+        package.sort_entries(repkg_core::SortKey::Directory);
+
+        let mut output = Vec::new();
+        PackageWriter::new(package.magic.clone())
+            .write_to(&mut output, &package.entries)
+            .unwrap();
+        let reread = PackageReader::new()
+            .read_from(&mut Cursor::new(&output))
+            .unwrap();
+
+        assert_eq!(reread.entries.len(), 3);
+        for (sorted, read) in package.entries.iter().zip(&reread.entries) {
+            assert_eq!(sorted.full_path, read.full_path);
+            assert_eq!(sorted.bytes, read.bytes);
+        }
+    }
+
+    #[test]
+    fn test_write_entry_without_bytes() {
+        let entries = vec![PackageEntry::new("scene.json".to_string(), 0, 2)];
+        let mut output = Vec::new();
+        let result = PackageWriter::default().write_to(&mut output, &entries);
+        assert!(result.is_err());
+        assert!(output.is_empty());
+    }
+}
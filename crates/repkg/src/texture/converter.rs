@@ -1,16 +1,42 @@
 //! TEX to image conversion.
 
 use image::{
-    codecs::gif::{GifEncoder, Repeat},
+    codecs::gif::{GifDecoder, GifEncoder, Repeat},
+    codecs::png::{CompressionType, FilterType as PngFilterType, PngEncoder},
     imageops::FilterType,
-    DynamicImage, Frame, ImageBuffer, ImageFormat, Luma, LumaA, RgbaImage,
+    AnimationDecoder, DynamicImage, Frame, ImageBuffer, ImageFormat, Luma, LumaA, Rgba, RgbaImage,
 };
-use repkg_core::{MipmapFormat, Tex, TexMipmap};
+use repkg_core::{MipmapFormat, Tex, TexFrameInfo, TexHeader, TexImageContainerVersion, TexMipmap};
+use std::borrow::Cow;
+use std::fmt;
 use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
+use super::dds::{self, DdsLevel};
+use super::mp4;
+use super::transform::{
+    apply_color_lut, composite_checkerboard, flatten_onto, invert_green, premultiply_alpha,
+    reconstruct_normal_z, rotate_bilinear, swizzle_channels, tile_images, tone_lut,
+};
+use super::webp;
+#[cfg(feature = "buffer-pool")]
+use super::BufferPool;
+use super::MipmapDecompressor;
 use crate::error::{Error, Result};
 
+/// Background used when flattening transparency without a configured color.
+const DEFAULT_BACKGROUND: [u8; 4] = [255, 255, 255, 255];
+
+/// Checker size used by `debug_comparison` when no checkerboard is configured.
+const DEFAULT_CHECKER_SIZE: u32 = 8;
+
+/// Slowest frame rate accepted by [`TexToImageConverter::with_gif_fps`].
+pub const MIN_GIF_FPS: f32 = 0.01;
+
+/// Fastest frame rate accepted by [`TexToImageConverter::with_gif_fps`].
+pub const MAX_GIF_FPS: f32 = 100.0;
+
 /// Output format for converted images.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum OutputFormat {
@@ -28,6 +54,10 @@ pub enum OutputFormat {
     Tiff,
     /// TGA/Targa
     Tga,
+    /// QOI (lossless, fast to encode and decode)
+    Qoi,
+    /// DDS (GPU-ready, keeps DXT block data when available)
+    Dds,
     /// MP4 video (passthrough only)
     Mp4,
 }
@@ -43,6 +73,8 @@ impl OutputFormat {
             OutputFormat::Bmp => "bmp",
             OutputFormat::Tiff => "tiff",
             OutputFormat::Tga => "tga",
+            OutputFormat::Qoi => "qoi",
+            OutputFormat::Dds => "dds",
             OutputFormat::Mp4 => "mp4",
         }
     }
@@ -57,11 +89,27 @@ impl OutputFormat {
             "bmp" => Some(OutputFormat::Bmp),
             "tiff" | "tif" => Some(OutputFormat::Tiff),
             "tga" | "targa" => Some(OutputFormat::Tga),
+            "qoi" => Some(OutputFormat::Qoi),
+            "dds" => Some(OutputFormat::Dds),
             "mp4" => Some(OutputFormat::Mp4),
             _ => None,
         }
     }
 
+    /// Whether the format can store an alpha channel.
+    pub fn supports_alpha(&self) -> bool {
+        !matches!(self, OutputFormat::Jpeg | OutputFormat::Mp4)
+    }
+
+    /// Whether the format can store 16 bits per channel.
+    ///
+    /// High-bit-depth sources, such as 16-bit embedded PNGs or TIFFs, keep
+    /// their precision in these formats and are reduced to 8 bits for the
+    /// others. Pixel adjustments other than resizing work on 8-bit data.
+    pub fn supports_16_bit(&self) -> bool {
+        matches!(self, OutputFormat::Png | OutputFormat::Tiff)
+    }
+
     /// Get all available formats.
     pub fn all() -> &'static [OutputFormat] {
         &[
@@ -72,6 +120,8 @@ impl OutputFormat {
             OutputFormat::Bmp,
             OutputFormat::Tiff,
             OutputFormat::Tga,
+            OutputFormat::Qoi,
+            OutputFormat::Dds,
         ]
     }
 }
@@ -89,19 +139,262 @@ pub struct ConversionResult {
     pub bytes: Vec<u8>,
     /// The format of the converted image.
     pub format: OutputFormat,
+    /// Width of the output in pixels, after cropping and resizing.
+    pub width: u32,
+    /// Height of the output in pixels, after cropping and resizing.
+    pub height: u32,
+}
+
+/// Direction in which filmstrip frames are laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilmstripLayout {
+    /// Frames side by side in a single row
+    Horizontal,
+    /// Frames stacked in a single column
+    Vertical,
+}
+
+impl FilmstripLayout {
+    /// Parse from a string, returning None for unknown layouts.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "horizontal" | "h" | "row" => Some(FilmstripLayout::Horizontal),
+            "vertical" | "v" | "column" => Some(FilmstripLayout::Vertical),
+            _ => None,
+        }
+    }
+
+    /// Get the layout name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilmstripLayout::Horizontal => "horizontal",
+            FilmstripLayout::Vertical => "vertical",
+        }
+    }
+}
+
+/// Green channel orientation of normal map output.
+///
+/// Wallpaper Engine renders with Direct3D, so its normal maps are stored in
+/// the DirectX convention; choosing OpenGL inverts the green channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum NormalConvention {
+    /// +Y points down (as stored by Wallpaper Engine)
+    #[default]
+    DirectX,
+    /// +Y points up
+    OpenGl,
+}
+
+impl NormalConvention {
+    /// Parse from a string, returning None for unknown conventions.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "dx" | "directx" | "d3d" => Some(NormalConvention::DirectX),
+            "gl" | "opengl" => Some(NormalConvention::OpenGl),
+            _ => None,
+        }
+    }
+
+    /// Get the short convention name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NormalConvention::DirectX => "dx",
+            NormalConvention::OpenGl => "gl",
+        }
+    }
+}
+
+/// Source of one output channel in a swizzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// The red input channel
+    R,
+    /// The green input channel
+    G,
+    /// The blue input channel
+    B,
+    /// The alpha input channel
+    A,
+    /// Constant 0
+    Zero,
+    /// Constant 255
+    One,
+}
+
+/// An animation rendered as a single filmstrip image.
+#[derive(Debug)]
+pub struct Filmstrip {
+    /// The encoded filmstrip image.
+    pub result: ConversionResult,
+    /// Direction the frames are laid out in.
+    pub layout: FilmstripLayout,
+    /// Number of frames in the strip.
+    pub frame_count: usize,
+    /// Width of each frame cell in pixels.
+    pub cell_width: u32,
+    /// Height of each frame cell in pixels.
+    pub cell_height: u32,
+    /// Padding between cells in pixels.
+    pub padding: u32,
+    /// Display duration of each frame in milliseconds.
+    pub frame_durations_ms: Vec<u32>,
+}
+
+/// Kind of texture, as reported by [`DecodeTrace::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextureKind {
+    /// A single still image
+    Static,
+    /// Frames assembled from source images
+    Animated,
+    /// An embedded MP4 video
+    Video,
+}
+
+impl TextureKind {
+    /// Get the kind name ("static", "animated" or "video").
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextureKind::Static => "static",
+            TextureKind::Animated => "animated",
+            TextureKind::Video => "video",
+        }
+    }
+}
+
+impl std::fmt::Display for TextureKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Decisions the conversion pipeline made for a texture.
+///
+/// Returned by [`TexToImageConverter::convert_traced`] to help diagnose
+/// unexpected output, e.g. when a header misreports its pixel format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeTrace {
+    /// Container magic as stored in the file.
+    pub container_magic: String,
+    /// Whether a V4 (or V5) container without video was read as V3.
+    pub downgraded_to_v3: bool,
+    /// Kind of texture.
+    pub kind: TextureKind,
+    /// Mipmap format declared by the texture and container headers.
+    pub declared_format: MipmapFormat,
+    /// Format the first mipmap was actually decoded as.
+    pub inferred_format: MipmapFormat,
+    /// Whether the mipmap data was stored LZ4-compressed.
+    pub lz4_compressed: bool,
+    /// Whether DXT blocks were decompressed to RGBA.
+    pub dxt_decompressed: bool,
+    /// Whether the decoded image was cropped to the header's image size.
+    pub cropped: bool,
+    /// Whether embedded image bytes were copied without re-encoding.
+    pub passthrough: bool,
+    /// Whether pixel adjustments (resize, checkerboard, gamma, brightness) were applied.
+    pub post_processed: bool,
+    /// Format of the produced output.
+    pub output_format: OutputFormat,
+    /// Dimensions of the produced output, if they could be determined.
+    pub output_dimensions: Option<(u32, u32)>,
+    /// Human-readable notes on heuristic decisions, in pipeline order.
+    pub notes: Vec<String>,
+}
+
+/// A cancellation request checked while a conversion runs.
+///
+/// Implemented for [`AtomicBool`]; implement it to poll another source,
+/// such as a callback into the host environment.
+pub trait CancelFlag: fmt::Debug + Sync {
+    /// Whether the conversion should stop.
+    fn is_cancelled(&self) -> bool;
+}
+
+impl CancelFlag for AtomicBool {
+    fn is_cancelled(&self) -> bool {
+        self.load(Ordering::Relaxed)
+    }
 }
 
 /// Converter for TEX textures to standard image formats.
 #[derive(Debug, Clone, Copy)]
-pub struct TexToImageConverter {
+pub struct TexToImageConverter<'a> {
     /// Quality for lossy formats (0-100)
     pub quality: u8,
+    /// Copy embedded images that already match the output format unchanged
+    pub passthrough: bool,
+    /// Checker size in pixels to composite images over before encoding, if any
+    pub checkerboard: Option<u32>,
+    /// Treat RG88 textures as normal maps and rebuild the dropped Z channel
+    pub reconstruct_normal_z: bool,
+    /// Green channel convention for reconstructed normal maps
+    pub normal_convention: NormalConvention,
+    /// Sources of the output R, G, B and A channels for RGBA8888 data
+    pub swizzle: Option<[Channel; 4]>,
+    /// Color to flatten transparency onto for formats without alpha (white if unset)
+    pub background_color: Option<[u8; 4]>,
+    /// Multiply color channels by alpha before encoding formats with alpha
+    pub premultiply_alpha: bool,
+    /// Gamma applied to color channels after decoding (>1 brightens midtones)
+    pub gamma: Option<f32>,
+    /// Offset added to color channels after decoding
+    pub brightness: Option<i16>,
+    /// Constant frame rate for animated output, replacing per-frame timings
+    pub gif_fps: Option<f32>,
+    /// GIF encoder speed (1-30, higher is faster with lower quality)
+    pub gif_speed: Option<i32>,
+    /// Draw each animation frame over the previous one instead of on its own
+    pub composite_frames: bool,
+    /// Crop static textures from their padded texture size to the image size
+    pub crop: bool,
+    /// Downscale output so neither side exceeds this many pixels
+    pub max_dimension: Option<u32>,
+    /// Resize output to exactly these dimensions (takes precedence over
+    /// `max_dimension`)
+    pub resize_to: Option<(u32, u32)>,
+    /// Filter used for resizing output and animation frames
+    pub resize_filter: FilterType,
+    /// zlib compression level for PNG output
+    pub png_compression: CompressionType,
+    /// Scanline filter for PNG output
+    pub png_filter: PngFilterType,
+    /// Pool to take decode scratch buffers from instead of allocating
+    #[cfg(feature = "buffer-pool")]
+    pub pool: Option<&'a BufferPool>,
+    /// Flag checked between decode/encode steps; conversion stops with
+    /// `Error::Cancelled` once it is set
+    pub cancel_flag: Option<&'a dyn CancelFlag>,
 }
 
-impl TexToImageConverter {
+impl<'a> TexToImageConverter<'a> {
     /// Create a new converter with default settings.
     pub fn new() -> Self {
-        Self { quality: 90 }
+        Self {
+            quality: 90,
+            passthrough: true,
+            checkerboard: None,
+            reconstruct_normal_z: false,
+            normal_convention: NormalConvention::DirectX,
+            swizzle: None,
+            background_color: None,
+            premultiply_alpha: false,
+            gamma: None,
+            brightness: None,
+            gif_fps: None,
+            gif_speed: None,
+            composite_frames: false,
+            crop: true,
+            max_dimension: None,
+            resize_to: None,
+            resize_filter: FilterType::Lanczos3,
+            png_compression: CompressionType::default(),
+            png_filter: PngFilterType::default(),
+            #[cfg(feature = "buffer-pool")]
+            pool: None,
+            cancel_flag: None,
+        }
     }
 
     /// Set the quality for lossy formats.
@@ -110,6 +403,338 @@ impl TexToImageConverter {
         self
     }
 
+    /// Copy embedded images through unchanged when they already match the
+    /// output format (the default), at any mipmap level.
+    ///
+    /// Passthrough keeps the source bytes exactly, including their
+    /// compression. Disable it to always decode and re-encode, e.g. to apply
+    /// the configured PNG compression settings. Pixel adjustments such as
+    /// gamma or resizing always force a re-encode.
+    pub fn with_passthrough(mut self, enabled: bool) -> Self {
+        self.passthrough = enabled;
+        self
+    }
+
+    /// Composite images over a gray checkerboard before encoding.
+    ///
+    /// This makes transparency visible in viewers that show alpha on white.
+    /// Embedded images are always re-encoded when this is enabled.
+    pub fn with_checkerboard(mut self, checker_size: u32) -> Self {
+        self.checkerboard = Some(checker_size.max(1));
+        self
+    }
+
+    /// Set the color transparent pixels are flattened onto for formats
+    /// without alpha (e.g. JPEG). The color's alpha component is ignored.
+    pub fn with_background(mut self, color: [u8; 4]) -> Self {
+        self.background_color = Some(color);
+        self
+    }
+
+    /// Write premultiplied instead of straight alpha.
+    ///
+    /// Decoded images, like everything the `image` crate handles, carry
+    /// straight alpha: a pixel's color is independent of its opacity. With
+    /// premultiplication each color channel is scaled by alpha before
+    /// encoding, so `(255, 0, 0, 128)` is written as `(128, 0, 0, 128)`, as
+    /// expected by consumers that blend with `src + dst * (1 - alpha)`.
+    /// Only formats with an alpha channel are affected; JPEG output is still
+    /// flattened from straight alpha. Embedded images are re-encoded when
+    /// this is enabled.
+    pub fn with_premultiplied_alpha(mut self, enabled: bool) -> Self {
+        self.premultiply_alpha = enabled;
+        self
+    }
+
+    /// Apply a gamma curve to color channels after decoding.
+    ///
+    /// Each channel becomes `255 * (c / 255)^(1 / gamma)`, so values above 1
+    /// brighten midtones. This is a simple post-decode adjustment on the
+    /// stored values, not a colorspace-correct conversion. Alpha is unchanged.
+    pub fn with_gamma(mut self, gamma: f32) -> Self {
+        self.gamma = Some(gamma);
+        self
+    }
+
+    /// Add a brightness offset (-255 to 255) to color channels after decoding.
+    ///
+    /// Applied after gamma, clamped per channel. Alpha is unchanged.
+    pub fn with_brightness(mut self, brightness: i16) -> Self {
+        self.brightness = Some(brightness.clamp(-255, 255));
+        self
+    }
+
+    /// Play animated textures at a constant frame rate.
+    ///
+    /// Every frame is shown for `1 / fps` seconds instead of its stored
+    /// frametime, which also lets animations be slowed down or sped up.
+    /// Non-positive or non-finite values are ignored; others are clamped to
+    /// [`MIN_GIF_FPS`]..=[`MAX_GIF_FPS`].
+    pub fn with_gif_fps(mut self, fps: f32) -> Self {
+        self.gif_fps = Some(fps)
+            .filter(|fps| fps.is_finite() && *fps > 0.0)
+            .map(|fps| fps.clamp(MIN_GIF_FPS, MAX_GIF_FPS));
+        self
+    }
+
+    /// Set the GIF encoder speed (1-30, default 10).
+    ///
+    /// Higher speeds quantize colors faster at the cost of quality.
+    pub fn with_gif_speed(mut self, speed: i32) -> Self {
+        self.gif_speed = Some(speed.clamp(1, 30));
+        self
+    }
+
+    /// Composite animation frames onto a persistent canvas.
+    ///
+    /// Some animations store frames as deltas: only the pixels that change
+    /// are opaque and the rest is transparent, expecting the previous frame
+    /// to show through. When enabled, each frame is alpha-blended over the
+    /// canvas left by the frames before it, and the full canvas is emitted,
+    /// so such animations don't flicker or show gaps. Frames are not resized:
+    /// each is cropped from its source rect and drawn at the frame's `x`/`y`
+    /// on the canvas.
+    pub fn with_frame_compositing(mut self, enabled: bool) -> Self {
+        self.composite_frames = enabled;
+        self
+    }
+
+    /// Crop static textures to their image size (the default).
+    ///
+    /// Textures are usually stored padded, e.g. to a power of two, with the
+    /// header's image size marking the visible region. Disable cropping to
+    /// output the full `texture_width` x `texture_height` data including the
+    /// padding, which helps when debugging padding or atlas artifacts.
+    /// Embedded images and animation frames are not affected.
+    pub fn with_crop(mut self, enabled: bool) -> Self {
+        self.crop = enabled;
+        self
+    }
+
+    /// Downscale output so its longest edge is at most `max` pixels.
+    ///
+    /// The aspect ratio is preserved and smaller images are left as is.
+    /// Resizing happens after cropping and before other adjustments, using
+    /// the filter set with [`with_filter`](Self::with_filter).
+    pub fn with_max_dimension(mut self, max: u32) -> Self {
+        self.max_dimension = Some(max.max(1));
+        self
+    }
+
+    /// Resize output to exactly `width` x `height` pixels with `filter`.
+    ///
+    /// The aspect ratio is not preserved. Takes precedence over
+    /// [`with_max_dimension`](Self::with_max_dimension).
+    pub fn with_resize(mut self, width: u32, height: u32, filter: FilterType) -> Self {
+        self.resize_to = Some((width.max(1), height.max(1)));
+        self.resize_filter = filter;
+        self
+    }
+
+    /// Set the filter used whenever images are resized (default Lanczos3).
+    ///
+    /// Applies to animation frames scaled to the GIF size, filmstrip cells
+    /// and configured output resizing. `FilterType::Nearest` keeps pixel art
+    /// sharp.
+    pub fn with_filter(mut self, filter: FilterType) -> Self {
+        self.resize_filter = filter;
+        self
+    }
+
+    /// Set the compression level for PNG output.
+    ///
+    /// `CompressionType::Best` gives the smallest files, `Fast` (the default)
+    /// the quickest encodes. Embedded PNGs that are passed through unchanged
+    /// keep their original compression.
+    pub fn with_png_compression(mut self, compression: CompressionType) -> Self {
+        self.png_compression = compression;
+        self
+    }
+
+    /// Set the scanline filter for PNG output (default `Adaptive`).
+    pub fn with_png_filter(mut self, filter: PngFilterType) -> Self {
+        self.png_filter = filter;
+        self
+    }
+
+    /// Reuse decode buffers from `pool` across conversions.
+    ///
+    /// Raw pixel copies are taken from the pool and returned to it once the
+    /// image is encoded. Output bytes are always freshly allocated; callers
+    /// may hand them back with [`BufferPool::put_bytes`] when done.
+    #[cfg(feature = "buffer-pool")]
+    pub fn with_pool(mut self, pool: &'a BufferPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Abort conversions once `flag` is set.
+    ///
+    /// The flag is checked between pipeline steps: before decoding, before
+    /// encoding, for each image and mipmap level, and for each source image
+    /// and frame of animated textures. A conversion that observes it fails
+    /// with [`Error::Cancelled`].
+    pub fn with_cancel_flag(mut self, flag: &'a dyn CancelFlag) -> Self {
+        self.cancel_flag = Some(flag);
+        self
+    }
+
+    /// Reconstruct the Z (blue) channel of RG88 normal maps.
+    ///
+    /// Wallpaper Engine drops Z from two-channel normal maps; when enabled,
+    /// RG88 mipmaps are output as RGBA with `Z = sqrt(1 - X^2 - Y^2)`.
+    pub fn with_normal_reconstruction(mut self, enabled: bool) -> Self {
+        self.reconstruct_normal_z = enabled;
+        self
+    }
+
+    /// Set the green channel convention of reconstructed normal maps.
+    ///
+    /// Only affects RG88 textures when normal reconstruction is enabled.
+    /// The default, DirectX, leaves the stored data unchanged.
+    pub fn with_normal_convention(mut self, convention: NormalConvention) -> Self {
+        self.normal_convention = convention;
+        self
+    }
+
+    /// Rearrange the channels of RGBA8888 data after decoding.
+    ///
+    /// Output channel `i` takes its value from `swizzle[i]`, so
+    /// `[B, G, R, A]` turns BGRA data into RGBA and `[A, A, A, One]` pulls
+    /// a mask packed into alpha out as an opaque grayscale image. Applies
+    /// to raw RGBA8888 and decompressed DXT data; embedded images and other
+    /// raw formats are unaffected.
+    pub fn with_swizzle(mut self, swizzle: [Channel; 4]) -> Self {
+        self.swizzle = Some(swizzle);
+        self
+    }
+
+    /// Lay out the frames of an animated texture in a single filmstrip image.
+    ///
+    /// Frames are placed in order along a row or column with `padding`
+    /// transparent pixels between them. Frames that differ in size are
+    /// resized to the largest frame's dimensions. Configured resizing
+    /// applies to each frame rather than to the whole sheet.
+    pub fn to_filmstrip(
+        &self,
+        tex: &Tex,
+        layout: FilmstripLayout,
+        padding: u32,
+        format: OutputFormat,
+    ) -> Result<Filmstrip> {
+        if !tex.is_gif() {
+            return Err(Error::invalid_data(
+                "Filmstrips can only be built from animated textures",
+            ));
+        }
+
+        let frames = self.extract_frames(tex)?;
+        let frame_durations_ms = frames
+            .iter()
+            .map(|frame| {
+                let (numer, denom) = frame.delay().numer_denom_ms();
+                numer / denom.max(1)
+            })
+            .collect();
+        let images = self.sheet_cells(frames);
+
+        let columns = match layout {
+            FilmstripLayout::Horizontal => images.len() as u32,
+            FilmstripLayout::Vertical => 1,
+        };
+        let (sheet, cell_width, cell_height) =
+            tile_images(&images, columns, padding, self.resize_filter);
+
+        Ok(Filmstrip {
+            result: self.encode_sheet(sheet, format)?,
+            layout,
+            frame_count: images.len(),
+            cell_width,
+            cell_height,
+            padding,
+            frame_durations_ms,
+        })
+    }
+
+    /// Lay out the frames of an animated texture in a grid for quick review.
+    ///
+    /// Frames fill `columns` cells per row (at least 1, at most the frame
+    /// count) in order, with as many rows as needed; cells left over in the
+    /// last row stay transparent. Frames are sized like in
+    /// [`to_filmstrip`](Self::to_filmstrip), and the sheet is encoded as a
+    /// single still image in `format`.
+    pub fn to_contact_sheet(
+        &self,
+        tex: &Tex,
+        columns: u32,
+        format: OutputFormat,
+    ) -> Result<ConversionResult> {
+        if !tex.is_gif() {
+            return Err(Error::invalid_data(
+                "Contact sheets can only be built from animated textures",
+            ));
+        }
+
+        let images = self.sheet_cells(self.extract_frames(tex)?);
+        let (sheet, _, _) = tile_images(&images, columns, 0, self.resize_filter);
+        self.encode_sheet(sheet, format)
+    }
+
+    /// Turn animation frames into the cells of a filmstrip or contact sheet.
+    ///
+    /// Configured resizing applies to each frame rather than to the sheet so
+    /// the cell layout stays exact.
+    fn sheet_cells(&self, frames: Vec<Frame>) -> Vec<RgbaImage> {
+        frames
+            .into_iter()
+            .map(|frame| {
+                let image = DynamicImage::ImageRgba8(frame.into_buffer());
+                self.resized(&image).unwrap_or(image).into_rgba8()
+            })
+            .collect()
+    }
+
+    /// Encode a sheet of frame cells built by [`sheet_cells`](Self::sheet_cells).
+    fn encode_sheet(&self, sheet: RgbaImage, format: OutputFormat) -> Result<ConversionResult> {
+        let sheet_converter = Self {
+            max_dimension: None,
+            resize_to: None,
+            ..*self
+        };
+        sheet_converter.encode_image(&DynamicImage::ImageRgba8(sheet), format)
+    }
+
+    /// Render a side-by-side image for inspecting decode results.
+    ///
+    /// The left half is the decoded texture composited over a checkerboard,
+    /// the right half is its alpha channel as grayscale. Animated textures use
+    /// the first source image; video textures are not supported.
+    pub fn debug_comparison(&self, tex: &Tex) -> Result<DynamicImage> {
+        if tex.is_video() {
+            return Err(Error::invalid_data(
+                "Cannot render a comparison of a video texture",
+            ));
+        }
+
+        let mipmap = tex
+            .first_image()
+            .and_then(|img| img.first_mipmap())
+            .ok_or_else(|| Error::invalid_data("Texture has no image data"))?;
+        let image = self.decode_mipmap(tex, mipmap)?.to_rgba8();
+        let (width, height) = image.dimensions();
+
+        let composited =
+            composite_checkerboard(&image, self.checkerboard.unwrap_or(DEFAULT_CHECKER_SIZE));
+        let mut output = RgbaImage::new(width * 2, height);
+        image::imageops::replace(&mut output, &composited, 0, 0);
+        for (x, y, pixel) in image.enumerate_pixels() {
+            let alpha = pixel[3];
+            output.put_pixel(width + x, y, Rgba([alpha, alpha, alpha, 255]));
+        }
+
+        Ok(DynamicImage::ImageRgba8(output))
+    }
+
     /// Get the recommended output format for a texture.
     pub fn recommended_format(&self, tex: &Tex) -> OutputFormat {
         if tex.is_video() {
@@ -123,6 +748,9 @@ impl TexToImageConverter {
 
     /// Convert a texture to an image.
     pub fn convert(&self, tex: &Tex, format: OutputFormat) -> Result<ConversionResult> {
+        ensure_container_read(tex)?;
+        self.check_cancelled()?;
+
         // Handle video textures
         if tex.is_video() {
             return self.convert_video(tex);
@@ -137,77 +765,636 @@ impl TexToImageConverter {
         self.convert_static(tex, format)
     }
 
-    /// Convert a video texture (passthrough).
-    fn convert_video(&self, tex: &Tex) -> Result<ConversionResult> {
+    /// Decode the first image of a static texture once, for encoding to
+    /// several formats with [`convert_decoded`](Self::convert_decoded).
+    ///
+    /// The decoded image is the cache: the caller keeps it for as long as it
+    /// needs and drops it when done. The converter itself retains nothing
+    /// between calls. Animated and video textures cannot be decoded to a
+    /// single image; use [`convert`](Self::convert) for those.
+    pub fn decode(&self, tex: &Tex) -> Result<DynamicImage> {
+        ensure_container_read(tex)?;
+        self.check_cancelled()?;
+        if tex.is_video() || tex.is_gif() {
+            return Err(Error::invalid_data(
+                "Only static textures can be decoded to a single image",
+            ));
+        }
+
         let mipmap = tex
             .first_image()
-            .and_then(|img| img.first_mipmap())
-            .ok_or_else(|| Error::invalid_data("Video texture has no data"))?;
+            .and_then(|image| image.first_mipmap())
+            .ok_or_else(|| Error::invalid_data("Texture has no image data"))?;
+        self.decode_mipmap(tex, mipmap)
+    }
 
-        // Verify MP4 magic
-        if mipmap.bytes.len() >= 12 {
-            let magic = &mipmap.bytes[4..12];
-            let magic_str = String::from_utf8_lossy(magic);
-            if !magic_str.starts_with("ftyp") {
-                return Err(Error::invalid_data("Expected MP4 magic header"));
+    /// Encode an image returned by [`decode`](Self::decode).
+    ///
+    /// Produces the same output as [`convert`](Self::convert), except that
+    /// the image is always re-encoded: embedded images are not passed
+    /// through and DDS output holds uncompressed RGBA rather than the
+    /// source DXT blocks. Check [`would_passthrough`](Self::would_passthrough)
+    /// to decide when `convert` is the better choice.
+    pub fn convert_decoded(
+        &self,
+        image: &DynamicImage,
+        format: OutputFormat,
+    ) -> Result<ConversionResult> {
+        self.check_cancelled()?;
+        self.encode_image(image, format)
+    }
+
+    /// Convert a texture and record the decisions made along the way.
+    ///
+    /// Produces the same output as [`convert`](Self::convert).
+    pub fn convert_traced(
+        &self,
+        tex: &Tex,
+        format: OutputFormat,
+    ) -> Result<(ConversionResult, DecodeTrace)> {
+        let result = self.convert(tex, format)?;
+        let trace = self.trace(tex, &result);
+        Ok((result, trace))
+    }
+
+    /// Reconstruct the pipeline's decisions for a finished conversion.
+    fn trace(&self, tex: &Tex, result: &ConversionResult) -> DecodeTrace {
+        let container = &tex.images_container;
+        let declared_format = container.mipmap_format_for(&tex.header);
+        let mipmap = tex.first_image().and_then(|img| img.first_mipmap());
+        let kind = if tex.is_video() {
+            TextureKind::Video
+        } else if tex.is_gif() {
+            TextureKind::Animated
+        } else {
+            TextureKind::Static
+        };
+
+        let mut notes = Vec::new();
+        let downgraded_to_v3 = container.is_downgraded();
+        if downgraded_to_v3 {
+            let label = match container.raw_version() {
+                TexImageContainerVersion::Version5 => "V5",
+                _ => "V4",
+            };
+            notes.push(format!(
+                "{} container without video read with V3 mipmap layout",
+                label
+            ));
+        }
+
+        let inferred_format = match mipmap {
+            Some(mipmap) if mipmap.format.is_image() => embedded_source_format(mipmap),
+            Some(mipmap) if mipmap.format.is_raw() => mipmap.format.infer_from_size(
+                mipmap.width as usize * mipmap.height as usize,
+                mipmap.bytes.len(),
+            ),
+            Some(mipmap) => mipmap.format,
+            None => MipmapFormat::Invalid,
+        };
+        if let Some(mipmap) = mipmap {
+            if inferred_format != mipmap.format {
+                notes.push(format!(
+                    "Mipmap declared as {:?} but data matches {:?}",
+                    mipmap.format, inferred_format
+                ));
             }
         }
 
-        Ok(ConversionResult {
-            bytes: mipmap.bytes.clone(),
-            format: OutputFormat::Mp4,
-        })
+        // The reader clears the LZ4 flag after decompressing, but the stored
+        // and decompressed sizes still differ
+        let lz4_compressed = mipmap.is_some_and(|m| {
+            m.is_lz4_compressed
+                || (m.decompressed_bytes_count > 0
+                    && m.decompressed_bytes_count != m.original_byte_count)
+        });
+        let dxt_decompressed =
+            declared_format.is_compressed() && mipmap.is_some_and(|m| !m.format.is_compressed());
+
+        let cropped = kind == TextureKind::Static
+            && self.crops(tex)
+            && mipmap.is_some_and(|m| !m.format.is_image());
+        if kind == TextureKind::Animated {
+            notes.push(format!(
+                "Frames cropped from {} source image(s)",
+                container.images.len()
+            ));
+        }
+        if self.reconstruct_normal_z && inferred_format == MipmapFormat::RG88 {
+            notes.push(format!(
+                "RG88 treated as a normal map, Z reconstructed ({} convention)",
+                self.normal_convention.as_str()
+            ));
+        }
+
+        DecodeTrace {
+            container_magic: container.magic.clone(),
+            downgraded_to_v3,
+            kind,
+            declared_format,
+            inferred_format,
+            lz4_compressed,
+            dxt_decompressed,
+            cropped,
+            passthrough: self.would_passthrough(tex, result.format),
+            post_processed: self.modifies_pixels() && kind != TextureKind::Video,
+            output_format: result.format,
+            output_dimensions: Some((result.width, result.height)),
+            notes,
+        }
     }
 
-    /// Convert a static texture.
-    fn convert_static(&self, tex: &Tex, format: OutputFormat) -> Result<ConversionResult> {
-        let mipmap = tex
-            .first_image()
-            .and_then(|img| img.first_mipmap())
-            .ok_or_else(|| Error::invalid_data("Texture has no image data"))?;
+    /// Check whether `convert` would copy the embedded image bytes unchanged.
+    ///
+    /// True for static textures whose first mipmap is an embedded image in
+    /// the requested format, when passthrough is enabled and no pixel
+    /// adjustments are configured. In that case encoder options such as
+    /// quality have no effect.
+    pub fn would_passthrough(&self, tex: &Tex, format: OutputFormat) -> bool {
+        if tex.unsupported_container.is_some() || tex.is_video() || tex.is_gif() {
+            return false;
+        }
 
-        // If the mipmap is already an image format, we might be able to passthrough
-        if mipmap.format.is_image() {
-            return self.convert_embedded_image(mipmap, format);
+        match tex.first_image().and_then(|img| img.first_mipmap()) {
+            Some(mipmap) if mipmap.format.is_image() => {
+                self.passes_through(embedded_source_format(mipmap), format)
+            }
+            _ => false,
         }
+    }
 
-        // Convert raw pixel data to image
-        let image = self.mipmap_to_image(mipmap)?;
+    /// Estimate the size in bytes of the output `convert` would produce.
+    ///
+    /// Exact when the source data is written unchanged (passthrough of
+    /// embedded images, video, DDS output of raw data). Otherwise a rough
+    /// guess from the output dimensions and typical compression ratios of
+    /// `format`; real sizes depend on the image content. Nothing is decoded,
+    /// so this also works on textures read with
+    /// [`TexReader::headers_only`](super::TexReader::headers_only).
+    pub fn estimated_output_bytes(&self, tex: &Tex, format: OutputFormat) -> usize {
+        let Some(image) = tex.first_image() else {
+            return 0;
+        };
+        let Some(mipmap) = image.first_mipmap() else {
+            return 0;
+        };
 
-        // Crop if needed
-        let image = if tex.header.needs_crop() {
-            let (crop_w, crop_h) = tex.header.crop_dimensions();
-            image.crop_imm(0, 0, crop_w, crop_h)
+        if tex.is_video() || self.would_passthrough(tex, format) {
+            return stored_size(mipmap);
+        }
+
+        let (width, height, frames) = match &tex.frame_info_container {
+            Some(info) if tex.is_gif() => {
+                let animated = matches!(format, OutputFormat::Gif | OutputFormat::WebP);
+                let frames = if animated { info.frame_count() } else { 1 };
+                (info.gif_width, info.gif_height, frames)
+            }
+            _ if mipmap.format.is_image() => (mipmap.width, mipmap.height, 1),
+            _ => {
+                if format == OutputFormat::Dds
+                    && !self.modifies_pixels()
+                    && (mipmap.format.is_compressed() || mipmap.format.bytes_per_pixel().is_some())
+                {
+                    let levels = image.mipmaps.iter().map(|m| m.expected_size());
+                    return dds::FILE_HEADER_SIZE + levels.sum::<usize>();
+                }
+                let (width, height) = if self.crops(tex) {
+                    mipmap_crop_dimensions(&tex.header, mipmap)
+                } else {
+                    (mipmap.width, mipmap.height)
+                };
+                (width, height, 1)
+            }
+        };
+        let (width, height) = self.resized_dimensions(width, height);
+        let pixels = width as usize * height as usize;
+
+        let channels = match mipmap.format {
+            MipmapFormat::R8 => 1,
+            MipmapFormat::RG88 if !self.reconstruct_normal_z => 2,
+            MipmapFormat::ImageJPEG => 3,
+            _ => 4,
+        };
+        let channels = if format.supports_alpha() {
+            channels
         } else {
-            image
+            channels.min(3)
         };
+        let raw = pixels * channels;
 
-        // Encode to requested format
-        self.encode_image(&image, format)
+        let per_frame = match format {
+            OutputFormat::Png => raw / 2,
+            OutputFormat::Jpeg => pixels * (self.quality as usize + 20) / 250,
+            OutputFormat::WebP => raw * 2 / 5,
+            OutputFormat::Gif => pixels / 2,
+            OutputFormat::Qoi => raw.max(pixels * 3) * 3 / 5,
+            OutputFormat::Bmp | OutputFormat::Tga | OutputFormat::Tiff => raw,
+            OutputFormat::Dds => pixels * 4,
+            OutputFormat::Mp4 => stored_size(mipmap),
+        };
+        let header = match format {
+            OutputFormat::Dds => dds::FILE_HEADER_SIZE,
+            OutputFormat::Bmp => 54,
+            _ => 0,
+        };
+        header + per_frame * frames
     }
 
-    /// Convert an embedded image format.
-    fn convert_embedded_image(
+    /// Dimensions of an image of `width` x `height` after configured resizing.
+    fn resized_dimensions(&self, width: u32, height: u32) -> (u32, u32) {
+        match (self.resize_to, self.max_dimension) {
+            (Some(size), _) => size,
+            (None, Some(max)) if width > max || height > max => {
+                let scale = max as f64 / width.max(height) as f64;
+                (
+                    ((width as f64 * scale).round() as u32).max(1),
+                    ((height as f64 * scale).round() as u32).max(1),
+                )
+            }
+            _ => (width, height),
+        }
+    }
+
+    /// Convert every image in the container to a still image.
+    ///
+    /// Returns one result per image, in container order, each converted from
+    /// its largest mipmap. Unlike animated conversion, images are not
+    /// composited into frames.
+    pub fn convert_all(&self, tex: &Tex, format: OutputFormat) -> Result<Vec<ConversionResult>> {
+        ensure_container_read(tex)?;
+        if tex.is_video() {
+            return Err(Error::invalid_data(
+                "Cannot convert the images of a video texture individually",
+            ));
+        }
+
+        tex.images_container
+            .images
+            .iter()
+            .enumerate()
+            .map(|(index, image)| {
+                self.check_cancelled()?;
+                let mipmap = image.first_mipmap().ok_or_else(|| {
+                    Error::invalid_data(format!("Image {} has no mipmaps", index))
+                })?;
+                self.convert_static_mipmap(tex, mipmap, format)
+            })
+            .collect()
+    }
+
+    /// Convert a single mipmap level of the first image to a still image.
+    ///
+    /// Level 0 is the largest mipmap. The crop to the image dimensions is
+    /// scaled to the chosen level, so smaller levels produce proportionally
+    /// smaller output. Animated textures yield the selected level of the
+    /// source atlas rather than an animation.
+    pub fn convert_mipmap(
         &self,
-        mipmap: &TexMipmap,
+        tex: &Tex,
+        level: usize,
         format: OutputFormat,
     ) -> Result<ConversionResult> {
-        // Try to decode the embedded image
-        let image = image::load_from_memory(&mipmap.bytes)?;
-
-        // If same format, passthrough
-        if self.formats_match(mipmap.format, format) {
-            return Ok(ConversionResult {
-                bytes: mipmap.bytes.clone(),
-                format,
-            });
+        ensure_container_read(tex)?;
+        if tex.is_video() {
+            return Err(Error::invalid_data(
+                "Cannot select a mipmap level of a video texture",
+            ));
         }
 
-        // Otherwise re-encode
-        self.encode_image(&image, format)
+        let image = tex
+            .first_image()
+            .ok_or_else(|| Error::invalid_data("Texture has no image data"))?;
+        let mipmap = image.mipmaps.get(level).ok_or_else(|| {
+            Error::invalid_data(format!(
+                "Mipmap level {} out of range (image has {} levels)",
+                level,
+                image.mipmap_count()
+            ))
+        })?;
+
+        self.convert_static_mipmap(tex, mipmap, format)
     }
 
-    /// Check if mipmap format matches output format.
+    /// Decode the first mipmap to tightly packed RGBA8888 pixels.
+    ///
+    /// Returns `(width, height, bytes)` without any image container, e.g. for
+    /// uploading straight to a GPU. The pixels are cropped and adjusted like
+    /// [`convert`](Self::convert) output, including alpha premultiplication
+    /// when enabled. Animated textures yield their source atlas; video
+    /// textures are rejected.
+    pub fn to_raw_rgba(&self, tex: &Tex) -> Result<(u32, u32, Vec<u8>)> {
+        ensure_container_read(tex)?;
+        if tex.is_video() {
+            return Err(Error::invalid_data(
+                "Cannot export the pixels of a video texture",
+            ));
+        }
+
+        let mipmap = tex
+            .first_image()
+            .and_then(|img| img.first_mipmap())
+            .ok_or_else(|| Error::invalid_data("Texture has no image data"))?;
+        let image = self.decode_mipmap(tex, mipmap)?;
+        let mut rgba = self.post_process(&image).to_rgba8();
+        self.recycle(image);
+        if self.premultiply_alpha {
+            premultiply_alpha(&mut rgba);
+        }
+
+        Ok((rgba.width(), rgba.height(), rgba.into_raw()))
+    }
+
+    /// Extract the first channel of the first mipmap as a grayscale plane.
+    ///
+    /// Returns `(width, height, bytes)` with one byte per pixel: the data
+    /// itself for R8 masks and the R channel for RG88 and RGBA8888, cropped
+    /// like [`convert`](Self::convert) output. Raw data is sliced directly
+    /// without building an image; embedded images and configured
+    /// adjustments go through [`to_raw_rgba`](Self::to_raw_rgba) instead.
+    pub fn to_grayscale_plane(&self, tex: &Tex) -> Result<(u32, u32, Vec<u8>)> {
+        ensure_container_read(tex)?;
+        let mipmap = tex
+            .first_image()
+            .and_then(|img| img.first_mipmap())
+            .ok_or_else(|| Error::invalid_data("Texture has no image data"))?;
+
+        if !tex.is_video() && !mipmap.format.is_image() && !self.modifies_pixels() {
+            let mipmap = decompressed(mipmap)?;
+            let (width, height) = (mipmap.width as usize, mipmap.height as usize);
+            let format = mipmap
+                .format
+                .infer_from_size(width * height, mipmap.bytes.len());
+
+            if let Some(bpp) = format.bytes_per_pixel().map(|bpp| bpp as usize) {
+                if mipmap.bytes.len() == width * height * bpp {
+                    let (crop_w, crop_h) = if self.crops(tex) {
+                        mipmap_crop_dimensions(&tex.header, &mipmap)
+                    } else {
+                        (mipmap.width, mipmap.height)
+                    };
+                    let plane = mipmap
+                        .bytes
+                        .chunks_exact(width * bpp)
+                        .take(crop_h as usize)
+                        .flat_map(|row| row.iter().step_by(bpp).take(crop_w as usize))
+                        .copied()
+                        .collect();
+                    return Ok((crop_w, crop_h, plane));
+                }
+            }
+        }
+
+        let (width, height, rgba) = self.to_raw_rgba(tex)?;
+        Ok((width, height, rgba.into_iter().step_by(4).collect()))
+    }
+
+    /// Decode a single frame of a video texture to a still image.
+    ///
+    /// The embedded MP4 is demuxed and sample `frame_index` of its first video
+    /// track is decoded. Only intra-frame codecs that can be decoded without a
+    /// video decoder are supported: Motion JPEG (`jpeg`, `mjpa`) and PNG
+    /// (`png `). Other codecs such as H.264 or HEVC return
+    /// [`Error::UnsupportedVideoCodec`].
+    pub fn extract_video_frame(
+        &self,
+        tex: &Tex,
+        frame_index: usize,
+        format: OutputFormat,
+    ) -> Result<ConversionResult> {
+        if format == OutputFormat::Mp4 {
+            return Err(Error::invalid_data(
+                "Video frames must be converted to a still image format",
+            ));
+        }
+
+        let bytes = video_bytes(tex)?;
+        let track = mp4::parse_video_track(bytes)?;
+
+        let image_format = match &track.codec {
+            b"jpeg" | b"mjpa" => ImageFormat::Jpeg,
+            b"png " => ImageFormat::Png,
+            _ => {
+                return Err(Error::UnsupportedVideoCodec {
+                    codec: track.codec_name(),
+                })
+            }
+        };
+
+        let sample = track.sample(bytes, frame_index)?;
+        let image = image::load_from_memory_with_format(sample, image_format)?;
+        self.encode_image(&image, format)
+    }
+
+    /// Decode the first keyframe of a video texture as a poster image.
+    ///
+    /// Supports the same codecs as [`extract_video_frame`](Self::extract_video_frame).
+    pub fn extract_video_poster(
+        &self,
+        tex: &Tex,
+        format: OutputFormat,
+    ) -> Result<ConversionResult> {
+        let track = mp4::parse_video_track(video_bytes(tex)?)?;
+        self.extract_video_frame(tex, track.first_keyframe(), format)
+    }
+
+    /// Convert a video texture (passthrough).
+    fn convert_video(&self, tex: &Tex) -> Result<ConversionResult> {
+        let mipmap = tex
+            .first_image()
+            .and_then(|img| img.first_mipmap())
+            .ok_or_else(|| Error::invalid_data("Video texture has no data"))?;
+
+        // Verify MP4 magic
+        if mipmap.bytes.len() >= 12 {
+            let magic = &mipmap.bytes[4..12];
+            let magic_str = String::from_utf8_lossy(magic);
+            if !magic_str.starts_with("ftyp") {
+                return Err(Error::invalid_data("Expected MP4 magic header"));
+            }
+        }
+
+        Ok(ConversionResult {
+            bytes: mipmap.bytes.clone(),
+            format: OutputFormat::Mp4,
+            width: mipmap.width,
+            height: mipmap.height,
+        })
+    }
+
+    /// Convert a static texture.
+    fn convert_static(&self, tex: &Tex, format: OutputFormat) -> Result<ConversionResult> {
+        let image = tex
+            .first_image()
+            .filter(|img| img.first_mipmap().is_some())
+            .ok_or_else(|| Error::invalid_data("Texture has no image data"))?;
+
+        if format == OutputFormat::Dds {
+            if let Some(result) = self.source_dds(&image.mipmaps)? {
+                return Ok(result);
+            }
+        }
+
+        // The first level can't be wrapped on its own either, so go straight
+        // to decoding instead of trying the DDS source path again
+        self.reencode_static_mipmap(tex, &image.mipmaps[0], format)
+    }
+
+    /// Convert one mipmap of a static texture.
+    fn convert_static_mipmap(
+        &self,
+        tex: &Tex,
+        mipmap: &TexMipmap,
+        format: OutputFormat,
+    ) -> Result<ConversionResult> {
+        if format == OutputFormat::Dds {
+            if let Some(result) = self.source_dds(std::slice::from_ref(mipmap))? {
+                return Ok(result);
+            }
+        }
+
+        self.reencode_static_mipmap(tex, mipmap, format)
+    }
+
+    /// Convert one mipmap of a static texture without wrapping its source
+    /// data in a DDS container.
+    fn reencode_static_mipmap(
+        &self,
+        tex: &Tex,
+        mipmap: &TexMipmap,
+        format: OutputFormat,
+    ) -> Result<ConversionResult> {
+        // If the mipmap is already an image format, we might be able to passthrough
+        if mipmap.format.is_image() {
+            return self.convert_embedded_image(mipmap, format);
+        }
+
+        // Encode to requested format
+        let image = self.decode_mipmap(tex, mipmap)?;
+        if let Err(e) = self.check_cancelled() {
+            self.recycle(image);
+            return Err(e);
+        }
+        let result = self.encode_image(&image, format);
+        self.recycle(image);
+        result
+    }
+
+    /// Decode a mipmap of a static texture, cropped to the image dimensions.
+    fn decode_mipmap(&self, tex: &Tex, mipmap: &TexMipmap) -> Result<DynamicImage> {
+        if mipmap.format.is_image() {
+            let source_format = sniff_image_format(&mipmap.bytes).unwrap_or(mipmap.format);
+            return decode_embedded(&mipmap.bytes, source_format);
+        }
+
+        // Convert raw pixel data to image
+        let image = self.mipmap_to_image(mipmap)?;
+
+        // Crop if needed
+        if self.crops(tex) {
+            let (crop_w, crop_h) = mipmap_crop_dimensions(&tex.header, mipmap);
+            Ok(image.crop_imm(0, 0, crop_w, crop_h))
+        } else {
+            Ok(image)
+        }
+    }
+
+    /// Wrap the source data of a mip chain in a DDS container.
+    ///
+    /// DXT levels keep their block data and raw levels are stored
+    /// uncompressed; LZ4 data is decompressed first. Since block data can't
+    /// be cropped, levels keep their full (padded) size. The chain ends at
+    /// the first level that doesn't halve the previous one. Returns `None`
+    /// when the pixels have to be decoded instead, e.g. for embedded images
+    /// or when adjustments are configured.
+    fn source_dds(&self, mipmaps: &[TexMipmap]) -> Result<Option<ConversionResult>> {
+        let Some(first) = mipmaps.first() else {
+            return Ok(None);
+        };
+        if first.format.is_image() || self.modifies_pixels() || self.swizzle.is_some() {
+            return Ok(None);
+        }
+
+        let decompressor = MipmapDecompressor::new();
+        let mut sources = Vec::with_capacity(mipmaps.len());
+        for (index, mipmap) in mipmaps.iter().enumerate() {
+            let expected = (
+                (first.width >> index).max(1),
+                (first.height >> index).max(1),
+            );
+            if (mipmap.width, mipmap.height) != expected || mipmap.format != first.format {
+                break;
+            }
+            self.check_cancelled()?;
+            let bytes = if mipmap.is_lz4_compressed {
+                let mut copy = mipmap.clone();
+                decompressor.decompress_lz4(&mut copy)?;
+                Cow::Owned(copy.bytes)
+            } else {
+                Cow::Borrowed(mipmap.bytes.as_slice())
+            };
+            sources.push((mipmap, bytes));
+        }
+
+        let format = if first.format.is_raw() {
+            first.format.infer_from_size(
+                first.width as usize * first.height as usize,
+                sources[0].1.len(),
+            )
+        } else {
+            first.format
+        };
+        let supported = format.is_compressed() || format.bytes_per_pixel().is_some();
+        if !supported || (format == MipmapFormat::RG88 && self.reconstruct_normal_z) {
+            return Ok(None);
+        }
+
+        let levels: Vec<_> = sources
+            .iter()
+            .map(|(mipmap, bytes)| DdsLevel {
+                width: mipmap.width,
+                height: mipmap.height,
+                data: bytes,
+            })
+            .collect();
+        Ok(Some(ConversionResult {
+            bytes: dds::encode(format, &levels)?,
+            format: OutputFormat::Dds,
+            width: first.width,
+            height: first.height,
+        }))
+    }
+
+    /// Convert an embedded image format.
+    fn convert_embedded_image(
+        &self,
+        mipmap: &TexMipmap,
+        format: OutputFormat,
+    ) -> Result<ConversionResult> {
+        let source_format = embedded_source_format(mipmap);
+
+        // Try to decode the embedded image
+        let image = decode_embedded(&mipmap.bytes, source_format)?;
+
+        // If same format, passthrough
+        if self.passes_through(source_format, format) {
+            return Ok(ConversionResult {
+                bytes: mipmap.bytes.clone(),
+                format,
+                width: image.width(),
+                height: image.height(),
+            });
+        }
+
+        // Otherwise re-encode
+        self.encode_image(&image, format)
+    }
+
+    /// Whether an embedded image in `source_format` is copied unchanged.
+    fn passes_through(&self, source_format: MipmapFormat, format: OutputFormat) -> bool {
+        self.passthrough && !self.modifies_pixels() && self.formats_match(source_format, format)
+    }
+
+    /// Check if mipmap format matches output format.
     fn formats_match(&self, mipmap_fmt: MipmapFormat, output_fmt: OutputFormat) -> bool {
         matches!(
             (mipmap_fmt, output_fmt),
@@ -218,11 +1405,14 @@ impl TexToImageConverter {
                 | (MipmapFormat::ImageBMP, OutputFormat::Bmp)
                 | (MipmapFormat::ImageTIFF, OutputFormat::Tiff)
                 | (MipmapFormat::ImageTGA, OutputFormat::Tga)
+                | (MipmapFormat::ImageDDS, OutputFormat::Dds)
         )
     }
 
     /// Convert a mipmap to a DynamicImage.
     fn mipmap_to_image(&self, mipmap: &TexMipmap) -> Result<DynamicImage> {
+        let mipmap = decompressed(mipmap)?;
+
         let width = mipmap.width;
         let height = mipmap.height;
         let pixel_count = (width as usize) * (height as usize);
@@ -230,28 +1420,42 @@ impl TexToImageConverter {
 
         // Infer the actual format from data size, as the header format can be incorrect
         // This handles cases where the TEX header says RG88 but the data is actually R8
-        let actual_format = self.infer_format_from_size(mipmap.format, pixel_count, data_size);
+        let actual_format = mipmap.format.infer_from_size(pixel_count, data_size);
 
         match actual_format {
             MipmapFormat::RGBA8888 => {
-                let img: RgbaImage = ImageBuffer::from_raw(width, height, mipmap.bytes.clone())
-                    .ok_or_else(|| {
-                        Error::invalid_data("Invalid RGBA8888 data size for dimensions")
-                    })?;
+                let mut img: RgbaImage =
+                    ImageBuffer::from_raw(width, height, self.scratch_copy(&mipmap.bytes))
+                        .ok_or_else(|| {
+                            Error::invalid_data("Invalid RGBA8888 data size for dimensions")
+                        })?;
+                if let Some(swizzle) = self.swizzle {
+                    swizzle_channels(&mut img, swizzle);
+                }
                 Ok(DynamicImage::ImageRgba8(img))
             }
             MipmapFormat::R8 => {
                 let img: ImageBuffer<Luma<u8>, Vec<u8>> =
-                    ImageBuffer::from_raw(width, height, mipmap.bytes.clone()).ok_or_else(
-                        || Error::invalid_data("Invalid R8 data size for dimensions"),
-                    )?;
+                    ImageBuffer::from_raw(width, height, self.scratch_copy(&mipmap.bytes))
+                        .ok_or_else(|| {
+                            Error::invalid_data("Invalid R8 data size for dimensions")
+                        })?;
                 Ok(DynamicImage::ImageLuma8(img))
             }
+            MipmapFormat::RG88 if self.reconstruct_normal_z => {
+                let mut img = reconstruct_normal_z(width, height, &mipmap.bytes)
+                    .ok_or_else(|| Error::invalid_data("Invalid RG88 data size for dimensions"))?;
+                if self.normal_convention == NormalConvention::OpenGl {
+                    invert_green(&mut img);
+                }
+                Ok(DynamicImage::ImageRgba8(img))
+            }
             MipmapFormat::RG88 => {
                 let img: ImageBuffer<LumaA<u8>, Vec<u8>> =
-                    ImageBuffer::from_raw(width, height, mipmap.bytes.clone()).ok_or_else(
-                        || Error::invalid_data("Invalid RG88 data size for dimensions"),
-                    )?;
+                    ImageBuffer::from_raw(width, height, self.scratch_copy(&mipmap.bytes))
+                        .ok_or_else(|| {
+                            Error::invalid_data("Invalid RG88 data size for dimensions")
+                        })?;
                 Ok(DynamicImage::ImageLumaA8(img))
             }
             _ => Err(Error::UnsupportedMipmapFormat {
@@ -260,37 +1464,51 @@ impl TexToImageConverter {
         }
     }
 
-    /// Infer the actual pixel format from data size.
-    /// Sometimes TEX headers report incorrect formats (e.g., RG88 when data is actually R8).
-    fn infer_format_from_size(
-        &self,
-        declared_format: MipmapFormat,
-        pixel_count: usize,
-        data_size: usize,
-    ) -> MipmapFormat {
-        // Check if the declared format matches the data size
-        let declared_bpp = declared_format.bytes_per_pixel();
-        if let Some(bpp) = declared_bpp {
-            if data_size == pixel_count * (bpp as usize) {
-                return declared_format;
-            }
-        }
-
-        // Infer format from actual data size
-        if data_size == pixel_count * 4 {
-            MipmapFormat::RGBA8888
-        } else if data_size == pixel_count * 2 {
-            MipmapFormat::RG88
-        } else if data_size == pixel_count {
-            MipmapFormat::R8
-        } else {
-            // Can't determine, return original
-            declared_format
+    /// Fail with `Error::Cancelled` if the cancel flag has been set.
+    fn check_cancelled(&self) -> Result<()> {
+        match self.cancel_flag {
+            Some(flag) if flag.is_cancelled() => Err(Error::Cancelled),
+            _ => Ok(()),
         }
     }
 
-    /// Convert an animated GIF texture.
-    fn convert_gif(&self, tex: &Tex, format: OutputFormat) -> Result<ConversionResult> {
+    /// Copy raw pixel data into a buffer, taken from the pool if one is set.
+    fn scratch_copy(&self, data: &[u8]) -> Vec<u8> {
+        #[cfg(feature = "buffer-pool")]
+        if let Some(pool) = self.pool {
+            let mut buffer = pool.take_bytes(data.len());
+            buffer.extend_from_slice(data);
+            return buffer;
+        }
+        data.to_vec()
+    }
+
+    /// Return a decoded image's pixel buffer to the pool, if one is set.
+    #[cfg(feature = "buffer-pool")]
+    fn recycle(&self, image: DynamicImage) {
+        let Some(pool) = self.pool else {
+            return;
+        };
+        match image {
+            DynamicImage::ImageRgba8(img) => pool.put_bytes(img.into_raw()),
+            DynamicImage::ImageRgb8(img) => pool.put_bytes(img.into_raw()),
+            DynamicImage::ImageLumaA8(img) => pool.put_bytes(img.into_raw()),
+            DynamicImage::ImageLuma8(img) => pool.put_bytes(img.into_raw()),
+            _ => {}
+        }
+    }
+
+    /// Return a decoded image's pixel buffer to the pool, if one is set.
+    #[cfg(not(feature = "buffer-pool"))]
+    fn recycle(&self, _image: DynamicImage) {}
+
+    /// Assemble the frames of an animated texture.
+    ///
+    /// Returns one frame per frame info. Each is cropped from its source
+    /// image, un-rotated and resized to the animation size, and carries its
+    /// display duration as the delay. Frame infos over an animated embedded
+    /// GIF take its frames in turn, along with their stored delays.
+    pub fn extract_frames(&self, tex: &Tex) -> Result<Vec<Frame>> {
         let frame_info = tex
             .frame_info_container
             .as_ref()
@@ -301,15 +1519,11 @@ impl TexToImageConverter {
         }
 
         // Convert all source images
-        let mut source_images: Vec<DynamicImage> = Vec::new();
+        let mut source_images: Vec<Vec<SourceFrame>> = Vec::new();
         for image in &tex.images_container.images {
+            self.check_cancelled()?;
             if let Some(mipmap) = image.first_mipmap() {
-                let img = if mipmap.format.is_image() {
-                    image::load_from_memory(&mipmap.bytes)?
-                } else {
-                    self.mipmap_to_image(mipmap)?
-                };
-                source_images.push(img);
+                source_images.push(self.decode_source_frames(mipmap)?);
             }
         }
 
@@ -317,63 +1531,124 @@ impl TexToImageConverter {
             return Err(Error::invalid_data("No valid images in GIF texture"));
         }
 
-        // Build frames
+        // Build frames, on a persistent canvas when compositing
         let mut frames: Vec<Frame> = Vec::new();
+        let mut canvas = self
+            .composite_frames
+            .then(|| RgbaImage::new(frame_info.gif_width, frame_info.gif_height));
 
+        // Frame infos referencing an animated embedded GIF step through its
+        // frames in order, wrapping around when there are more infos
+        let mut next_source_frame = vec![0usize; source_images.len()];
         for frame_info in &frame_info.frames {
+            self.check_cancelled()?;
             let source_idx = frame_info.image_id as usize;
-            if source_idx >= source_images.len() {
+            let Some(source_frames) = source_images.get(source_idx) else {
                 continue;
-            }
-
-            let source = &source_images[source_idx];
-            let (crop_x, crop_y, crop_w, crop_h) = frame_info.crop_rect();
-
-            // Crop the frame from the source atlas
-            let cropped = source.crop_imm(crop_x, crop_y, crop_w, crop_h);
+            };
+            let Some((source, source_delay)) =
+                source_frames.get(next_source_frame[source_idx] % source_frames.len().max(1))
+            else {
+                continue;
+            };
+            next_source_frame[source_idx] += 1;
 
-            // Apply rotation if needed
-            let rotation_deg = (frame_info.rotation_angle() * 180.0 / std::f64::consts::PI).round();
-            let rotated = if rotation_deg.abs() > 1.0 {
-                match rotation_deg as i32 {
-                    90 | -270 => cropped.rotate90(),
-                    180 | -180 => cropped.rotate180(),
-                    270 | -90 => cropped.rotate270(),
-                    _ => cropped, // For non-90-degree rotations, skip (would need interpolation)
+            let rotation = frame_info.rotation_angle();
+            let final_frame = match &mut canvas {
+                Some(canvas) => {
+                    let partial = self.unrotated_frame(source, frame_info, rotation);
+                    let (x, y, _, _) = frame_info.crop_rect();
+                    image::imageops::overlay(canvas, &partial.to_rgba8(), x as i64, y as i64);
+                    canvas.clone()
                 }
-            } else {
-                cropped
+                None => self.assemble_frame(source, frame_info, rotation).to_rgba8(),
             };
 
-            // Resize to target dimensions if needed
-            let final_frame = if rotated.width() != frame_info.gif_width()
-                || rotated.height() != frame_info.gif_height()
-            {
-                rotated.resize_exact(
-                    frame_info.gif_width(),
-                    frame_info.gif_height(),
-                    FilterType::Lanczos3,
-                )
-            } else {
-                rotated
+            let delay = match (self.gif_fps, source_delay) {
+                (None, Some(delay)) => *delay,
+                _ => image::Delay::from_saturating_duration(self.frame_delay(frame_info)),
             };
-
-            // Create frame with delay
-            let delay_ms = (frame_info.frametime * 1000.0) as u32;
-            let frame = Frame::from_parts(
-                final_frame.to_rgba8(),
-                0,
-                0,
-                image::Delay::from_saturating_duration(Duration::from_millis(delay_ms as u64)),
-            );
-            frames.push(frame);
+            frames.push(Frame::from_parts(final_frame, 0, 0, delay));
         }
 
         if frames.is_empty() {
             return Err(Error::invalid_data("No frames could be extracted from GIF"));
         }
 
-        // For non-GIF output, just return the first frame
+        Ok(frames)
+    }
+
+    /// Decode the first mipmap of an animation source image.
+    ///
+    /// Embedded GIFs can be animated themselves, so every frame of one is
+    /// returned with its own delay. Other sources, and single-frame GIFs,
+    /// yield one image without a delay, timed by the TEX frame info instead.
+    fn decode_source_frames(&self, mipmap: &TexMipmap) -> Result<Vec<SourceFrame>> {
+        if !mipmap.format.is_image() {
+            return Ok(vec![(self.mipmap_to_image(mipmap)?, None)]);
+        }
+        if embedded_source_format(mipmap) != MipmapFormat::ImageGIF {
+            return Ok(vec![(image::load_from_memory(&mipmap.bytes)?, None)]);
+        }
+
+        let frames = GifDecoder::new(Cursor::new(&mipmap.bytes))?
+            .into_frames()
+            .collect_frames()?;
+        if frames.len() <= 1 {
+            return Ok(frames
+                .into_iter()
+                .map(|frame| (DynamicImage::ImageRgba8(frame.into_buffer()), None))
+                .collect());
+        }
+        Ok(frames
+            .into_iter()
+            .map(|frame| {
+                let delay = frame.delay();
+                (DynamicImage::ImageRgba8(frame.into_buffer()), Some(delay))
+            })
+            .collect())
+    }
+
+    /// Display duration of a frame, honoring a forced frame rate.
+    ///
+    /// Rates whose frame duration can't be represented fall back to the
+    /// stored frametime.
+    fn frame_delay(&self, frame_info: &TexFrameInfo) -> Duration {
+        self.gif_fps
+            .and_then(|fps| Duration::try_from_secs_f32(1.0 / fps).ok())
+            .unwrap_or_else(|| Duration::from_millis((frame_info.frametime * 1000.0) as u64))
+    }
+
+    /// Convert an animated GIF texture.
+    fn convert_gif(&self, tex: &Tex, format: OutputFormat) -> Result<ConversionResult> {
+        let mut frames = self.extract_frames(tex)?;
+
+        // Still output goes through encode_image, which post-processes itself
+        if self.modifies_pixels() && matches!(format, OutputFormat::Gif | OutputFormat::WebP) {
+            for frame in &mut frames {
+                self.check_cancelled()?;
+                let mut processed = self
+                    .post_process(&DynamicImage::ImageRgba8(frame.buffer().clone()))
+                    .to_rgba8();
+                if self.premultiply_alpha {
+                    premultiply_alpha(&mut processed);
+                }
+                *frame = Frame::from_parts(processed, frame.left(), frame.top(), frame.delay());
+            }
+        }
+
+        // WebP supports animation natively, avoiding GIF's 256-color palette
+        let (width, height) = frames[0].buffer().dimensions();
+        if format == OutputFormat::WebP {
+            return Ok(ConversionResult {
+                bytes: webp::encode_animated(&frames)?,
+                format: OutputFormat::WebP,
+                width,
+                height,
+            });
+        }
+
+        // For other non-GIF output, just return the first frame
         if format != OutputFormat::Gif {
             let first_frame = &frames[0];
             let img = DynamicImage::ImageRgba8(first_frame.buffer().clone());
@@ -383,24 +1658,164 @@ impl TexToImageConverter {
         // Encode as GIF
         let mut output = Vec::new();
         {
-            let mut encoder = GifEncoder::new_with_speed(&mut output, 10);
+            let mut encoder = GifEncoder::new_with_speed(&mut output, self.gif_speed.unwrap_or(10));
             encoder.set_repeat(Repeat::Infinite)?;
-            encoder.encode_frames(frames.into_iter())?;
+            for frame in frames {
+                self.check_cancelled()?;
+                encoder.encode_frame(frame)?;
+            }
         }
 
         Ok(ConversionResult {
             bytes: output,
             format: OutputFormat::Gif,
+            width,
+            height,
         })
     }
 
+    /// Crop, un-rotate and resize a single animation frame from its source atlas.
+    ///
+    /// `rotation` is the clockwise angle in radians needed to un-rotate the
+    /// frame. Quarter turns are applied losslessly; any other angle is
+    /// resampled with bilinear interpolation before resizing.
+    fn assemble_frame(
+        &self,
+        source: &DynamicImage,
+        frame_info: &TexFrameInfo,
+        rotation: f64,
+    ) -> DynamicImage {
+        let rotated = self.unrotated_frame(source, frame_info, rotation);
+
+        // Resize to target dimensions if needed
+        if rotated.width() != frame_info.gif_width() || rotated.height() != frame_info.gif_height()
+        {
+            rotated.resize_exact(
+                frame_info.gif_width(),
+                frame_info.gif_height(),
+                self.resize_filter,
+            )
+        } else {
+            rotated
+        }
+    }
+
+    /// Crop and un-rotate a single animation frame from its source atlas,
+    /// keeping its stored size.
+    fn unrotated_frame(
+        &self,
+        source: &DynamicImage,
+        frame_info: &TexFrameInfo,
+        rotation: f64,
+    ) -> DynamicImage {
+        let (crop_x, crop_y, crop_w, crop_h) = frame_info.crop_rect();
+
+        // Crop the frame from the source atlas
+        let cropped = source.crop_imm(crop_x, crop_y, crop_w, crop_h);
+
+        // Apply rotation if needed
+        let rotation_deg = rotation.to_degrees().round();
+        if rotation_deg.abs() > 1.0 {
+            match rotation_deg as i32 {
+                90 | -270 => cropped.rotate90(),
+                180 | -180 => cropped.rotate180(),
+                270 | -90 => cropped.rotate270(),
+                _ => rotate_bilinear(&cropped, rotation),
+            }
+        } else {
+            cropped
+        }
+    }
+
+    /// Whether raw static data of `tex` is cropped to its image size.
+    fn crops(&self, tex: &Tex) -> bool {
+        self.crop && tex.header.needs_crop()
+    }
+
+    /// Whether post-processing changes decoded pixels (disables passthrough).
+    fn modifies_pixels(&self) -> bool {
+        self.checkerboard.is_some()
+            || self.gamma.is_some()
+            || self.brightness.is_some()
+            || self.premultiply_alpha
+            || self.max_dimension.is_some()
+            || self.resize_to.is_some()
+    }
+
+    /// Apply resizing, tone adjustments and checkerboard compositing to a
+    /// decoded image.
+    fn post_process<'i>(&self, image: &'i DynamicImage) -> Cow<'i, DynamicImage> {
+        let image = match self.resized(image) {
+            Some(resized) => Cow::Owned(resized),
+            None => Cow::Borrowed(image),
+        };
+        if self.checkerboard.is_none() && self.gamma.is_none() && self.brightness.is_none() {
+            return image;
+        }
+
+        let mut rgba = image.to_rgba8();
+        if self.gamma.is_some() || self.brightness.is_some() {
+            apply_color_lut(&mut rgba, &tone_lut(self.gamma, self.brightness));
+        }
+        if let Some(checker_size) = self.checkerboard {
+            rgba = composite_checkerboard(&rgba, checker_size);
+        }
+        Cow::Owned(DynamicImage::ImageRgba8(rgba))
+    }
+
+    /// Resize an image as configured, or `None` if it keeps its size.
+    fn resized(&self, image: &DynamicImage) -> Option<DynamicImage> {
+        let (width, height) = (image.width(), image.height());
+        match (self.resize_to, self.max_dimension) {
+            (Some((w, h)), _) if (w, h) != (width, height) => {
+                Some(image.resize_exact(w, h, self.resize_filter))
+            }
+            (None, Some(max)) if width > max || height > max => {
+                Some(image.resize(max, max, self.resize_filter))
+            }
+            _ => None,
+        }
+    }
+
     /// Encode an image to the specified format.
     fn encode_image(&self, image: &DynamicImage, format: OutputFormat) -> Result<ConversionResult> {
+        let processed = self.post_process(image);
+        let image = processed.as_ref();
+
+        // Formats without alpha would otherwise drop it, leaving transparent areas black
+        let adjusted;
+        let image = if !format.supports_alpha() && image.color().has_alpha() {
+            let background = self.background_color.unwrap_or(DEFAULT_BACKGROUND);
+            adjusted = DynamicImage::ImageRgb8(flatten_onto(&image.to_rgba8(), background));
+            &adjusted
+        } else if self.premultiply_alpha && image.color().has_alpha() {
+            let mut rgba = image.to_rgba8();
+            premultiply_alpha(&mut rgba);
+            adjusted = DynamicImage::ImageRgba8(rgba);
+            &adjusted
+        } else {
+            image
+        };
+
+        let converted;
+        let image = match encodable_depth(image, format) {
+            Some(image) => {
+                converted = image;
+                &converted
+            }
+            None => image,
+        };
+
         let mut output = Vec::new();
 
         match format {
             OutputFormat::Png => {
-                image.write_to(&mut Cursor::new(&mut output), ImageFormat::Png)?;
+                let encoder = PngEncoder::new_with_quality(
+                    &mut output,
+                    self.png_compression,
+                    self.png_filter,
+                );
+                image.write_with_encoder(encoder)?;
             }
             OutputFormat::Jpeg => {
                 // JPEG encoder with quality
@@ -423,6 +1838,18 @@ impl TexToImageConverter {
             OutputFormat::Tga => {
                 image.write_to(&mut Cursor::new(&mut output), ImageFormat::Tga)?;
             }
+            OutputFormat::Qoi => {
+                image.write_to(&mut Cursor::new(&mut output), ImageFormat::Qoi)?;
+            }
+            OutputFormat::Dds => {
+                let rgba = image.to_rgba8();
+                let level = DdsLevel {
+                    width: rgba.width(),
+                    height: rgba.height(),
+                    data: rgba.as_raw(),
+                };
+                output = dds::encode(MipmapFormat::RGBA8888, &[level])?;
+            }
             OutputFormat::Mp4 => {
                 return Err(Error::invalid_data("Cannot encode static image as MP4"));
             }
@@ -431,25 +1858,220 @@ impl TexToImageConverter {
         Ok(ConversionResult {
             bytes: output,
             format,
+            width: image.width(),
+            height: image.height(),
         })
     }
 }
 
-impl Default for TexToImageConverter {
-    fn default() -> Self {
-        Self::new()
+/// Convert `image` to a pixel type `format` can encode, keeping 16 bits per
+/// channel where the format supports it.
+///
+/// Floating-point images become 16-bit, and 16-bit or floating-point images
+/// become 8-bit for formats without high-bit-depth support. Returns `None`
+/// when the image can be encoded as is.
+fn encodable_depth(image: &DynamicImage, format: OutputFormat) -> Option<DynamicImage> {
+    let alpha = image.color().has_alpha();
+    let float = matches!(
+        image,
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+    );
+    let high = float || image.color().bytes_per_pixel() > image.color().channel_count();
+
+    if format.supports_16_bit() {
+        float.then(|| {
+            if alpha {
+                DynamicImage::ImageRgba16(image.to_rgba16())
+            } else {
+                DynamicImage::ImageRgb16(image.to_rgb16())
+            }
+        })
+    } else {
+        high.then(|| {
+            if alpha {
+                DynamicImage::ImageRgba8(image.to_rgba8())
+            } else {
+                DynamicImage::ImageRgb8(image.to_rgb8())
+            }
+        })
     }
 }
 
-// Extension trait for TexFrameInfo
-trait TexFrameInfoExt {
-    fn gif_width(&self) -> u32;
-    fn gif_height(&self) -> u32;
-}
+/// A decoded animation source image, with its own delay if it came from
+/// an animated embedded GIF.
+type SourceFrame = (DynamicImage, Option<image::Delay>);
 
-impl TexFrameInfoExt for repkg_core::TexFrameInfo {
-    fn gif_width(&self) -> u32 {
-        self.actual_width() as u32
+/// Size of a mipmap's data once LZ4 decompressed, without reading it.
+fn stored_size(mipmap: &TexMipmap) -> usize {
+    if mipmap.is_lz4_compressed {
+        mipmap.decompressed_bytes_count as usize
+    } else if mipmap.has_data() {
+        mipmap.byte_count()
+    } else {
+        mipmap.original_byte_count as usize
+    }
+}
+
+/// Format of an embedded image mipmap.
+///
+/// The container metadata can be wrong, so trust the magic bytes when present.
+fn embedded_source_format(mipmap: &TexMipmap) -> MipmapFormat {
+    match sniff_image_format(&mipmap.bytes) {
+        Some(actual) if actual != mipmap.format => {
+            log::debug!(
+                "Embedded image declared as {:?} but data is {:?}, using detected format",
+                mipmap.format,
+                actual
+            );
+            actual
+        }
+        _ => mipmap.format,
+    }
+}
+
+/// Fail if the texture's image container was skipped as unsupported.
+fn ensure_container_read(tex: &Tex) -> Result<()> {
+    match &tex.unsupported_container {
+        Some(magic) => Err(Error::UnsupportedContainerVersion {
+            version: magic.clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Get the embedded MP4 bytes of a video texture.
+fn video_bytes(tex: &Tex) -> Result<&[u8]> {
+    if !tex.is_video() {
+        return Err(Error::invalid_data("Texture is not a video texture"));
+    }
+
+    tex.first_image()
+        .and_then(|img| img.first_mipmap())
+        .map(|mipmap| mipmap.bytes.as_slice())
+        .ok_or_else(|| Error::invalid_data("Video texture has no data"))
+}
+
+/// Scale the header's crop dimensions to a mipmap level.
+///
+/// For the full-size level this is exactly the image dimensions.
+fn mipmap_crop_dimensions(header: &TexHeader, mipmap: &TexMipmap) -> (u32, u32) {
+    let scale = |image: u32, texture: u32, mip: u32| -> u32 {
+        if texture == 0 {
+            return mip;
+        }
+        let scaled = (image as u64 * mip as u64).div_ceil(texture as u64);
+        (scaled as u32).min(mip)
+    };
+
+    let (image_w, image_h) = header.crop_dimensions();
+    (
+        scale(image_w, header.texture_width, mipmap.width),
+        scale(image_h, header.texture_height, mipmap.height),
+    )
+}
+
+/// Decompress a mipmap of a texture read without decompression.
+///
+/// Already decompressed mipmaps are borrowed as is.
+fn decompressed(mipmap: &TexMipmap) -> Result<Cow<'_, TexMipmap>> {
+    if mipmap.is_lz4_compressed || mipmap.format.is_compressed() {
+        let mut copy = mipmap.clone();
+        MipmapDecompressor::new().decompress(&mut copy)?;
+        Ok(Cow::Owned(copy))
+    } else {
+        Ok(Cow::Borrowed(mipmap))
+    }
+}
+
+/// Detect an embedded image format from its magic bytes.
+///
+/// Returns `None` for formats without a reliable signature (e.g. TGA).
+fn sniff_image_format(bytes: &[u8]) -> Option<MipmapFormat> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(MipmapFormat::ImagePNG)
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(MipmapFormat::ImageJPEG)
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some(MipmapFormat::ImageGIF)
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some(MipmapFormat::ImageWEBP)
+    } else if bytes.starts_with(b"DDS ") {
+        Some(MipmapFormat::ImageDDS)
+    } else if bytes.starts_with(b"II*\0") || bytes.starts_with(b"MM\0*") {
+        Some(MipmapFormat::ImageTIFF)
+    } else if bytes.starts_with(b"BM") {
+        Some(MipmapFormat::ImageBMP)
+    } else if bytes.starts_with(&[0, 0, 1, 0]) || bytes.starts_with(&[0, 0, 2, 0]) {
+        // ICO (type 1) or CUR (type 2)
+        Some(MipmapFormat::ImageICO)
+    } else {
+        None
+    }
+}
+
+/// Decode an embedded image using the decoder for its format.
+fn decode_embedded(bytes: &[u8], format: MipmapFormat) -> Result<DynamicImage> {
+    let image_format = match format {
+        MipmapFormat::ImagePNG => ImageFormat::Png,
+        MipmapFormat::ImageJPEG => ImageFormat::Jpeg,
+        MipmapFormat::ImageGIF => ImageFormat::Gif,
+        MipmapFormat::ImageWEBP => ImageFormat::WebP,
+        MipmapFormat::ImageBMP => ImageFormat::Bmp,
+        MipmapFormat::ImageTIFF => ImageFormat::Tiff,
+        MipmapFormat::ImageTGA => ImageFormat::Tga,
+        MipmapFormat::ImageDDS => ImageFormat::Dds,
+        // Picks the largest, deepest entry; CUR files share the ICO layout
+        MipmapFormat::ImageICO => ImageFormat::Ico,
+        _ => return Ok(image::load_from_memory(bytes)?),
+    };
+    Ok(image::load_from_memory_with_format(bytes, image_format)?)
+}
+
+/// Decode a raw mipmap buffer into an image without parsing a TEX container.
+///
+/// Supported input formats are the raw pixel formats (`RGBA8888`, `RG88`, `R8`)
+/// and the DXT block formats (`CompressedDXT1`, `CompressedDXT3`,
+/// `CompressedDXT5`), which are decompressed to RGBA8888 first. LZ4-compressed
+/// data must be decompressed by the caller.
+///
+/// If the buffer size doesn't match `format` for the given dimensions, the
+/// actual raw format is inferred from the size (4, 2 or 1 bytes per pixel),
+/// matching how TEX conversion handles headers that misreport their format.
+/// When `flip_y` is set, the decoded image is flipped vertically.
+pub fn decode_raw(
+    bytes: &[u8],
+    width: u32,
+    height: u32,
+    format: MipmapFormat,
+    flip_y: bool,
+) -> Result<DynamicImage> {
+    let mut mipmap = TexMipmap::new(width, height);
+    mipmap.format = format;
+    mipmap.bytes = bytes.to_vec();
+
+    if format.is_compressed() {
+        MipmapDecompressor::new().decompress(&mut mipmap)?;
+    }
+
+    let image = TexToImageConverter::new().mipmap_to_image(&mipmap)?;
+    Ok(if flip_y { image.flipv() } else { image })
+}
+
+impl Default for TexToImageConverter<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Extension trait for TexFrameInfo
+trait TexFrameInfoExt {
+    fn gif_width(&self) -> u32;
+    fn gif_height(&self) -> u32;
+}
+
+impl TexFrameInfoExt for TexFrameInfo {
+    fn gif_width(&self) -> u32 {
+        self.actual_width() as u32
     }
 
     fn gif_height(&self) -> u32 {
@@ -460,6 +2082,124 @@ impl TexFrameInfoExt for repkg_core::TexFrameInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use repkg_core::{FreeImageFormat, TexFlags, TexFormat, TexHeader, TexImage};
+
+    /// Build a single-image texture around one mipmap.
+    fn tex_with_mipmap(mipmap: TexMipmap) -> Tex {
+        let mut header = TexHeader::new();
+        header.texture_width = mipmap.width;
+        header.texture_height = mipmap.height;
+        header.image_width = mipmap.width;
+        header.image_height = mipmap.height;
+        header.flags = TexFlags::NONE;
+        header.format = TexFormat::RGBA8888;
+
+        let mut tex = Tex::new(header);
+        tex.images_container.image_format = FreeImageFormat::Unknown;
+        let mut image = TexImage::new();
+        image.mipmaps.push(mipmap);
+        tex.images_container.images.push(image);
+        tex
+    }
+
+    fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+            width,
+            height,
+            image::Rgba([10, 20, 30, 255]),
+        ));
+        let mut bytes = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    /// A minimal 4x4 DXT1 DDS file with a single solid block.
+    fn dds_dxt1_bytes() -> Vec<u8> {
+        let mut header = [0u32; 31];
+        header[0] = 124; // dwSize
+        header[1] = 0x0008_1007; // CAPS | HEIGHT | WIDTH | PIXELFORMAT | LINEARSIZE
+        header[2] = 4; // height
+        header[3] = 4; // width
+        header[4] = 8; // linear size
+        header[6] = 1; // mipmap count
+        header[18] = 32; // pixel format size
+        header[19] = 0x4; // DDPF_FOURCC
+        header[20] = u32::from_le_bytes(*b"DXT1");
+        header[26] = 0x1000; // DDSCAPS_TEXTURE
+
+        let mut bytes = b"DDS ".to_vec();
+        for value in header {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0]);
+        bytes
+    }
+
+    /// A single-entry ICO (or CUR) file wrapping a PNG image.
+    fn ico_bytes(resource_type: u16, width: u32, height: u32) -> Vec<u8> {
+        let png = png_bytes(width, height);
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&0u16.to_le_bytes()); // reserved
+        bytes.extend_from_slice(&resource_type.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // image count
+        bytes.push(width as u8);
+        bytes.push(height as u8);
+        bytes.extend_from_slice(&[0, 0]); // color count, reserved
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // planes / hotspot x
+        bytes.extend_from_slice(&32u16.to_le_bytes()); // bit depth / hotspot y
+        bytes.extend_from_slice(&(png.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&22u32.to_le_bytes()); // data offset
+        bytes.extend_from_slice(&png);
+        bytes
+    }
+
+    /// A two-frame animated texture built from a 8x4 RGBA atlas.
+    fn gif_tex() -> Tex {
+        let mut atlas = raw_mipmap(MipmapFormat::RGBA8888, 8, 4);
+        for (i, pixel) in atlas.bytes.chunks_mut(4).enumerate() {
+            let left = i % 8 < 4;
+            pixel.copy_from_slice(if left {
+                &[255, 0, 0, 255]
+            } else {
+                &[0, 0, 255, 255]
+            });
+        }
+
+        let mut tex = tex_with_mipmap(atlas);
+        tex.header.flags = TexFlags::IS_GIF;
+        let mut frame_info = repkg_core::TexFrameInfoContainer::new(4, 4);
+        for (i, x) in [0.0, 4.0].into_iter().enumerate() {
+            frame_info.frames.push(TexFrameInfo {
+                image_id: 0,
+                frametime: 0.1 * (i + 1) as f32,
+                x,
+                y: 0.0,
+                width: 4.0,
+                height: 4.0,
+                width_y: 0.0,
+                height_x: 0.0,
+            });
+        }
+        tex.frame_info_container = Some(frame_info);
+        tex
+    }
+
+    fn raw_mipmap(format: MipmapFormat, width: u32, height: u32) -> TexMipmap {
+        let mut mipmap = TexMipmap::new(width, height);
+        mipmap.format = format;
+        let bpp = format.bytes_per_pixel().unwrap_or(4) as usize;
+        mipmap.bytes = vec![128; width as usize * height as usize * bpp];
+        mipmap
+    }
+
+    fn embedded_mipmap(format: MipmapFormat, bytes: Vec<u8>, width: u32, height: u32) -> TexMipmap {
+        let mut mipmap = TexMipmap::new(width, height);
+        mipmap.format = format;
+        mipmap.bytes = bytes;
+        mipmap
+    }
 
     #[test]
     fn test_output_format_extension() {
@@ -474,6 +2214,1061 @@ mod tests {
         assert_eq!(OutputFormat::parse("PNG"), Some(OutputFormat::Png));
         assert_eq!(OutputFormat::parse("jpg"), Some(OutputFormat::Jpeg));
         assert_eq!(OutputFormat::parse("jpeg"), Some(OutputFormat::Jpeg));
+        assert_eq!(OutputFormat::parse("QOI"), Some(OutputFormat::Qoi));
         assert_eq!(OutputFormat::parse("unknown"), None);
     }
+
+    #[test]
+    fn test_debug_comparison() {
+        let mut mipmap = raw_mipmap(MipmapFormat::RGBA8888, 4, 2);
+        mipmap.bytes[3] = 0;
+        let tex = tex_with_mipmap(mipmap);
+
+        let comparison = TexToImageConverter::new()
+            .with_checkerboard(2)
+            .debug_comparison(&tex)
+            .unwrap()
+            .to_rgba8();
+
+        assert_eq!(comparison.dimensions(), (8, 2));
+        assert_eq!(comparison.get_pixel(0, 0).0, [204, 204, 204, 255]);
+        assert_eq!(comparison.get_pixel(4, 0).0, [0, 0, 0, 255]);
+        assert_eq!(comparison.get_pixel(5, 0).0, [128, 128, 128, 255]);
+    }
+
+    #[test]
+    fn test_checkerboard_disables_passthrough() {
+        let mut png = Vec::new();
+        DynamicImage::ImageRgba8(RgbaImage::new(3, 3))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let tex = tex_with_mipmap(embedded_mipmap(MipmapFormat::ImagePNG, png.clone(), 3, 3));
+
+        let passthrough = TexToImageConverter::new()
+            .convert(&tex, OutputFormat::Png)
+            .unwrap();
+        let composited = TexToImageConverter::new()
+            .with_checkerboard(4)
+            .convert(&tex, OutputFormat::Png)
+            .unwrap();
+
+        assert_eq!(passthrough.bytes, png);
+        assert_ne!(composited.bytes, passthrough.bytes);
+        let image = image::load_from_memory(&composited.bytes).unwrap();
+        assert!(image.to_rgba8().pixels().all(|p| p[3] == 255));
+    }
+
+    #[test]
+    fn test_normal_convention_inverts_green() {
+        let mut mipmap = raw_mipmap(MipmapFormat::RG88, 1, 1);
+        mipmap.bytes = vec![128, 200];
+        let tex = tex_with_mipmap(mipmap);
+        let green = |convention| {
+            let converter = TexToImageConverter::new()
+                .with_normal_reconstruction(true)
+                .with_normal_convention(convention);
+            let result = converter.convert(&tex, OutputFormat::Png).unwrap();
+            image::load_from_memory(&result.bytes)
+                .unwrap()
+                .to_rgba8()
+                .get_pixel(0, 0)
+                .0[1]
+        };
+
+        assert_eq!(green(NormalConvention::DirectX), 200);
+        assert_eq!(green(NormalConvention::OpenGl), 55);
+        assert_eq!(
+            NormalConvention::parse("GL"),
+            Some(NormalConvention::OpenGl)
+        );
+        assert_eq!(NormalConvention::parse("xyz"), None);
+    }
+
+    #[test]
+    fn test_convert_decoded_matches_convert() {
+        let tex = tex_with_mipmap(raw_mipmap(MipmapFormat::RGBA8888, 4, 4));
+        let converter = TexToImageConverter::new();
+        let image = converter.decode(&tex).unwrap();
+
+        for format in [OutputFormat::Png, OutputFormat::Jpeg, OutputFormat::Bmp] {
+            let direct = converter.convert(&tex, format).unwrap();
+            let reused = converter.convert_decoded(&image, format).unwrap();
+            assert_eq!(direct.bytes, reused.bytes, "{}", format);
+            assert_eq!(reused.format, format);
+        }
+
+        assert!(converter.decode(&gif_tex()).is_err());
+    }
+
+    #[test]
+    fn test_premultiplied_alpha() {
+        let mut mipmap = raw_mipmap(MipmapFormat::RGBA8888, 1, 1);
+        mipmap.bytes = vec![255, 0, 0, 128];
+        let tex = tex_with_mipmap(mipmap);
+        let pixel = |converter: TexToImageConverter| {
+            let result = converter.convert(&tex, OutputFormat::Png).unwrap();
+            image::load_from_memory(&result.bytes)
+                .unwrap()
+                .to_rgba8()
+                .get_pixel(0, 0)
+                .0
+        };
+
+        assert_eq!(pixel(TexToImageConverter::new()), [255, 0, 0, 128]);
+        assert_eq!(
+            pixel(TexToImageConverter::new().with_premultiplied_alpha(true)),
+            [128, 0, 0, 128]
+        );
+    }
+
+    #[test]
+    fn test_swizzle_swaps_red_and_blue() {
+        let mut mipmap = raw_mipmap(MipmapFormat::RGBA8888, 1, 1);
+        mipmap.bytes = vec![10, 20, 30, 40];
+        let tex = tex_with_mipmap(mipmap);
+        let pixel = |converter: TexToImageConverter| {
+            let result = converter.convert(&tex, OutputFormat::Png).unwrap();
+            image::load_from_memory(&result.bytes)
+                .unwrap()
+                .to_rgba8()
+                .get_pixel(0, 0)
+                .0
+        };
+
+        assert_eq!(pixel(TexToImageConverter::new()), [10, 20, 30, 40]);
+        assert_eq!(
+            pixel(TexToImageConverter::new().with_swizzle([
+                Channel::B,
+                Channel::G,
+                Channel::R,
+                Channel::A
+            ])),
+            [30, 20, 10, 40]
+        );
+        assert_eq!(
+            pixel(TexToImageConverter::new().with_swizzle([
+                Channel::A,
+                Channel::A,
+                Channel::Zero,
+                Channel::One
+            ])),
+            [40, 40, 0, 255]
+        );
+    }
+
+    #[test]
+    fn test_rg88_normal_reconstruction() {
+        let tex = tex_with_mipmap(raw_mipmap(MipmapFormat::RG88, 2, 2));
+
+        let converter = TexToImageConverter::new().with_normal_reconstruction(true);
+        let image = converter
+            .mipmap_to_image(tex.first_image().unwrap().first_mipmap().unwrap())
+            .unwrap();
+        let DynamicImage::ImageRgba8(image) = image else {
+            panic!("expected RGBA output");
+        };
+        let [r, g, b, a] = image.get_pixel(1, 1).0;
+        assert_eq!((r, g, a), (128, 128, 255));
+        assert!(b >= 254);
+
+        // Without the option the two channels pass through as luma + alpha
+        let plain = TexToImageConverter::new()
+            .mipmap_to_image(tex.first_image().unwrap().first_mipmap().unwrap())
+            .unwrap();
+        assert!(matches!(plain, DynamicImage::ImageLumaA8(_)));
+    }
+
+    #[test]
+    fn test_jpeg_flattens_onto_background() {
+        let mut mipmap = raw_mipmap(MipmapFormat::RGBA8888, 8, 8);
+        mipmap.bytes.fill(0);
+        let tex = tex_with_mipmap(mipmap);
+
+        let decode = |converter: TexToImageConverter| {
+            let result = converter.convert(&tex, OutputFormat::Jpeg).unwrap();
+            image::load_from_memory(&result.bytes).unwrap().to_rgb8()
+        };
+        let close = |actual: [u8; 3], expected: [u8; 3]| {
+            actual
+                .iter()
+                .zip(expected)
+                .all(|(a, e)| (*a as i16 - e as i16).abs() <= 2)
+        };
+
+        let white = decode(TexToImageConverter::new());
+        assert!(close(white.get_pixel(3, 3).0, [255, 255, 255]));
+
+        let blue = decode(TexToImageConverter::new().with_background([0, 0, 255, 255]));
+        assert!(close(blue.get_pixel(3, 3).0, [0, 0, 255]));
+    }
+
+    #[test]
+    fn test_extract_video_frame() {
+        let mut jpeg = Vec::new();
+        DynamicImage::ImageRgb8(image::RgbImage::from_pixel(8, 8, image::Rgb([0, 0, 255])))
+            .write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+            .unwrap();
+        let mp4 = mp4::build_test_mp4(b"jpeg", &[png_bytes(4, 4), jpeg]);
+
+        // The first sample is a PNG mislabelled as JPEG, so decoding it fails
+        let mut tex = tex_with_mipmap(embedded_mipmap(MipmapFormat::VideoMp4, mp4, 8, 8));
+        tex.header.flags = TexFlags::IS_VIDEO_TEXTURE;
+        let converter = TexToImageConverter::new();
+        assert!(converter
+            .extract_video_frame(&tex, 0, OutputFormat::Png)
+            .is_err());
+
+        let result = converter
+            .extract_video_frame(&tex, 1, OutputFormat::Png)
+            .unwrap();
+        let frame = image::load_from_memory(&result.bytes).unwrap().to_rgb8();
+        assert_eq!(frame.dimensions(), (8, 8));
+        assert!(frame.get_pixel(4, 4).0[2] > 250);
+
+        assert!(converter
+            .extract_video_frame(&tex, 2, OutputFormat::Png)
+            .is_err());
+        assert!(converter
+            .extract_video_poster(&tex, OutputFormat::Png)
+            .is_err());
+    }
+
+    #[test]
+    fn test_extract_video_frame_unsupported_codec() {
+        let mp4 = mp4::build_test_mp4(b"avc1", &[vec![0; 16]]);
+        let mut tex = tex_with_mipmap(embedded_mipmap(MipmapFormat::VideoMp4, mp4, 8, 8));
+        tex.header.flags = TexFlags::IS_VIDEO_TEXTURE;
+
+        let err = TexToImageConverter::new()
+            .extract_video_frame(&tex, 0, OutputFormat::Png)
+            .unwrap_err();
+        assert!(matches!(err, Error::UnsupportedVideoCodec { codec } if codec == "avc1"));
+    }
+
+    #[test]
+    fn test_gamma_and_brightness() {
+        let tex = tex_with_mipmap(raw_mipmap(MipmapFormat::RGBA8888, 2, 2));
+        let convert = |converter: TexToImageConverter| {
+            let result = converter.convert(&tex, OutputFormat::Png).unwrap();
+            image::load_from_memory(&result.bytes)
+                .unwrap()
+                .to_rgba8()
+                .get_pixel(0, 0)
+                .0
+        };
+
+        assert_eq!(convert(TexToImageConverter::new()), [128; 4]);
+        assert_eq!(
+            convert(TexToImageConverter::new().with_brightness(20)),
+            [148, 148, 148, 128]
+        );
+        let [r, _, _, a] = convert(TexToImageConverter::new().with_gamma(2.2));
+        assert_eq!((r, a), (186, 128));
+    }
+
+    #[cfg(feature = "buffer-pool")]
+    #[test]
+    fn test_convert_with_pool_recycles_buffers() {
+        let pool = BufferPool::new();
+        let tex = tex_with_mipmap(raw_mipmap(MipmapFormat::RGBA8888, 2, 2));
+        let plain = TexToImageConverter::new()
+            .convert(&tex, OutputFormat::Png)
+            .unwrap();
+
+        let converter = TexToImageConverter::new().with_pool(&pool);
+        for _ in 0..3 {
+            let pooled = converter.convert(&tex, OutputFormat::Png).unwrap();
+            assert_eq!(pooled.bytes, plain.bytes);
+            assert_eq!(pool.pooled_bytes(), 1);
+        }
+    }
+
+    #[test]
+    fn test_cancel_flag() {
+        let tex = tex_with_mipmap(raw_mipmap(MipmapFormat::RGBA8888, 2, 2));
+        let flag = AtomicBool::new(false);
+        let converter = TexToImageConverter::new().with_cancel_flag(&flag);
+        assert!(converter.convert(&tex, OutputFormat::Png).is_ok());
+
+        flag.store(true, Ordering::Relaxed);
+        assert!(matches!(
+            converter.convert(&tex, OutputFormat::Png),
+            Err(Error::Cancelled)
+        ));
+        assert!(matches!(
+            converter.convert(&gif_tex(), OutputFormat::Gif),
+            Err(Error::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn test_cancel_flag_polled_during_conversion() {
+        /// Requests cancellation from the second poll on, once conversion is under way.
+        #[derive(Debug, Default)]
+        struct CancelOnSecondPoll(std::sync::atomic::AtomicUsize);
+
+        impl CancelFlag for CancelOnSecondPoll {
+            fn is_cancelled(&self) -> bool {
+                self.0.fetch_add(1, Ordering::Relaxed) >= 1
+            }
+        }
+
+        let flag = CancelOnSecondPoll::default();
+        let converter = TexToImageConverter::new().with_cancel_flag(&flag);
+        assert!(matches!(
+            converter.convert(&gif_tex(), OutputFormat::Gif),
+            Err(Error::Cancelled)
+        ));
+        assert_eq!(flag.0.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_convert_traced() {
+        // Header says RG88 but the data is one byte per pixel
+        let mut mipmap = raw_mipmap(MipmapFormat::RG88, 4, 4);
+        mipmap.bytes.truncate(16);
+        let mut tex = tex_with_mipmap(mipmap);
+        tex.header.format = TexFormat::RG88;
+        tex.header.texture_width = 4;
+        tex.header.texture_height = 4;
+        tex.header.image_width = 3;
+        tex.header.image_height = 2;
+        tex.images_container.magic = "TEXB0004".to_string();
+
+        let (result, trace) = TexToImageConverter::new()
+            .convert_traced(&tex, OutputFormat::Png)
+            .unwrap();
+        assert_eq!(result.format, OutputFormat::Png);
+        assert!(trace.downgraded_to_v3);
+        assert_eq!(trace.kind, TextureKind::Static);
+        assert_eq!(trace.declared_format, MipmapFormat::RG88);
+        assert_eq!(trace.inferred_format, MipmapFormat::R8);
+        assert!(trace.cropped);
+        assert!(!trace.passthrough);
+        assert!(!trace.lz4_compressed && !trace.dxt_decompressed);
+        assert_eq!(trace.output_dimensions, Some((3, 2)));
+        assert_eq!(trace.notes.len(), 2);
+    }
+
+    #[test]
+    fn test_conversion_result_dimensions() {
+        let converter = TexToImageConverter::new();
+
+        let gif = gif_tex();
+        for format in [OutputFormat::Gif, OutputFormat::WebP, OutputFormat::Png] {
+            let result = converter.convert(&gif, format).unwrap();
+            assert_eq!((result.width, result.height), (4, 4), "{}", format);
+        }
+
+        // Passed-through embedded images report their own size
+        let png = png_bytes(5, 3);
+        let tex = tex_with_mipmap(embedded_mipmap(MipmapFormat::ImagePNG, png.clone(), 5, 3));
+        let result = converter.convert(&tex, OutputFormat::Png).unwrap();
+        assert_eq!(result.bytes, png);
+        assert_eq!((result.width, result.height), (5, 3));
+    }
+
+    #[test]
+    fn test_convert_all() {
+        let mut tex = tex_with_mipmap(raw_mipmap(MipmapFormat::RGBA8888, 4, 2));
+        let mut second = TexImage::new();
+        second
+            .mipmaps
+            .push(raw_mipmap(MipmapFormat::RGBA8888, 2, 1));
+        tex.images_container.images.push(second);
+        tex.header.texture_width = 4;
+        tex.header.texture_height = 2;
+        tex.header.image_width = 3;
+        tex.header.image_height = 2;
+
+        let results = TexToImageConverter::new()
+            .convert_all(&tex, OutputFormat::Png)
+            .unwrap();
+        assert_eq!(results.len(), 2);
+
+        let dimensions: Vec<_> = results
+            .iter()
+            .map(|result| {
+                assert_eq!(result.format, OutputFormat::Png);
+                let image = image::load_from_memory(&result.bytes).unwrap();
+                (image.width(), image.height())
+            })
+            .collect();
+        assert_eq!(dimensions, vec![(3, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn test_would_passthrough() {
+        let converter = TexToImageConverter::new();
+        let embedded = tex_with_mipmap(embedded_mipmap(
+            MipmapFormat::ImagePNG,
+            png_bytes(2, 2),
+            2,
+            2,
+        ));
+        assert!(converter.would_passthrough(&embedded, OutputFormat::Png));
+        assert!(!converter.would_passthrough(&embedded, OutputFormat::Jpeg));
+        assert!(!converter
+            .with_gamma(1.5)
+            .would_passthrough(&embedded, OutputFormat::Png));
+
+        let result = converter.convert(&embedded, OutputFormat::Png).unwrap();
+        assert_eq!(result.bytes, png_bytes(2, 2));
+
+        let raw = tex_with_mipmap(raw_mipmap(MipmapFormat::RGBA8888, 2, 2));
+        assert!(!converter.would_passthrough(&raw, OutputFormat::Png));
+    }
+
+    #[test]
+    fn test_convert_to_qoi() {
+        let tex = tex_with_mipmap(raw_mipmap(MipmapFormat::RGBA8888, 5, 3));
+        let result = TexToImageConverter::new()
+            .convert(&tex, OutputFormat::Qoi)
+            .unwrap();
+
+        assert_eq!(result.format, OutputFormat::Qoi);
+        assert_eq!(&result.bytes[0..4], b"qoif");
+        let image = image::load_from_memory_with_format(&result.bytes, ImageFormat::Qoi).unwrap();
+        assert_eq!((image.width(), image.height()), (5, 3));
+        assert_eq!(image.to_rgba8().get_pixel(4, 2).0, [128; 4]);
+    }
+
+    #[test]
+    fn test_decode_raw_rgba() {
+        let bytes = [255, 0, 0, 255, 0, 255, 0, 255];
+        let image = decode_raw(&bytes, 2, 1, MipmapFormat::RGBA8888, false).unwrap();
+        assert_eq!(image.to_rgba8().get_pixel(1, 0).0, [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn test_decode_raw_infers_format_from_size() {
+        // Declared RG88 but only one byte per pixel
+        let bytes = [10, 20, 30, 40];
+        let image = decode_raw(&bytes, 2, 2, MipmapFormat::RG88, false).unwrap();
+        assert!(matches!(image, DynamicImage::ImageLuma8(_)));
+    }
+
+    #[test]
+    fn test_decode_raw_flip_y() {
+        let bytes = [1, 2];
+        let image = decode_raw(&bytes, 1, 2, MipmapFormat::R8, true).unwrap();
+        assert_eq!(image.to_luma8().get_pixel(0, 0).0, [2]);
+    }
+
+    #[test]
+    fn test_decode_raw_bad_size() {
+        let bytes = [0u8; 3];
+        assert!(decode_raw(&bytes, 2, 2, MipmapFormat::RGBA8888, false).is_err());
+    }
+
+    #[test]
+    fn test_sniff_image_format() {
+        assert_eq!(
+            sniff_image_format(&png_bytes(1, 1)),
+            Some(MipmapFormat::ImagePNG)
+        );
+        assert_eq!(
+            sniff_image_format(&dds_dxt1_bytes()),
+            Some(MipmapFormat::ImageDDS)
+        );
+        assert_eq!(sniff_image_format(&[0, 1, 2, 3]), None);
+    }
+
+    #[test]
+    fn test_declared_dds_but_png_bytes() {
+        let png = png_bytes(4, 4);
+        let tex = tex_with_mipmap(embedded_mipmap(MipmapFormat::ImageDDS, png.clone(), 4, 4));
+
+        let result = TexToImageConverter::new()
+            .convert(&tex, OutputFormat::Png)
+            .unwrap();
+
+        // Detected as PNG, so passed through untouched
+        assert_eq!(result.bytes, png);
+    }
+
+    #[test]
+    fn test_declared_png_but_dds_bytes() {
+        let tex = tex_with_mipmap(embedded_mipmap(
+            MipmapFormat::ImagePNG,
+            dds_dxt1_bytes(),
+            4,
+            4,
+        ));
+
+        let result = TexToImageConverter::new()
+            .convert(&tex, OutputFormat::Png)
+            .unwrap();
+
+        // Must be re-encoded rather than passing the DDS bytes through as PNG
+        assert_eq!(&result.bytes[0..8], b"\x89PNG\r\n\x1a\n");
+        let image = image::load_from_memory(&result.bytes).unwrap();
+        assert_eq!((image.width(), image.height()), (4, 4));
+    }
+
+    #[test]
+    fn test_convert_mipmap_scales_crop() {
+        let mut tex = tex_with_mipmap(raw_mipmap(MipmapFormat::R8, 8, 8));
+        tex.images_container.images[0]
+            .mipmaps
+            .push(raw_mipmap(MipmapFormat::R8, 4, 4));
+        tex.header.image_width = 6;
+        tex.header.image_height = 4;
+
+        let converter = TexToImageConverter::new();
+        let level0 = converter
+            .convert_mipmap(&tex, 0, OutputFormat::Png)
+            .unwrap();
+        let level1 = converter
+            .convert_mipmap(&tex, 1, OutputFormat::Png)
+            .unwrap();
+
+        let level0 = image::load_from_memory(&level0.bytes).unwrap();
+        let level1 = image::load_from_memory(&level1.bytes).unwrap();
+        assert_eq!((level0.width(), level0.height()), (6, 4));
+        assert_eq!((level1.width(), level1.height()), (3, 2));
+
+        assert!(converter
+            .convert_mipmap(&tex, 2, OutputFormat::Png)
+            .is_err());
+    }
+
+    #[test]
+    fn test_convert_without_crop_keeps_texture_size() {
+        let mut tex = tex_with_mipmap(raw_mipmap(MipmapFormat::R8, 8, 8));
+        tex.header.image_width = 6;
+        tex.header.image_height = 4;
+
+        let result = TexToImageConverter::new()
+            .with_crop(false)
+            .convert(&tex, OutputFormat::Png)
+            .unwrap();
+        let image = image::load_from_memory(&result.bytes).unwrap();
+        assert_eq!(
+            (image.width(), image.height()),
+            (tex.header.texture_width, tex.header.texture_height)
+        );
+        assert_eq!((result.width, result.height), (8, 8));
+    }
+
+    #[test]
+    fn test_frame_compositing_keeps_previous_frame() {
+        // An 8x4 canvas: frame 0 is red at (0, 0), frame 1 at (4, 0) is
+        // transparent in its left half and blue in its right half
+        let mut tex = gif_tex();
+        let atlas = &mut tex.images_container.images[0].mipmaps[0].bytes;
+        for (i, pixel) in atlas.chunks_mut(4).enumerate() {
+            pixel.copy_from_slice(match i % 8 {
+                0..=3 => &[255, 0, 0, 255],
+                6 | 7 => &[0, 0, 255, 255],
+                _ => &[0, 0, 0, 0],
+            });
+        }
+        let frame_info = tex.frame_info_container.as_mut().unwrap();
+        frame_info.gif_width = 8;
+
+        let frames = TexToImageConverter::new()
+            .with_frame_compositing(true)
+            .extract_frames(&tex)
+            .unwrap();
+        let (first, second) = (frames[0].buffer(), frames[1].buffer());
+        assert_eq!(first.dimensions(), (8, 4));
+        assert_eq!(first.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(first.get_pixel(7, 0).0, [0, 0, 0, 0]);
+        assert_eq!(second.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(second.get_pixel(5, 0).0, [0, 0, 0, 0]);
+        assert_eq!(second.get_pixel(7, 3).0, [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_16_bit_source_keeps_depth() {
+        let source =
+            image::ImageBuffer::from_pixel(2, 2, image::Rgba([0x1234u16, 0, 0xFFFF, 0xFFFF]));
+        let mut tiff = Vec::new();
+        DynamicImage::ImageRgba16(source)
+            .write_to(&mut Cursor::new(&mut tiff), ImageFormat::Tiff)
+            .unwrap();
+        let tex = tex_with_mipmap(embedded_mipmap(MipmapFormat::ImageTIFF, tiff, 2, 2));
+
+        let converter = TexToImageConverter::new();
+        let png = converter.convert(&tex, OutputFormat::Png).unwrap();
+        let decoded = image::load_from_memory(&png.bytes).unwrap();
+        assert_eq!(decoded.color(), image::ColorType::Rgba16);
+        assert_eq!(
+            decoded.to_rgba16().get_pixel(1, 1).0,
+            [0x1234, 0, 0xFFFF, 0xFFFF]
+        );
+
+        // Formats limited to 8 bits per channel still encode
+        for format in [OutputFormat::Jpeg, OutputFormat::Bmp, OutputFormat::Qoi] {
+            let result = converter.convert(&tex, format).unwrap();
+            let decoded = image::load_from_memory(&result.bytes).unwrap();
+            assert_eq!(
+                decoded.color().bytes_per_pixel(),
+                decoded.color().channel_count()
+            );
+        }
+    }
+
+    #[test]
+    fn test_embedded_mipmap_level_passthrough() {
+        let level0 = png_bytes(4, 4);
+        let level1 = png_bytes(2, 2);
+        let mut tex = tex_with_mipmap(embedded_mipmap(MipmapFormat::ImagePNG, level0, 4, 4));
+        tex.images_container.images[0].mipmaps.push(embedded_mipmap(
+            MipmapFormat::ImagePNG,
+            level1.clone(),
+            2,
+            2,
+        ));
+
+        let converter = TexToImageConverter::new();
+        let result = converter
+            .convert_mipmap(&tex, 1, OutputFormat::Png)
+            .unwrap();
+        assert_eq!(result.bytes, level1);
+        assert_eq!((result.width, result.height), (2, 2));
+
+        let reencoded = converter
+            .with_passthrough(false)
+            .with_png_compression(CompressionType::Best)
+            .convert_mipmap(&tex, 1, OutputFormat::Png)
+            .unwrap();
+        assert_ne!(reencoded.bytes, level1);
+        assert_eq!(
+            image::load_from_memory(&reencoded.bytes)
+                .unwrap()
+                .to_rgba8(),
+            image::load_from_memory(&level1).unwrap().to_rgba8()
+        );
+    }
+
+    #[test]
+    fn test_assemble_frame_arbitrary_rotation() {
+        let atlas = DynamicImage::ImageRgba8(RgbaImage::from_pixel(64, 64, image::Rgba([255; 4])));
+        let frame_info = TexFrameInfo {
+            image_id: 0,
+            frametime: 0.1,
+            x: 8.0,
+            y: 8.0,
+            width: 40.0,
+            height: 24.0,
+            width_y: 0.0,
+            height_x: 0.0,
+        };
+
+        let frame =
+            TexToImageConverter::new().assemble_frame(&atlas, &frame_info, 30f64.to_radians());
+
+        assert_eq!(frame.width(), frame_info.gif_width());
+        assert_eq!(frame.height(), frame_info.gif_height());
+        // Corners uncovered by the rotation stay transparent
+        assert_eq!(frame.to_rgba8().get_pixel(0, 0).0[3], 0);
+    }
+
+    #[test]
+    fn test_convert_embedded_ico_and_cur() {
+        for resource_type in [1, 2] {
+            let tex = tex_with_mipmap(embedded_mipmap(
+                MipmapFormat::ImageICO,
+                ico_bytes(resource_type, 16, 8),
+                16,
+                8,
+            ));
+
+            let result = TexToImageConverter::new()
+                .convert(&tex, OutputFormat::Png)
+                .unwrap();
+
+            let image = image::load_from_memory(&result.bytes).unwrap();
+            assert_eq!((image.width(), image.height()), (16, 8));
+        }
+    }
+
+    #[test]
+    fn test_to_filmstrip() {
+        let converter = TexToImageConverter::new();
+        let tex = gif_tex();
+
+        let horizontal = converter
+            .to_filmstrip(&tex, FilmstripLayout::Horizontal, 2, OutputFormat::Png)
+            .unwrap();
+        assert_eq!(horizontal.frame_count, 2);
+        assert_eq!((horizontal.cell_width, horizontal.cell_height), (4, 4));
+        assert_eq!(horizontal.frame_durations_ms, vec![100, 200]);
+        let image = image::load_from_memory(&horizontal.result.bytes)
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(image.dimensions(), (10, 4));
+        assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(4, 0).0[3], 0);
+        assert_eq!(image.get_pixel(6, 0).0, [0, 0, 255, 255]);
+
+        let vertical = converter
+            .to_filmstrip(&tex, FilmstripLayout::Vertical, 0, OutputFormat::Png)
+            .unwrap();
+        let image = image::load_from_memory(&vertical.result.bytes).unwrap();
+        assert_eq!((image.width(), image.height()), (4, 8));
+    }
+
+    #[test]
+    fn test_to_contact_sheet() {
+        let mut tex = gif_tex();
+        let frame_info = tex.frame_info_container.as_mut().unwrap();
+        let extra = frame_info.frames.clone();
+        frame_info.frames.extend(extra);
+
+        let converter = TexToImageConverter::new();
+        let result = converter
+            .to_contact_sheet(&tex, 2, OutputFormat::Png)
+            .unwrap();
+        let image = image::load_from_memory(&result.bytes).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (8, 8));
+        assert_eq!((result.width, result.height), (8, 8));
+        assert_eq!(image.get_pixel(0, 4).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(4, 4).0, [0, 0, 255, 255]);
+
+        let still = TexToImageConverter::new().to_contact_sheet(
+            &tex_with_mipmap(raw_mipmap(MipmapFormat::R8, 4, 4)),
+            2,
+            OutputFormat::Png,
+        );
+        assert!(still.is_err());
+    }
+
+    #[test]
+    fn test_filmstrip_layout_parse() {
+        assert_eq!(
+            FilmstripLayout::parse("Vertical"),
+            Some(FilmstripLayout::Vertical)
+        );
+        assert_eq!(
+            FilmstripLayout::parse("h"),
+            Some(FilmstripLayout::Horizontal)
+        );
+        assert_eq!(FilmstripLayout::parse("diagonal"), None);
+    }
+
+    #[test]
+    fn test_convert_gif_to_animated_webp() {
+        use image::{codecs::webp::WebPDecoder, AnimationDecoder};
+
+        let result = TexToImageConverter::new()
+            .convert(&gif_tex(), OutputFormat::WebP)
+            .unwrap();
+
+        assert_eq!(result.format, OutputFormat::WebP);
+        assert_eq!(&result.bytes[0..4], b"RIFF");
+        assert_eq!(&result.bytes[8..12], b"WEBP");
+
+        let frames = WebPDecoder::new(Cursor::new(&result.bytes))
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].buffer().get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(frames[1].buffer().get_pixel(0, 0).0, [0, 0, 255, 255]);
+        assert_eq!(frames[1].delay().numer_denom_ms(), (200, 1));
+    }
+
+    #[test]
+    fn test_embedded_animated_gif_frames() {
+        let mut gif = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut gif);
+            for (color, delay_ms) in [([255, 0, 0, 255], 30), ([0, 0, 255, 255], 70)] {
+                let buffer = RgbaImage::from_pixel(2, 2, Rgba(color));
+                let delay = image::Delay::from_numer_denom_ms(delay_ms, 1);
+                encoder
+                    .encode_frame(Frame::from_parts(buffer, 0, 0, delay))
+                    .unwrap();
+            }
+        }
+
+        let mut tex = tex_with_mipmap(embedded_mipmap(MipmapFormat::ImageGIF, gif, 2, 2));
+        tex.header.flags = TexFlags::IS_GIF;
+        let mut frame_info = repkg_core::TexFrameInfoContainer::new(2, 2);
+        for _ in 0..2 {
+            frame_info.frames.push(TexFrameInfo {
+                image_id: 0,
+                frametime: 0.5,
+                x: 0.0,
+                y: 0.0,
+                width: 2.0,
+                height: 2.0,
+                width_y: 0.0,
+                height_x: 0.0,
+            });
+        }
+        tex.frame_info_container = Some(frame_info);
+
+        // One output frame per frame info, each taking the next GIF frame
+        let frames = TexToImageConverter::new().extract_frames(&tex).unwrap();
+        assert_eq!(frames.len(), 2);
+        let [r, _, b, _] = frames[0].buffer().get_pixel(0, 0).0;
+        assert!(r > 200 && b < 50);
+        let [r, _, b, _] = frames[1].buffer().get_pixel(0, 0).0;
+        assert!(r < 50 && b > 200);
+        assert_eq!(frames[0].delay().numer_denom_ms(), (30, 1));
+        assert_eq!(frames[1].delay().numer_denom_ms(), (70, 1));
+
+        // A forced frame rate still wins over the embedded timings
+        let frames = TexToImageConverter::new()
+            .with_gif_fps(4.0)
+            .extract_frames(&tex)
+            .unwrap();
+        assert!(frames
+            .iter()
+            .all(|frame| frame.delay().numer_denom_ms() == (250, 1)));
+
+        // Extra frame infos wrap around instead of repeating the animation
+        let frame_info = tex.frame_info_container.as_mut().unwrap();
+        let extra = frame_info.frames[0];
+        frame_info.frames.push(extra);
+        let frames = TexToImageConverter::new().extract_frames(&tex).unwrap();
+        assert_eq!(frames.len(), tex.frame_info_container.unwrap().frames.len());
+        let [r, _, b, _] = frames[2].buffer().get_pixel(0, 0).0;
+        assert!(r > 200 && b < 50);
+    }
+
+    #[test]
+    fn test_gif_fps_overrides_frame_delays() {
+        use image::{codecs::gif::GifDecoder, AnimationDecoder};
+
+        let tex = gif_tex();
+        let frames = TexToImageConverter::new()
+            .with_gif_fps(4.0)
+            .extract_frames(&tex)
+            .unwrap();
+        assert!(frames
+            .iter()
+            .all(|frame| frame.delay().numer_denom_ms() == (250, 1)));
+
+        let result = TexToImageConverter::new()
+            .with_gif_fps(4.0)
+            .with_gif_speed(30)
+            .convert(&tex, OutputFormat::Gif)
+            .unwrap();
+        let decoded = GifDecoder::new(Cursor::new(&result.bytes))
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[1].delay().numer_denom_ms(), (250, 1));
+
+        // Invalid rates leave the stored timings alone
+        assert_eq!(TexToImageConverter::new().with_gif_fps(0.0).gif_fps, None);
+    }
+
+    #[test]
+    fn test_tiny_gif_fps_does_not_panic() {
+        let tex = gif_tex();
+        let converter = TexToImageConverter::new().with_gif_fps(1e-39);
+        assert_eq!(converter.gif_fps, Some(MIN_GIF_FPS));
+        let frames = converter.extract_frames(&tex).unwrap();
+        assert_eq!(frames[0].delay().numer_denom_ms(), (100_000, 1));
+
+        // Rates set directly on the field fall back to the stored timings
+        let converter = TexToImageConverter {
+            gif_fps: Some(1e-39),
+            ..TexToImageConverter::new()
+        };
+        let frames = converter.extract_frames(&tex).unwrap();
+        assert_eq!(frames[0].delay().numer_denom_ms(), (100, 1));
+    }
+
+    #[test]
+    fn test_dds_keeps_dxt_blocks() {
+        let mut level0 = TexMipmap::new(8, 8);
+        level0.format = MipmapFormat::CompressedDXT5;
+        level0.bytes = (0..64).collect();
+        let mut level1 = TexMipmap::new(4, 4);
+        level1.format = MipmapFormat::CompressedDXT5;
+        level1.bytes = vec![7; 16];
+        let mut tex = tex_with_mipmap(level0);
+        tex.images_container.images[0].mipmaps.push(level1);
+
+        let converter = TexToImageConverter::new();
+        let result = converter.convert(&tex, OutputFormat::Dds).unwrap();
+        let bytes = &result.bytes;
+        let header =
+            |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        assert_eq!(&bytes[..4], b"DDS ");
+        assert_eq!((header(16), header(12)), (8, 8));
+        assert_eq!(header(28), 2);
+        assert_eq!(&bytes[84..88], b"DXT5");
+        assert_eq!(
+            &bytes[128..192],
+            &tex.images_container.images[0].mipmaps[0].bytes[..]
+        );
+        assert_eq!(&bytes[192..], &[7; 16]);
+
+        // Other formats decode the block data on demand
+        let png = converter.convert(&tex, OutputFormat::Png).unwrap();
+        assert_eq!(png.format, OutputFormat::Png);
+    }
+
+    #[test]
+    fn test_dds_raw_rgba() {
+        let tex = tex_with_mipmap(raw_mipmap(MipmapFormat::RGBA8888, 4, 2));
+        let result = TexToImageConverter::new()
+            .convert(&tex, OutputFormat::Dds)
+            .unwrap();
+
+        assert_eq!(&result.bytes[..4], b"DDS ");
+        assert_eq!(dds::dimensions(&result.bytes), Some((4, 2)));
+        assert_eq!(result.bytes.len(), 128 + 4 * 2 * 4);
+        assert_eq!((result.width, result.height), (4, 2));
+    }
+
+    #[test]
+    fn test_to_raw_rgba() {
+        let mut mipmap = raw_mipmap(MipmapFormat::RGBA8888, 2, 2);
+        mipmap.bytes = (0..16).collect();
+        let tex = tex_with_mipmap(mipmap);
+        let (width, height, bytes) = TexToImageConverter::new().to_raw_rgba(&tex).unwrap();
+        assert_eq!((width, height), (2, 2));
+        assert_eq!(bytes, (0..16).collect::<Vec<u8>>());
+
+        // Other raw formats are expanded to four channels
+        let tex = tex_with_mipmap(raw_mipmap(MipmapFormat::R8, 4, 2));
+        let (width, height, bytes) = TexToImageConverter::new().to_raw_rgba(&tex).unwrap();
+        assert_eq!((width, height), (4, 2));
+        assert_eq!(bytes.len(), 4 * 2 * 4);
+        assert_eq!(&bytes[..4], &[128, 128, 128, 255]);
+
+        let mut mipmap = raw_mipmap(MipmapFormat::RGBA8888, 1, 1);
+        mipmap.bytes = vec![255, 0, 0, 128];
+        let tex = tex_with_mipmap(mipmap);
+        let (_, _, bytes) = TexToImageConverter::new()
+            .with_premultiplied_alpha(true)
+            .to_raw_rgba(&tex)
+            .unwrap();
+        assert_eq!(bytes, [128, 0, 0, 128]);
+    }
+
+    #[test]
+    fn test_to_grayscale_plane() {
+        let mut mipmap = raw_mipmap(MipmapFormat::R8, 4, 2);
+        mipmap.bytes = (0..8).collect();
+        let mut tex = tex_with_mipmap(mipmap);
+        let converter = TexToImageConverter::new();
+        let (width, height, plane) = converter.to_grayscale_plane(&tex).unwrap();
+        assert_eq!((width, height), (4, 2));
+        assert_eq!(plane, (0..8).collect::<Vec<u8>>());
+
+        // Cropping keeps the top-left corner
+        tex.header.image_width = 3;
+        tex.header.image_height = 1;
+        let (width, height, plane) = converter.to_grayscale_plane(&tex).unwrap();
+        assert_eq!((width, height), (3, 1));
+        assert_eq!(plane, vec![0, 1, 2]);
+
+        // RGBA keeps the R channel
+        let mut mipmap = raw_mipmap(MipmapFormat::RGBA8888, 2, 1);
+        mipmap.bytes = vec![10, 20, 30, 40, 50, 60, 70, 80];
+        let tex = tex_with_mipmap(mipmap);
+        let (_, _, plane) = converter.to_grayscale_plane(&tex).unwrap();
+        assert_eq!(plane, vec![10, 50]);
+    }
+
+    #[test]
+    fn test_max_dimension_preserves_aspect_ratio() {
+        let tex = tex_with_mipmap(raw_mipmap(MipmapFormat::R8, 3840, 2160));
+        let converter = TexToImageConverter::new().with_max_dimension(512);
+        let (width, height, _) = converter.to_raw_rgba(&tex).unwrap();
+        assert_eq!((width, height), (512, 288));
+
+        let (_, trace) = converter.convert_traced(&tex, OutputFormat::Bmp).unwrap();
+        assert_eq!(trace.output_dimensions, Some((512, 288)));
+        assert!(trace.post_processed);
+
+        // Smaller images are not upscaled
+        let small = tex_with_mipmap(raw_mipmap(MipmapFormat::R8, 64, 32));
+        let (width, height, _) = converter.to_raw_rgba(&small).unwrap();
+        assert_eq!((width, height), (64, 32));
+
+        let (width, height, _) = TexToImageConverter::new()
+            .with_resize(16, 16, FilterType::Nearest)
+            .to_raw_rgba(&small)
+            .unwrap();
+        assert_eq!((width, height), (16, 16));
+    }
+
+    #[test]
+    fn test_nearest_filter_keeps_pixel_values() {
+        let mut mipmap = raw_mipmap(MipmapFormat::R8, 8, 8);
+        mipmap.bytes = (0..64)
+            .map(|i| if (i / 8 + i % 8) % 2 == 0 { 0 } else { 255 })
+            .collect();
+        let tex = tex_with_mipmap(mipmap);
+
+        let (_, _, pixels) = TexToImageConverter::new()
+            .with_filter(FilterType::Nearest)
+            .with_max_dimension(4)
+            .to_raw_rgba(&tex)
+            .unwrap();
+        assert!(pixels.iter().all(|&value| value == 0 || value == 255));
+    }
+
+    #[test]
+    fn test_png_compression_levels() {
+        let mut mipmap = raw_mipmap(MipmapFormat::R8, 64, 64);
+        mipmap.bytes = (0..64 * 64).map(|i| (i % 64 * 4) as u8).collect();
+        let tex = tex_with_mipmap(mipmap);
+
+        let fast = TexToImageConverter::new()
+            .convert(&tex, OutputFormat::Png)
+            .unwrap();
+        let best = TexToImageConverter::new()
+            .with_png_compression(CompressionType::Best)
+            .with_png_filter(PngFilterType::Sub)
+            .convert(&tex, OutputFormat::Png)
+            .unwrap();
+        assert!(best.bytes.len() <= fast.bytes.len());
+
+        // Compression settings don't change the pixels
+        let decoded = image::load_from_memory(&best.bytes).unwrap();
+        assert_eq!(
+            decoded.to_luma8().as_raw(),
+            &tex.first_image().unwrap().mipmaps[0].bytes
+        );
+    }
+
+    #[test]
+    fn test_estimated_output_bytes() {
+        let mut png = Vec::new();
+        DynamicImage::ImageRgba8(RgbaImage::new(3, 3))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let tex = tex_with_mipmap(embedded_mipmap(MipmapFormat::ImagePNG, png.clone(), 3, 3));
+        let converter = TexToImageConverter::new();
+
+        // Passthrough and raw DDS output are exact
+        assert_eq!(
+            converter.estimated_output_bytes(&tex, OutputFormat::Png),
+            png.len()
+        );
+        let tex = tex_with_mipmap(raw_mipmap(MipmapFormat::RGBA8888, 16, 8));
+        let dds = converter.convert(&tex, OutputFormat::Dds).unwrap();
+        assert_eq!(
+            converter.estimated_output_bytes(&tex, OutputFormat::Dds),
+            dds.bytes.len()
+        );
+
+        // Re-encodes scale with the output size
+        let bmp = converter.estimated_output_bytes(&tex, OutputFormat::Bmp);
+        assert!(bmp >= 16 * 8 * 4);
+        let resized = converter.with_max_dimension(8);
+        assert!(resized.estimated_output_bytes(&tex, OutputFormat::Bmp) < bmp);
+
+        // Animations count every frame
+        let gif = gif_tex();
+        let animated = converter.estimated_output_bytes(&gif, OutputFormat::Gif);
+        let still = converter.estimated_output_bytes(&gif, OutputFormat::Bmp);
+        assert!(animated > 0 && still > 0);
+        assert_eq!(animated, 2 * (4 * 4 / 2));
+    }
 }
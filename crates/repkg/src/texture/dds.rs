@@ -0,0 +1,257 @@
+//! DDS container encoding.
+//!
+//! Writes a legacy DirectDraw Surface header (no DX10 extension) around
+//! either DXT block data, identified by its fourCC, or uncompressed pixels
+//! described by channel bit masks. Levels are stored largest first.
+
+use repkg_core::MipmapFormat;
+
+use crate::error::{Error, Result};
+
+const DDS_MAGIC: &[u8; 4] = b"DDS ";
+const HEADER_SIZE: u32 = 124;
+const PIXEL_FORMAT_SIZE: u32 = 32;
+
+/// Size of the magic and header preceding the level data.
+pub(crate) const FILE_HEADER_SIZE: usize = 4 + HEADER_SIZE as usize;
+
+const DDSD_CAPS: u32 = 0x1;
+const DDSD_HEIGHT: u32 = 0x2;
+const DDSD_WIDTH: u32 = 0x4;
+const DDSD_PITCH: u32 = 0x8;
+const DDSD_PIXELFORMAT: u32 = 0x1000;
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDSD_LINEARSIZE: u32 = 0x80000;
+
+const DDPF_ALPHAPIXELS: u32 = 0x1;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDPF_LUMINANCE: u32 = 0x20000;
+
+const DDSCAPS_COMPLEX: u32 = 0x8;
+const DDSCAPS_TEXTURE: u32 = 0x1000;
+const DDSCAPS_MIPMAP: u32 = 0x400000;
+
+/// A single level of a DDS mip chain.
+pub(crate) struct DdsLevel<'a> {
+    pub width: u32,
+    pub height: u32,
+    pub data: &'a [u8],
+}
+
+/// Encode `levels` as a DDS file.
+///
+/// `format` must be a DXT block format or one of the raw formats RGBA8888,
+/// RG88 (stored as luminance + alpha) or R8 (stored as luminance). Each
+/// level must be half the size of the previous one, rounded down to at
+/// least 1, and hold at least the bytes its dimensions require.
+pub(crate) fn encode(format: MipmapFormat, levels: &[DdsLevel]) -> Result<Vec<u8>> {
+    let first = levels
+        .first()
+        .ok_or_else(|| Error::invalid_data("Cannot write a DDS file without image data"))?;
+
+    for (index, level) in levels.iter().enumerate() {
+        let expected_dims = (
+            (first.width >> index).max(1),
+            (first.height >> index).max(1),
+        );
+        if (level.width, level.height) != expected_dims {
+            return Err(Error::invalid_data(format!(
+                "DDS level {} is {}x{}, expected {}x{}",
+                index, level.width, level.height, expected_dims.0, expected_dims.1
+            )));
+        }
+        let size = level_size(format, level.width, level.height)?;
+        if level.data.len() < size {
+            return Err(Error::invalid_data(format!(
+                "DDS level {} holds {} bytes, expected {}",
+                index,
+                level.data.len(),
+                size
+            )));
+        }
+    }
+
+    let mut flags = DDSD_CAPS | DDSD_HEIGHT | DDSD_WIDTH | DDSD_PIXELFORMAT;
+    let mut caps = DDSCAPS_TEXTURE;
+    let pitch_or_linear_size = if format.is_compressed() {
+        flags |= DDSD_LINEARSIZE;
+        level_size(format, first.width, first.height)?
+    } else {
+        flags |= DDSD_PITCH;
+        first.width as usize * format.bytes_per_pixel().unwrap_or(4) as usize
+    };
+    if levels.len() > 1 {
+        flags |= DDSD_MIPMAPCOUNT;
+        caps |= DDSCAPS_COMPLEX | DDSCAPS_MIPMAP;
+    }
+
+    let mut output = Vec::with_capacity(
+        4 + HEADER_SIZE as usize + levels.iter().map(|l| l.data.len()).sum::<usize>(),
+    );
+    output.extend_from_slice(DDS_MAGIC);
+    for value in [
+        HEADER_SIZE,
+        flags,
+        first.height,
+        first.width,
+        pitch_or_linear_size as u32,
+        0, // depth
+        levels.len() as u32,
+    ] {
+        output.extend_from_slice(&value.to_le_bytes());
+    }
+    output.extend_from_slice(&[0u8; 44]); // reserved
+    for value in pixel_format(format)? {
+        output.extend_from_slice(&value.to_le_bytes());
+    }
+    for value in [caps, 0, 0, 0, 0] {
+        output.extend_from_slice(&value.to_le_bytes());
+    }
+
+    for level in levels {
+        let size = level_size(format, level.width, level.height)?;
+        output.extend_from_slice(&level.data[..size]);
+    }
+
+    Ok(output)
+}
+
+/// Read the dimensions from a DDS header.
+#[cfg(test)]
+pub(crate) fn dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.len() < 4 + HEADER_SIZE as usize || &bytes[..4] != DDS_MAGIC {
+        return None;
+    }
+    let read = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    Some((read(16), read(12)))
+}
+
+/// Byte size of a level of `format` at the given dimensions.
+fn level_size(format: MipmapFormat, width: u32, height: u32) -> Result<usize> {
+    let pixel_count = width as usize * height as usize;
+    match (format.bytes_per_block(), format.bytes_per_pixel()) {
+        (Some(block_bytes), _) => {
+            Ok(width.div_ceil(4) as usize * height.div_ceil(4) as usize * block_bytes as usize)
+        }
+        (None, Some(pixel_bytes)) => Ok(pixel_count * pixel_bytes as usize),
+        _ => Err(Error::UnsupportedMipmapFormat { format }),
+    }
+}
+
+/// The `DDS_PIXELFORMAT` fields for `format`.
+fn pixel_format(format: MipmapFormat) -> Result<[u32; 8]> {
+    let fourcc = |code: &[u8; 4]| u32::from_le_bytes(*code);
+    Ok(match format {
+        MipmapFormat::CompressedDXT1 => [
+            PIXEL_FORMAT_SIZE,
+            DDPF_FOURCC,
+            fourcc(b"DXT1"),
+            0,
+            0,
+            0,
+            0,
+            0,
+        ],
+        MipmapFormat::CompressedDXT3 => [
+            PIXEL_FORMAT_SIZE,
+            DDPF_FOURCC,
+            fourcc(b"DXT3"),
+            0,
+            0,
+            0,
+            0,
+            0,
+        ],
+        MipmapFormat::CompressedDXT5 => [
+            PIXEL_FORMAT_SIZE,
+            DDPF_FOURCC,
+            fourcc(b"DXT5"),
+            0,
+            0,
+            0,
+            0,
+            0,
+        ],
+        MipmapFormat::RGBA8888 => [
+            PIXEL_FORMAT_SIZE,
+            DDPF_RGB | DDPF_ALPHAPIXELS,
+            0,
+            32,
+            0x0000_00FF,
+            0x0000_FF00,
+            0x00FF_0000,
+            0xFF00_0000,
+        ],
+        MipmapFormat::RG88 => [
+            PIXEL_FORMAT_SIZE,
+            DDPF_LUMINANCE | DDPF_ALPHAPIXELS,
+            0,
+            16,
+            0x00FF,
+            0,
+            0,
+            0xFF00,
+        ],
+        MipmapFormat::R8 => [PIXEL_FORMAT_SIZE, DDPF_LUMINANCE, 0, 8, 0xFF, 0, 0, 0],
+        _ => return Err(Error::UnsupportedMipmapFormat { format }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header_u32(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_encode_dxt5_mip_chain() {
+        let level0 = vec![0u8; 32]; // 8x4 = 2 blocks
+        let level1 = vec![1u8; 16]; // 4x2 = 1 block
+        let bytes = encode(
+            MipmapFormat::CompressedDXT5,
+            &[
+                DdsLevel {
+                    width: 8,
+                    height: 4,
+                    data: &level0,
+                },
+                DdsLevel {
+                    width: 4,
+                    height: 2,
+                    data: &level1,
+                },
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(&bytes[..4], b"DDS ");
+        assert_eq!(dimensions(&bytes), Some((8, 4)));
+        assert_eq!(header_u32(&bytes, 28), 2); // mipmap count
+        assert_eq!(&bytes[84..88], b"DXT5");
+        assert_eq!(bytes.len(), 128 + 32 + 16);
+    }
+
+    #[test]
+    fn test_encode_rejects_broken_chain() {
+        let data = vec![0u8; 64];
+        let result = encode(
+            MipmapFormat::RGBA8888,
+            &[
+                DdsLevel {
+                    width: 4,
+                    height: 4,
+                    data: &data,
+                },
+                DdsLevel {
+                    width: 4,
+                    height: 4,
+                    data: &data,
+                },
+            ],
+        );
+        assert!(result.is_err());
+    }
+}
@@ -1,8 +1,10 @@
-//! Mipmap decompression (LZ4 and DXT).
+//! Mipmap decompression (LZ4 and DXT), and LZ4 compression.
 
-use repkg_core::{MipmapFormat, TexMipmap};
+use repkg_core::{MipmapFormat, Tex, TexImage, TexMipmap};
 
 use crate::error::{Error, Result};
+#[cfg(feature = "buffer-pool")]
+use crate::texture::BufferPool;
 
 /// Decompressor for mipmap data.
 #[derive(Debug, Clone, Copy)]
@@ -25,74 +27,129 @@ impl MipmapDecompressor {
 
         // Then, DXT decompress if needed
         if mipmap.format.is_compressed() {
-            self.decompress_dxt(mipmap)?;
+            self.decompress_dxt(mipmap, &mut Vec::new())?;
         }
 
         Ok(())
     }
 
-    /// Decompress LZ4-compressed data.
-    fn decompress_lz4(&self, mipmap: &mut TexMipmap) -> Result<()> {
+    /// Decompress LZ4-compressed data, leaving DXT block data as is.
+    pub(crate) fn decompress_lz4(&self, mipmap: &mut TexMipmap) -> Result<()> {
         if mipmap.decompressed_bytes_count == 0 {
             return Ok(());
         }
 
-        let decompressed =
-            lz4_flex::decompress(&mipmap.bytes, mipmap.decompressed_bytes_count as usize).map_err(
-                |e| Error::Lz4Decompression {
-                    message: e.to_string(),
-                },
-            )?;
+        let expected = mipmap.decompressed_bytes_count as usize;
+        let decompressed = lz4_flex::decompress(&mipmap.bytes, expected).map_err(|e| match e {
+            // The stream is valid so far but holds more than the declared size
+            lz4_flex::block::DecompressError::OutputTooSmall { .. } => Error::Lz4SizeMismatch {
+                expected,
+                actual: None,
+            },
+            e => Error::Lz4Decompression {
+                message: e.to_string(),
+            },
+        })?;
+
+        if decompressed.len() != expected {
+            return Err(Error::Lz4SizeMismatch {
+                expected,
+                actual: Some(decompressed.len()),
+            });
+        }
 
         mipmap.bytes = decompressed;
         mipmap.is_lz4_compressed = false;
         Ok(())
     }
 
-    /// Decompress DXT-compressed texture data.
-    fn decompress_dxt(&self, mipmap: &mut TexMipmap) -> Result<()> {
+    /// Decompress mipmap data in place, taking DXT scratch buffers from `pool`.
+    ///
+    /// Behaves like [`decompress`](Self::decompress) but reuses the pool's
+    /// `u32` buffers for block decoding instead of allocating one per mipmap.
+    #[cfg(feature = "buffer-pool")]
+    pub fn decompress_pooled(&self, mipmap: &mut TexMipmap, pool: &BufferPool) -> Result<()> {
+        if mipmap.is_lz4_compressed {
+            self.decompress_lz4(mipmap)?;
+        }
+
+        if mipmap.format.is_compressed() {
+            let mut scratch = pool.take_words(mipmap.width as usize * mipmap.height as usize);
+            let result = self.decompress_dxt(mipmap, &mut scratch);
+            pool.put_words(scratch);
+            result?;
+        }
+
+        Ok(())
+    }
+
+    /// Decompress DXT-compressed texture data, decoding blocks into `scratch`.
+    fn decompress_dxt(&self, mipmap: &mut TexMipmap, scratch: &mut Vec<u32>) -> Result<()> {
+        check_compressed_size(mipmap)?;
+
         let width = mipmap.width as usize;
         let height = mipmap.height as usize;
         let pixel_count = width * height;
 
-        let rgba =
-            match mipmap.format {
-                MipmapFormat::CompressedDXT1 => {
-                    let mut output = vec![0u32; pixel_count];
-                    texture2ddecoder::decode_bc1(&mipmap.bytes, width, height, &mut output)
-                        .map_err(|e| Error::DxtDecompression {
-                            details: format!("DXT1/BC1 decompression failed: {}", e),
-                        })?;
-                    u32_to_rgba_bytes(output)
-                }
-                MipmapFormat::CompressedDXT3 => {
-                    // BC2 is DXT3 - texture2ddecoder doesn't have decode_bc2
-                    // DXT3 is rare in Wallpaper Engine, return error for now
-                    return Err(Error::DxtDecompression {
-                        details: "DXT3/BC2 decompression not yet supported".to_string(),
-                    });
-                }
-                MipmapFormat::CompressedDXT5 => {
-                    let mut output = vec![0u32; pixel_count];
-                    texture2ddecoder::decode_bc3(&mipmap.bytes, width, height, &mut output)
-                        .map_err(|e| Error::DxtDecompression {
-                            details: format!("DXT5/BC3 decompression failed: {}", e),
-                        })?;
-                    u32_to_rgba_bytes(output)
-                }
-                _ => return Ok(()), // Not a compressed format
-            };
-
-        mipmap.bytes = rgba;
+        scratch.clear();
+        scratch.resize(pixel_count, 0);
+
+        match mipmap.format {
+            MipmapFormat::CompressedDXT1 => {
+                texture2ddecoder::decode_bc1(&mipmap.bytes, width, height, scratch).map_err(
+                    |e| Error::DxtDecompression {
+                        details: format!("DXT1/BC1 decompression failed: {}", e),
+                    },
+                )?;
+            }
+            MipmapFormat::CompressedDXT3 => {
+                // BC2 is DXT3 - texture2ddecoder doesn't have decode_bc2
+                // DXT3 is rare in Wallpaper Engine, return error for now
+                return Err(Error::DxtDecompression {
+                    details: "DXT3/BC2 decompression not yet supported".to_string(),
+                });
+            }
+            MipmapFormat::CompressedDXT5 => {
+                texture2ddecoder::decode_bc3(&mipmap.bytes, width, height, scratch).map_err(
+                    |e| Error::DxtDecompression {
+                        details: format!("DXT5/BC3 decompression failed: {}", e),
+                    },
+                )?;
+            }
+            _ => return Ok(()), // Not a compressed format
+        }
+
+        mipmap.bytes = u32_to_rgba_bytes(scratch);
         mipmap.format = MipmapFormat::RGBA8888;
         Ok(())
     }
 }
 
+/// Check that a compressed mipmap holds enough block data for its dimensions.
+///
+/// Truncated data would otherwise fail inside the block decoder with an
+/// unhelpful message. Extra trailing bytes are tolerated. Uncompressed
+/// formats always pass.
+pub(crate) fn check_compressed_size(mipmap: &TexMipmap) -> Result<()> {
+    if !mipmap.format.is_compressed() {
+        return Ok(());
+    }
+
+    let expected = mipmap.expected_size();
+    let actual = mipmap.bytes.len();
+    if actual < expected {
+        return Err(Error::invalid_data(format!(
+            "{:?} mipmap {}x{} is truncated: expected {} bytes, got {}",
+            mipmap.format, mipmap.width, mipmap.height, expected, actual
+        )));
+    }
+    Ok(())
+}
+
 /// Convert u32 RGBA pixels to byte array.
-fn u32_to_rgba_bytes(pixels: Vec<u32>) -> Vec<u8> {
+fn u32_to_rgba_bytes(pixels: &[u32]) -> Vec<u8> {
     let mut bytes = Vec::with_capacity(pixels.len() * 4);
-    for pixel in pixels {
+    for &pixel in pixels {
         // texture2ddecoder returns RGBA as u32 in native endian
         bytes.push((pixel & 0xFF) as u8); // R
         bytes.push(((pixel >> 8) & 0xFF) as u8); // G
@@ -108,10 +165,98 @@ impl Default for MipmapDecompressor {
     }
 }
 
+/// LZ4-compress mipmap data in place, the inverse of LZ4 decompression.
+///
+/// The bytes are compressed as a raw LZ4 block, `decompressed_bytes_count`
+/// records their original length and `original_byte_count` the compressed
+/// length, as stored in TEX files. DXT block data is compressed as is.
+/// Mipmaps that are already compressed are left unchanged.
+pub fn compress_lz4(mipmap: &mut TexMipmap) -> Result<()> {
+    if mipmap.is_lz4_compressed {
+        return Ok(());
+    }
+
+    let decompressed_bytes_count = u32::try_from(mipmap.bytes.len()).map_err(|_| {
+        Error::invalid_data(format!(
+            "Mipmap data of {} bytes is too large to compress",
+            mipmap.bytes.len()
+        ))
+    })?;
+    let compressed = lz4_flex::compress(&mipmap.bytes);
+
+    mipmap.original_byte_count = compressed.len() as u32;
+    mipmap.bytes = compressed;
+    mipmap.decompressed_bytes_count = decompressed_bytes_count;
+    mipmap.is_lz4_compressed = true;
+    Ok(())
+}
+
+/// On-demand decompression of textures read with
+/// [`TexReader::without_decompression`](super::TexReader::without_decompression).
+///
+/// Implemented for [`Tex`] (every image) and [`TexImage`] (one image).
+/// Mipmaps that are already decompressed, or were read without their bytes,
+/// are skipped, so decompressing again is a no-op.
+pub trait DecompressMipmaps {
+    /// Decompress every mipmap that still holds LZ4 or DXT data, in place.
+    fn decompress_all(&mut self) -> Result<()>;
+}
+
+impl DecompressMipmaps for TexImage {
+    fn decompress_all(&mut self) -> Result<()> {
+        let decompressor = MipmapDecompressor::new();
+        self.mipmaps
+            .iter_mut()
+            .filter(|mipmap| {
+                mipmap.has_data() && (mipmap.is_lz4_compressed || mipmap.format.is_compressed())
+            })
+            .try_for_each(|mipmap| decompressor.decompress(mipmap))
+    }
+}
+
+impl DecompressMipmaps for Tex {
+    fn decompress_all(&mut self) -> Result<()> {
+        self.images_container
+            .images
+            .iter_mut()
+            .try_for_each(|image| image.decompress_all())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_decompress_all_is_idempotent() {
+        let blocks: Vec<u8> = (0..64u8).collect(); // 8x8 DXT5 = 4 blocks
+        let mut mipmap = TexMipmap::new(8, 8);
+        mipmap.format = MipmapFormat::CompressedDXT5;
+        mipmap.bytes = blocks;
+        compress_lz4(&mut mipmap).unwrap();
+
+        let mut expected = mipmap.clone();
+        MipmapDecompressor::new().decompress(&mut expected).unwrap();
+
+        let mut image = TexImage::new();
+        image.mipmaps.push(mipmap);
+        image.mipmaps.push(TexMipmap::new(4, 4)); // read without bytes
+        let mut tex = Tex::new(repkg_core::TexHeader::new());
+        tex.images_container.images.push(image);
+
+        tex.decompress_all().unwrap();
+        let decompressed = tex.first_image().unwrap().first_mipmap().unwrap().clone();
+        assert_eq!(decompressed.format, MipmapFormat::RGBA8888);
+        assert!(!decompressed.is_lz4_compressed);
+        assert_eq!(decompressed.bytes, expected.bytes);
+
+        // Already decompressed mipmaps are left alone
+        tex.decompress_all().unwrap();
+        let mipmaps = &tex.first_image().unwrap().mipmaps;
+        assert_eq!(mipmaps[0].bytes, expected.bytes);
+        assert!(mipmaps[1].bytes.is_empty());
+    }
+
     #[test]
     fn test_decompressor_creation() {
         let decompressor = MipmapDecompressor::new();
@@ -125,11 +270,14 @@ mod tests {
             width: 4,
             height: 4,
             format: MipmapFormat::RGBA8888,
+            declared_format: MipmapFormat::RGBA8888,
             is_lz4_compressed: false,
             decompressed_bytes_count: 0,
             bytes: vec![0u8; 64],
             original_byte_count: 64,
             file_offset: 0,
+            v4_params: None,
+            raw_bytes: None,
         };
 
         // Should succeed without modifying anything
@@ -137,4 +285,109 @@ mod tests {
         assert_eq!(mipmap.bytes.len(), 64);
         assert_eq!(mipmap.format, MipmapFormat::RGBA8888);
     }
+
+    #[test]
+    fn test_lz4_size_mismatch() {
+        let data = vec![7u8; 100];
+        let mut mipmap = TexMipmap::new(5, 5);
+        mipmap.format = MipmapFormat::RGBA8888;
+        mipmap.is_lz4_compressed = true;
+        mipmap.bytes = lz4_flex::compress(&data);
+
+        mipmap.decompressed_bytes_count = 120;
+        let err = MipmapDecompressor::new()
+            .decompress(&mut mipmap.clone())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Lz4SizeMismatch {
+                expected: 120,
+                actual: Some(100)
+            }
+        ));
+
+        mipmap.decompressed_bytes_count = 80;
+        let err = MipmapDecompressor::new()
+            .decompress(&mut mipmap.clone())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Lz4SizeMismatch {
+                expected: 80,
+                actual: None
+            }
+        ));
+
+        // Malformed streams keep the generic error
+        mipmap.bytes = vec![0xF0];
+        mipmap.decompressed_bytes_count = 100;
+        let err = MipmapDecompressor::new()
+            .decompress(&mut mipmap)
+            .unwrap_err();
+        assert!(matches!(err, Error::Lz4Decompression { .. }));
+
+        mipmap.decompressed_bytes_count = 100;
+        mipmap.bytes = lz4_flex::compress(&data);
+        MipmapDecompressor::new().decompress(&mut mipmap).unwrap();
+        assert_eq!(mipmap.bytes, data);
+    }
+
+    #[test]
+    fn test_compress_lz4_round_trip() {
+        let data: Vec<u8> = (0..256u32).map(|i| (i % 7) as u8).collect();
+        let mut mipmap = TexMipmap::new(8, 8);
+        mipmap.format = MipmapFormat::RGBA8888;
+        mipmap.bytes = data.clone();
+
+        compress_lz4(&mut mipmap).unwrap();
+        assert!(mipmap.is_lz4_compressed);
+        assert_eq!(mipmap.decompressed_bytes_count, 256);
+        assert_eq!(mipmap.original_byte_count as usize, mipmap.bytes.len());
+        assert!(mipmap.bytes.len() < data.len());
+
+        // Compressing twice is a no-op
+        let compressed = mipmap.bytes.clone();
+        compress_lz4(&mut mipmap).unwrap();
+        assert_eq!(mipmap.bytes, compressed);
+
+        MipmapDecompressor::new().decompress(&mut mipmap).unwrap();
+        assert!(!mipmap.is_lz4_compressed);
+        assert_eq!(mipmap.bytes, data);
+    }
+
+    #[test]
+    fn test_truncated_dxt_is_rejected() {
+        let mut mipmap = TexMipmap::new(8, 8);
+        mipmap.format = MipmapFormat::CompressedDXT5;
+        mipmap.bytes = vec![0u8; 40];
+
+        let err = MipmapDecompressor::new()
+            .decompress(&mut mipmap)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("CompressedDXT5 mipmap 8x8"), "{}", err);
+        assert!(err.contains("expected 64 bytes, got 40"), "{}", err);
+        assert_eq!(mipmap.format, MipmapFormat::CompressedDXT5);
+
+        mipmap.bytes.resize(64, 0);
+        assert!(check_compressed_size(&mipmap).is_ok());
+    }
+
+    #[cfg(feature = "buffer-pool")]
+    #[test]
+    fn test_decompress_pooled_dxt1() {
+        let pool = BufferPool::new();
+        let mut mipmap = TexMipmap::new(4, 4);
+        mipmap.format = MipmapFormat::CompressedDXT1;
+        mipmap.bytes = vec![0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0];
+        let mut expected = mipmap.clone();
+
+        MipmapDecompressor::new()
+            .decompress_pooled(&mut mipmap, &pool)
+            .unwrap();
+        MipmapDecompressor::new().decompress(&mut expected).unwrap();
+        assert_eq!(mipmap.format, MipmapFormat::RGBA8888);
+        assert_eq!(mipmap.bytes, expected.bytes);
+        assert_eq!(pool.pooled_words(), 1);
+    }
 }
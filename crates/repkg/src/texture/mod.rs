@@ -1,9 +1,23 @@
 //! TEX texture reading and conversion functionality.
 
 mod converter;
+mod dds;
 mod decompressor;
+mod mp4;
+#[cfg(feature = "buffer-pool")]
+mod pool;
 mod reader;
+mod transform;
+mod webp;
 
-pub use converter::{OutputFormat, TexToImageConverter};
-pub use decompressor::MipmapDecompressor;
+pub use converter::{
+    decode_raw, CancelFlag, Channel, ConversionResult, DecodeTrace, Filmstrip, FilmstripLayout,
+    NormalConvention, OutputFormat, TexToImageConverter, TextureKind, MAX_GIF_FPS, MIN_GIF_FPS,
+};
+pub use decompressor::{compress_lz4, DecompressMipmaps, MipmapDecompressor};
+pub use image::codecs::png::{CompressionType, FilterType as PngFilterType};
+pub use image::imageops::FilterType;
+pub use image::DynamicImage;
+#[cfg(feature = "buffer-pool")]
+pub use pool::BufferPool;
 pub use reader::TexReader;
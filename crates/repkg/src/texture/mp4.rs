@@ -0,0 +1,394 @@
+//! Minimal MP4 (ISO BMFF) demuxing for video textures.
+//!
+//! Only enough of the container is parsed to locate the samples of the first
+//! video track: the sample description (codec), sample sizes, chunk offsets
+//! and sync samples. No video bitstream decoding happens here.
+
+use crate::error::{Error, Result};
+
+/// Sample table of the first video track in an MP4 file.
+#[derive(Debug, Clone)]
+pub(crate) struct VideoTrack {
+    /// Sample entry fourcc of the track (e.g. `avc1`, `jpeg`)
+    pub codec: [u8; 4],
+    /// Byte offset of each sample within the file
+    pub sample_offsets: Vec<u64>,
+    /// Size of each sample in bytes
+    pub sample_sizes: Vec<u32>,
+    /// 1-based sync sample numbers, or `None` if every sample is a sync sample
+    pub sync_samples: Option<Vec<u32>>,
+}
+
+impl VideoTrack {
+    /// Number of samples (frames) in the track.
+    pub fn sample_count(&self) -> usize {
+        self.sample_sizes.len()
+    }
+
+    /// Codec fourcc as a printable string.
+    pub fn codec_name(&self) -> String {
+        String::from_utf8_lossy(&self.codec).trim_end().to_string()
+    }
+
+    /// Index of the first sync (key) sample.
+    pub fn first_keyframe(&self) -> usize {
+        match &self.sync_samples {
+            Some(sync) => sync
+                .first()
+                .map(|&n| n.saturating_sub(1) as usize)
+                .unwrap_or(0),
+            None => 0,
+        }
+    }
+
+    /// Borrow the bytes of sample `index` from the file data.
+    pub fn sample<'a>(&self, data: &'a [u8], index: usize) -> Result<&'a [u8]> {
+        let (offset, size) = self
+            .sample_offsets
+            .get(index)
+            .zip(self.sample_sizes.get(index))
+            .ok_or_else(|| {
+                Error::invalid_data(format!(
+                    "Video frame {} out of range (track has {} frames)",
+                    index,
+                    self.sample_count()
+                ))
+            })?;
+
+        let start = usize::try_from(*offset)
+            .map_err(|_| Error::invalid_data("MP4 sample offset out of range"))?;
+        start
+            .checked_add(*size as usize)
+            .and_then(|end| data.get(start..end))
+            .ok_or_else(|| Error::invalid_data("MP4 sample extends past end of file"))
+    }
+}
+
+/// Parse the sample table of the first video track.
+pub(crate) fn parse_video_track(data: &[u8]) -> Result<VideoTrack> {
+    let moov =
+        find_box(data, b"moov")?.ok_or_else(|| Error::invalid_data("MP4 has no 'moov' box"))?;
+
+    for trak in boxes(moov).filter(|(kind, _)| kind == b"trak") {
+        let trak = trak.1?;
+        let Some(mdia) = find_box(trak, b"mdia")? else {
+            continue;
+        };
+        let is_video = match find_box(mdia, b"hdlr")? {
+            Some(hdlr) => full_box_payload(hdlr)?.get(4..8) == Some(b"vide"),
+            None => false,
+        };
+        if !is_video {
+            continue;
+        }
+
+        let stbl = find_path(mdia, &[b"minf", b"stbl"])?
+            .ok_or_else(|| Error::invalid_data("MP4 video track has no sample table"))?;
+        return parse_sample_table(stbl);
+    }
+
+    Err(Error::invalid_data("MP4 has no video track"))
+}
+
+/// Build a `VideoTrack` from an `stbl` box payload.
+fn parse_sample_table(stbl: &[u8]) -> Result<VideoTrack> {
+    let require = |kind: &[u8; 4]| -> Result<&[u8]> {
+        find_box(stbl, kind)?.ok_or_else(|| {
+            Error::invalid_data(format!(
+                "MP4 sample table missing '{}' box",
+                String::from_utf8_lossy(kind)
+            ))
+        })
+    };
+
+    // stsd: first sample entry's fourcc is the codec
+    let stsd = full_box_payload(require(b"stsd")?)?;
+    let codec: [u8; 4] = stsd
+        .get(8..12)
+        .and_then(|fourcc| fourcc.try_into().ok())
+        .ok_or_else(|| Error::invalid_data("MP4 'stsd' box has no sample entry"))?;
+
+    // stsz: per-sample sizes, or one size for all samples
+    let stsz = full_box_payload(require(b"stsz")?)?;
+    let uniform_size = read_u32(stsz, 0)?;
+    let sample_count = read_u32(stsz, 4)? as usize;
+    let sample_sizes = if uniform_size != 0 {
+        if sample_count > stsz.len() * 8 {
+            return Err(Error::safety_limit("MP4 sample count is implausibly large"));
+        }
+        vec![uniform_size; sample_count]
+    } else {
+        read_u32_table(stsz, 8, sample_count)?
+    };
+
+    // stco / co64: chunk offsets
+    let chunk_offsets: Vec<u64> = if let Some(stco) = find_box(stbl, b"stco")? {
+        let stco = full_box_payload(stco)?;
+        let count = read_u32(stco, 0)? as usize;
+        read_u32_table(stco, 4, count)?
+            .into_iter()
+            .map(u64::from)
+            .collect()
+    } else {
+        let co64 = full_box_payload(require(b"co64")?)?;
+        let count = read_u32(co64, 0)? as usize;
+        (0..count)
+            .map(|i| read_u64(co64, 4 + i * 8))
+            .collect::<Result<_>>()?
+    };
+
+    // stsc: runs of (first_chunk, samples_per_chunk)
+    let stsc = full_box_payload(require(b"stsc")?)?;
+    let run_count = read_u32(stsc, 0)? as usize;
+    let run_words = run_count
+        .checked_mul(3)
+        .ok_or_else(|| Error::invalid_data("MP4 'stsc' run count overflows"))?;
+    let runs = read_u32_table(stsc, 4, run_words)?;
+    let runs: Vec<(u32, u32)> = runs.chunks_exact(3).map(|r| (r[0], r[1])).collect();
+
+    // Lay samples out chunk by chunk
+    let mut sample_offsets = Vec::with_capacity(sample_count);
+    'chunks: for (chunk_index, &chunk_offset) in chunk_offsets.iter().enumerate() {
+        let chunk_number = chunk_index as u32 + 1;
+        let samples_in_chunk = runs
+            .iter()
+            .take_while(|(first_chunk, _)| *first_chunk <= chunk_number)
+            .last()
+            .map(|(_, samples)| *samples)
+            .unwrap_or(0);
+
+        let mut offset = chunk_offset;
+        for _ in 0..samples_in_chunk {
+            if sample_offsets.len() == sample_count {
+                break 'chunks;
+            }
+            sample_offsets.push(offset);
+            offset = offset
+                .checked_add(sample_sizes[sample_offsets.len() - 1] as u64)
+                .ok_or_else(|| Error::invalid_data("MP4 sample offset overflows"))?;
+        }
+    }
+
+    if sample_offsets.len() != sample_count {
+        return Err(Error::invalid_data(format!(
+            "MP4 chunk table covers {} of {} samples",
+            sample_offsets.len(),
+            sample_count
+        )));
+    }
+
+    // stss: sync samples (absent means every sample is a sync sample)
+    let sync_samples = match find_box(stbl, b"stss")? {
+        Some(stss) => {
+            let stss = full_box_payload(stss)?;
+            let count = read_u32(stss, 0)? as usize;
+            Some(read_u32_table(stss, 4, count)?)
+        }
+        None => None,
+    };
+
+    Ok(VideoTrack {
+        codec,
+        sample_offsets,
+        sample_sizes,
+        sync_samples,
+    })
+}
+
+/// Iterate over the child boxes in `data`, yielding `(type, payload)`.
+fn boxes(data: &[u8]) -> impl Iterator<Item = ([u8; 4], Result<&[u8]>)> {
+    let mut pos = 0usize;
+    std::iter::from_fn(move || {
+        if pos + 8 > data.len() {
+            return None;
+        }
+
+        let size = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
+        let kind = [data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]];
+        let (header, size) = match size {
+            0 => (8, (data.len() - pos) as u64),
+            1 => match read_u64(data, pos + 8) {
+                Ok(size) => (16, size),
+                Err(e) => {
+                    pos = data.len();
+                    return Some((kind, Err(e)));
+                }
+            },
+            size => (8, size as u64),
+        };
+
+        let end = usize::try_from(size)
+            .ok()
+            .and_then(|size| pos.checked_add(size))
+            .filter(|&end| end <= data.len() && end >= pos + header);
+        let Some(end) = end else {
+            pos = data.len();
+            return Some((
+                kind,
+                Err(Error::invalid_data("MP4 box extends past end of data")),
+            ));
+        };
+
+        let payload = &data[pos + header..end];
+        pos = end;
+        Some((kind, Ok(payload)))
+    })
+}
+
+/// Find the first child box of the given type.
+fn find_box<'a>(data: &'a [u8], kind: &[u8; 4]) -> Result<Option<&'a [u8]>> {
+    boxes(data)
+        .find(|(k, _)| k == kind)
+        .map(|(_, payload)| payload)
+        .transpose()
+}
+
+/// Follow a path of nested box types.
+fn find_path<'a>(data: &'a [u8], path: &[&[u8; 4]]) -> Result<Option<&'a [u8]>> {
+    let mut current = data;
+    for kind in path {
+        match find_box(current, kind)? {
+            Some(payload) => current = payload,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(current))
+}
+
+/// Skip the version and flags of a full box.
+fn full_box_payload(payload: &[u8]) -> Result<&[u8]> {
+    payload
+        .get(4..)
+        .ok_or_else(|| Error::invalid_data("MP4 full box is truncated"))
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Result<u32> {
+    data.get(pos..pos + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| Error::invalid_data("MP4 box is truncated"))
+}
+
+fn read_u64(data: &[u8], pos: usize) -> Result<u64> {
+    data.get(pos..pos + 8)
+        .map(|b| u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+        .ok_or_else(|| Error::invalid_data("MP4 box is truncated"))
+}
+
+/// Read `count` big-endian u32 values starting at `pos`.
+fn read_u32_table(data: &[u8], pos: usize, count: usize) -> Result<Vec<u32>> {
+    let end = count
+        .checked_mul(4)
+        .and_then(|len| pos.checked_add(len))
+        .filter(|&end| end <= data.len())
+        .ok_or_else(|| Error::invalid_data("MP4 table is truncated"))?;
+    Ok(data[pos..end]
+        .chunks_exact(4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+/// Build a single-track MP4 whose samples are stored back to back in one chunk.
+#[cfg(test)]
+pub(crate) fn build_test_mp4(codec: &[u8; 4], samples: &[Vec<u8>]) -> Vec<u8> {
+    fn mp4_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut out = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(payload);
+        out
+    }
+    fn full_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        mp4_box(kind, &[&[0u8; 4][..], payload].concat())
+    }
+    fn be(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    let ftyp = mp4_box(b"ftyp", b"isom\0\0\0\0isom");
+    let mdat_header = 8;
+
+    let build = |data_offset: u32| {
+        let sizes: Vec<u32> = samples.iter().map(|s| s.len() as u32).collect();
+        let stsd = full_box(b"stsd", &[be(&[1]), mp4_box(codec, &[0u8; 78])].concat());
+        let stsz = full_box(
+            b"stsz",
+            &[be(&[0, sizes.len() as u32]), be(&sizes)].concat(),
+        );
+        let stsc = full_box(b"stsc", &be(&[1, 1, sizes.len() as u32, 1]));
+        let stco = full_box(b"stco", &be(&[1, data_offset]));
+        let stbl = mp4_box(b"stbl", &[stsd, stsz, stsc, stco].concat());
+        let minf = mp4_box(b"minf", &stbl);
+        let hdlr = full_box(b"hdlr", &[be(&[0]), b"vide".to_vec(), vec![0; 13]].concat());
+        let mdia = mp4_box(b"mdia", &[hdlr, minf].concat());
+        mp4_box(b"moov", &mp4_box(b"trak", &mdia))
+    };
+
+    // The moov size doesn't depend on the offset value, so measure it first
+    let moov_len = build(0).len();
+    let data_offset = (ftyp.len() + moov_len + mdat_header) as u32;
+    let moov = build(data_offset);
+    let mdat = mp4_box(b"mdat", &samples.concat());
+
+    [ftyp, moov, mdat].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_video_track() {
+        let samples = vec![vec![1, 2, 3], vec![4, 5, 6, 7, 8]];
+        let data = build_test_mp4(b"avc1", &samples);
+
+        let track = parse_video_track(&data).unwrap();
+        assert_eq!(track.codec_name(), "avc1");
+        assert_eq!(track.sample_count(), 2);
+        assert_eq!(track.first_keyframe(), 0);
+
+        let mut keyed = track.clone();
+        keyed.sync_samples = Some(vec![2]);
+        assert_eq!(keyed.first_keyframe(), 1);
+        assert_eq!(track.sample(&data, 0).unwrap(), &[1, 2, 3]);
+        assert_eq!(track.sample(&data, 1).unwrap(), &[4, 5, 6, 7, 8]);
+        assert!(track.sample(&data, 2).is_err());
+    }
+
+    #[test]
+    fn test_parse_without_moov() {
+        let data = [0, 0, 0, 8, b'f', b'r', b'e', b'e'];
+        assert!(parse_video_track(&data).is_err());
+        assert!(parse_video_track(&[0, 0, 0, 64, b'm', b'o', b'o', b'v']).is_err());
+    }
+
+    #[test]
+    fn test_parse_sample_table_offset_overflow() {
+        fn full_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+            let mut out = ((payload.len() + 12) as u32).to_be_bytes().to_vec();
+            out.extend_from_slice(kind);
+            out.extend_from_slice(&[0; 4]);
+            out.extend_from_slice(payload);
+            out
+        }
+        fn be(values: &[u32]) -> Vec<u8> {
+            values.iter().flat_map(|v| v.to_be_bytes()).collect()
+        }
+
+        // One chunk near u64::MAX holding two samples; the second offset overflows
+        let co64 = [be(&[1]), u64::MAX.to_be_bytes().to_vec()].concat();
+        let stbl = [
+            full_box(
+                b"stsd",
+                &[be(&[1, 16]), b"avc1".to_vec(), vec![0; 8]].concat(),
+            ),
+            full_box(b"stsz", &be(&[4, 2])),
+            full_box(b"stsc", &be(&[1, 1, 2, 1])),
+            full_box(b"co64", &co64),
+        ]
+        .concat();
+
+        assert!(matches!(
+            parse_sample_table(&stbl),
+            Err(Error::InvalidData { .. })
+        ));
+    }
+}
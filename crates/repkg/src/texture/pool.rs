@@ -0,0 +1,169 @@
+//! Reusable scratch buffers for batch conversions.
+
+use std::sync::Mutex;
+
+/// Default number of buffers of each kind kept for reuse.
+const DEFAULT_MAX_POOLED: usize = 16;
+
+/// Pool of scratch buffers reused across conversions.
+///
+/// Decoding a texture allocates pixel buffers proportional to its size;
+/// when converting many textures in a loop, a pool lets those allocations
+/// be recycled instead. Buffers are handed out empty with at least the
+/// requested capacity and returned with the `put_*` methods. The pool is
+/// thread-safe, so one pool can be shared by parallel workers.
+#[derive(Debug)]
+pub struct BufferPool {
+    bytes: Mutex<Vec<Vec<u8>>>,
+    words: Mutex<Vec<Vec<u32>>>,
+    max_pooled: usize,
+}
+
+impl BufferPool {
+    /// Create an empty pool.
+    pub const fn new() -> Self {
+        Self::with_max_pooled(DEFAULT_MAX_POOLED)
+    }
+
+    /// Create an empty pool that keeps at most `max_pooled` buffers of each kind.
+    pub const fn with_max_pooled(max_pooled: usize) -> Self {
+        Self {
+            bytes: Mutex::new(Vec::new()),
+            words: Mutex::new(Vec::new()),
+            max_pooled,
+        }
+    }
+
+    /// Take an empty byte buffer with at least `capacity` reserved.
+    pub fn take_bytes(&self, capacity: usize) -> Vec<u8> {
+        take(&self.bytes, capacity)
+    }
+
+    /// Return a byte buffer to the pool for reuse.
+    pub fn put_bytes(&self, buffer: Vec<u8>) {
+        put(&self.bytes, buffer, self.max_pooled);
+    }
+
+    /// Take an empty `u32` buffer with at least `capacity` reserved.
+    pub fn take_words(&self, capacity: usize) -> Vec<u32> {
+        take(&self.words, capacity)
+    }
+
+    /// Return a `u32` buffer to the pool for reuse.
+    pub fn put_words(&self, buffer: Vec<u32>) {
+        put(&self.words, buffer, self.max_pooled);
+    }
+
+    /// Number of byte buffers currently held by the pool.
+    pub fn pooled_bytes(&self) -> usize {
+        lock(&self.bytes).len()
+    }
+
+    /// Number of `u32` buffers currently held by the pool.
+    pub fn pooled_words(&self) -> usize {
+        lock(&self.words).len()
+    }
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lock a buffer list, recovering from poisoning (the lists are always valid).
+fn lock<T>(list: &Mutex<Vec<Vec<T>>>) -> std::sync::MutexGuard<'_, Vec<Vec<T>>> {
+    list.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Take the smallest pooled buffer that fits, or allocate a new one.
+fn take<T>(list: &Mutex<Vec<Vec<T>>>, capacity: usize) -> Vec<T> {
+    let mut list = lock(list);
+    let best = list
+        .iter()
+        .enumerate()
+        .filter(|(_, buffer)| buffer.capacity() >= capacity)
+        .min_by_key(|(_, buffer)| buffer.capacity())
+        .map(|(index, _)| index);
+
+    match best {
+        Some(index) => list.swap_remove(index),
+        // Reuse the largest buffer's allocation if none fits
+        None => match (0..list.len()).max_by_key(|&index| list[index].capacity()) {
+            Some(index) => {
+                let mut buffer = list.swap_remove(index);
+                buffer.reserve_exact(capacity);
+                buffer
+            }
+            None => Vec::with_capacity(capacity),
+        },
+    }
+}
+
+/// Clear a buffer and keep it if the pool has room.
+fn put<T>(list: &Mutex<Vec<Vec<T>>>, mut buffer: Vec<T>, max_pooled: usize) {
+    if buffer.capacity() == 0 {
+        return;
+    }
+    buffer.clear();
+    let mut list = lock(list);
+    if list.len() < max_pooled {
+        list.push(buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffers_are_reused() {
+        let pool = BufferPool::new();
+        let mut buffer = pool.take_bytes(1024);
+        assert!(buffer.is_empty());
+        assert!(buffer.capacity() >= 1024);
+        buffer.extend_from_slice(&[1, 2, 3]);
+        let ptr = buffer.as_ptr();
+        pool.put_bytes(buffer);
+        assert_eq!(pool.pooled_bytes(), 1);
+
+        let reused = pool.take_bytes(512);
+        assert!(reused.is_empty());
+        assert_eq!(reused.as_ptr(), ptr);
+        assert_eq!(pool.pooled_bytes(), 0);
+    }
+
+    #[test]
+    fn test_take_prefers_smallest_fit() {
+        let pool = BufferPool::new();
+        pool.put_words(Vec::with_capacity(4096));
+        pool.put_words(Vec::with_capacity(64));
+        let words = pool.take_words(32);
+        assert!(words.capacity() >= 32 && words.capacity() < 4096);
+        assert_eq!(pool.pooled_words(), 1);
+
+        // Too small buffers grow instead of allocating alongside
+        let grown = pool.take_words(10_000);
+        assert!(grown.capacity() >= 10_000);
+        assert_eq!(pool.pooled_words(), 0);
+    }
+
+    #[test]
+    fn test_take_grows_largest_buffer() {
+        let pool = BufferPool::new();
+        pool.put_bytes(Vec::with_capacity(256));
+        pool.put_bytes(Vec::with_capacity(16));
+        let grown = pool.take_bytes(1024);
+        assert!(grown.capacity() >= 1024);
+        assert!(pool.take_bytes(1).capacity() < 256);
+    }
+
+    #[test]
+    fn test_pool_is_bounded() {
+        let pool = BufferPool::with_max_pooled(2);
+        for _ in 0..4 {
+            pool.put_bytes(vec![0u8; 16]);
+        }
+        assert_eq!(pool.pooled_bytes(), 2);
+    }
+}
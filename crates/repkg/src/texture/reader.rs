@@ -3,25 +3,61 @@
 use byteorder::{LittleEndian, ReadBytesExt};
 use repkg_core::{
     FreeImageFormat, MipmapFormat, Tex, TexFlags, TexFormat, TexFrameInfo, TexFrameInfoContainer,
-    TexHeader, TexImage, TexImageContainer, TexImageContainerVersion, TexMipmap,
+    TexHeader, TexImage, TexImageContainer, TexImageContainerVersion, TexMipmap, V4MipmapParams,
 };
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, SeekFrom};
+#[cfg(not(feature = "buffer-pool"))]
+use std::marker::PhantomData;
+use std::ops::Range;
 
+#[cfg(feature = "buffer-pool")]
+use super::BufferPool;
 use super::MipmapDecompressor;
 use crate::error::{Error, Result};
 
-/// Safety limits.
+/// Default safety limits.
 const MAX_IMAGE_COUNT: u32 = 1000;
 const MAX_MIPMAP_COUNT: u32 = 20;
 const MAX_FRAME_COUNT: u32 = 10000;
 
+/// Largest texture or image side considered plausible in a TEX header.
+const MAX_DIMENSION: u32 = 65536;
+
 /// Reader for Wallpaper Engine TEX files.
 #[derive(Debug, Clone)]
-pub struct TexReader {
+pub struct TexReader<'a> {
     /// Whether to read mipmap bytes
     pub read_mipmap_bytes: bool,
+    /// Only read the bytes of mipmap levels in this range (level 0 is the
+    /// largest); other levels keep their metadata with empty bytes
+    pub mipmap_level_filter: Option<Range<usize>>,
     /// Whether to decompress mipmaps after reading
     pub decompress_mipmaps: bool,
+    /// Keep a copy of each mipmap's stored bytes in `raw_bytes`
+    pub store_raw_bytes: bool,
+    /// Return the header with an empty image container instead of failing on
+    /// an unsupported container version
+    pub allow_unsupported_container: bool,
+    /// Correct raw mipmap formats that don't match the data size after
+    /// decompression (e.g. RG88 headers on R8 data)
+    pub infer_true_format: bool,
+    /// Record recoverable problems in `Tex::warnings` instead of failing
+    pub lenient: bool,
+    /// Maximum number of images in the image container
+    pub max_images: u32,
+    /// Maximum number of mipmaps per image
+    pub max_mipmaps: u32,
+    /// Maximum number of animation frames
+    pub max_frames: u32,
+    /// Pool to take DXT decompression scratch buffers from
+    #[cfg(feature = "buffer-pool")]
+    pub pool: Option<&'a BufferPool>,
+    /// Keeps the pool lifetime without the `buffer-pool` feature
+    #[cfg(not(feature = "buffer-pool"))]
+    _pool: PhantomData<&'a ()>,
+    /// Decompress the mipmaps of each image in parallel
+    #[cfg(feature = "parallel")]
+    pub parallel_decompress: bool,
 }
 
 /// Result of reading mipmap bytes - includes metadata even when bytes aren't read.
@@ -31,20 +67,37 @@ struct MipmapBytesResult {
     file_offset: u64,
 }
 
-impl TexReader {
+impl<'a> TexReader<'a> {
     /// Create a new TEX reader.
     pub fn new() -> Self {
         Self {
             read_mipmap_bytes: true,
             decompress_mipmaps: true,
+            store_raw_bytes: false,
+            allow_unsupported_container: false,
+            infer_true_format: false,
+            lenient: false,
+            max_images: MAX_IMAGE_COUNT,
+            max_mipmaps: MAX_MIPMAP_COUNT,
+            max_frames: MAX_FRAME_COUNT,
+            mipmap_level_filter: None,
+            #[cfg(feature = "buffer-pool")]
+            pool: None,
+            #[cfg(not(feature = "buffer-pool"))]
+            _pool: PhantomData,
+            #[cfg(feature = "parallel")]
+            parallel_decompress: false,
         }
     }
 
     /// Create a reader that doesn't decompress mipmaps.
+    ///
+    /// They can be decompressed later with
+    /// [`DecompressMipmaps`](super::DecompressMipmaps).
     pub fn without_decompression() -> Self {
         Self {
-            read_mipmap_bytes: true,
             decompress_mipmaps: false,
+            ..Self::new()
         }
     }
 
@@ -54,11 +107,134 @@ impl TexReader {
         Self {
             read_mipmap_bytes: false,
             decompress_mipmaps: false,
+            ..Self::new()
         }
     }
 
+    /// Salvage the header of textures with an unsupported container version.
+    ///
+    /// When enabled, an unknown container returns a `Tex` with the parsed
+    /// header, no images, and `unsupported_container` set to the container
+    /// magic. Converting such a texture still fails.
+    pub fn with_unsupported_container_fallback(mut self, enabled: bool) -> Self {
+        self.allow_unsupported_container = enabled;
+        self
+    }
+
+    /// Keep the exact stored bytes of each mipmap in `TexMipmap::raw_bytes`.
+    ///
+    /// `bytes` is still decompressed as configured, so both the on-disk
+    /// (possibly LZ4-compressed) data and the decoded data are available,
+    /// e.g. for re-packaging a texture without compressing it again. This
+    /// doubles the memory used by mipmap data. Mipmaps whose bytes aren't
+    /// read have no raw bytes either.
+    pub fn with_raw_bytes(mut self, enabled: bool) -> Self {
+        self.store_raw_bytes = enabled;
+        self
+    }
+
+    /// Correct raw mipmap formats from the decompressed data size.
+    ///
+    /// Some textures declare RG88 (or RGBA8888) while their data is R8 (or
+    /// RG88). When enabled, `format` is set to the format matching
+    /// `width * height * bytes_per_pixel`; the header's format stays
+    /// available as `declared_format`. Has no effect without decompression.
+    pub fn with_true_format_inference(mut self, enabled: bool) -> Self {
+        self.infer_true_format = enabled;
+        self
+    }
+
+    /// Read damaged textures as far as possible instead of failing.
+    ///
+    /// When enabled, mipmap data running past the end of the input is
+    /// truncated, mipmaps that fail to decompress keep their stored bytes,
+    /// and frame info with an unexpected magic is read anyway (or dropped
+    /// if it can't be read). Each of these is recorded in `Tex::warnings`.
+    /// Invalid TEX magics and safety limits still fail the read.
+    pub fn with_lenient_parsing(mut self, enabled: bool) -> Self {
+        self.lenient = enabled;
+        self
+    }
+
+    /// Set the maximum number of images accepted in the image container.
+    pub fn with_max_images(mut self, max: u32) -> Self {
+        self.max_images = max;
+        self
+    }
+
+    /// Set the maximum number of mipmaps accepted per image.
+    pub fn with_max_mipmaps(mut self, max: u32) -> Self {
+        self.max_mipmaps = max;
+        self
+    }
+
+    /// Set the maximum number of animation frames accepted.
+    pub fn with_max_frames(mut self, max: u32) -> Self {
+        self.max_frames = max;
+        self
+    }
+
+    /// Only read the bytes of mipmap levels within `levels`, e.g. `0..1` for
+    /// just the full-size level.
+    ///
+    /// Levels outside the range are skipped like with
+    /// [`headers_only`](Self::headers_only): their dimensions, offsets and
+    /// sizes are still reported, but their bytes are empty. Has no effect
+    /// when mipmap bytes aren't read at all.
+    pub fn with_mipmap_level_filter(mut self, levels: Range<usize>) -> Self {
+        self.mipmap_level_filter = Some(levels);
+        self
+    }
+
+    /// Reuse DXT decompression scratch buffers from `pool`.
+    #[cfg(feature = "buffer-pool")]
+    pub fn with_pool(mut self, pool: &'a BufferPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Decompress the mipmaps of each image in parallel with rayon.
+    ///
+    /// All mipmaps of an image are read first and then decompressed
+    /// concurrently, which pays off for textures with many large LZ4 or DXT
+    /// levels. The output is identical to serial decompression.
+    #[cfg(feature = "parallel")]
+    pub fn with_parallel_decompression(mut self, enabled: bool) -> Self {
+        self.parallel_decompress = enabled;
+        self
+    }
+
     /// Read a TEX file from a reader.
     pub fn read_from<R: Read + Seek>(&self, reader: &mut R) -> Result<Tex> {
+        self.read_tex(&mut Seekable(reader))
+    }
+
+    /// Read a TEX file from a forward-only reader, such as a network socket.
+    ///
+    /// Mipmap data is read by its length prefix without validating it
+    /// against the stream length; a stream that ends early fails with
+    /// [`Error::UnexpectedEof`]. Mipmap offsets are
+    /// relative to where the reader started. In headers-only mode the data
+    /// is still consumed, just discarded.
+    pub fn read_from_stream<R: Read>(&self, reader: &mut R) -> Result<Tex> {
+        let mut stream = Streaming {
+            inner: reader,
+            position: 0,
+        };
+        self.read_tex(&mut stream).map_err(|e| match e {
+            // Header and mipmap fields are read through `Read`, which can
+            // only report a short read as an I/O error
+            Error::Io(io) if io.kind() == std::io::ErrorKind::UnexpectedEof => {
+                Error::UnexpectedEof {
+                    position: stream.position,
+                }
+            }
+            e => e,
+        })
+    }
+
+    /// Read a TEX file from a parser source.
+    fn read_tex<R: TexSource>(&self, reader: &mut R) -> Result<Tex> {
         // Read magic strings
         let magic1 = read_null_terminated_string(reader, 16)?;
         if magic1 != "TEXV0005" {
@@ -78,13 +254,34 @@ impl TexReader {
 
         // Read header
         let header = self.read_header(reader)?;
+        check_header_dimensions(&header)?;
+        let mut warnings = Vec::new();
+        if let TexFormat::Unknown(value) = header.format {
+            warnings.push(format!("Unknown texture format {}", value));
+        }
 
         // Read image container
-        let images_container = self.read_image_container(reader, header.format)?;
+        let images_container = match self.read_image_container(reader, &header, &mut warnings) {
+            Err(Error::UnsupportedContainerVersion { version })
+                if self.allow_unsupported_container =>
+            {
+                let mut tex = Tex::new(header);
+                tex.unsupported_container = Some(version);
+                tex.warnings = warnings;
+                return Ok(tex);
+            }
+            result => result?,
+        };
 
         // Read frame info if this is a GIF
         let frame_info_container = if header.flags.contains(TexFlags::IS_GIF) {
-            Some(self.read_frame_info_container(reader)?)
+            match self.read_frame_info_container(reader, &mut warnings) {
+                Err(e) if self.lenient && !matches!(e, Error::SafetyLimit { .. }) => {
+                    warnings.push(format!("Frame info could not be read: {}", e));
+                    None
+                }
+                result => Some(result?),
+            }
         } else {
             None
         };
@@ -95,6 +292,8 @@ impl TexReader {
             header,
             images_container,
             frame_info_container,
+            unsupported_container: None,
+            warnings,
         })
     }
 
@@ -124,16 +323,19 @@ impl TexReader {
     /// Container structure (based on C# reference implementation):
     /// - magic (null-terminated string, e.g., "TEXB0004\0")
     /// - imageCount (i32) - ALWAYS first field after magic
-    /// - [V3+]: imageFormat (i32)
-    /// - [V4 only]: isVideoMp4 (i32) - 1 if video, 0 otherwise
+    /// - (V3+) imageFormat (i32)
+    /// - (V4 only) isVideoMp4 (i32) - 1 if video, 0 otherwise
+    ///
+    /// V5 containers are read with the V4 layout, which may not be exact.
     ///
     /// Then for each image (loop imageCount times):
     /// - mipmapCount (i32)
     /// - [mipmaps...]
-    fn read_image_container<R: Read + Seek>(
+    fn read_image_container<R: TexSource>(
         &self,
         reader: &mut R,
-        tex_format: TexFormat,
+        header: &TexHeader,
+        warnings: &mut Vec<String>,
     ) -> Result<TexImageContainer> {
         // Read container magic
         let container_magic = read_null_terminated_string(reader, 16)?;
@@ -147,13 +349,16 @@ impl TexReader {
 
         // First field is ALWAYS imageCount (for all versions)
         let image_count = reader.read_i32::<LittleEndian>()?;
-        if image_count < 0 || image_count as u32 > MAX_IMAGE_COUNT {
+        if image_count < 0 || image_count as u32 > self.max_images {
             return Err(Error::safety_limit(format!(
                 "Image count {} exceeds maximum {}",
-                image_count, MAX_IMAGE_COUNT
+                image_count, self.max_images
             )));
         }
 
+        // Raw V4/V5 header fields, reported if a V5 container fails to parse
+        let mut v4_fields = None;
+
         // Read additional fields based on version
         let image_format = match &version {
             TexImageContainerVersion::Version1 | TexImageContainerVersion::Version2 => {
@@ -164,10 +369,14 @@ impl TexReader {
                 // V3: imageFormat field
                 FreeImageFormat::from(reader.read_i32::<LittleEndian>()?)
             }
-            TexImageContainerVersion::Version4 => {
+            TexImageContainerVersion::Version4 | TexImageContainerVersion::Version5 => {
                 // V4: imageFormat + isVideoMp4 fields
-                let format = FreeImageFormat::from(reader.read_i32::<LittleEndian>()?);
-                let is_video_mp4 = reader.read_i32::<LittleEndian>()? == 1;
+                let raw_format = reader.read_i32::<LittleEndian>()?;
+                let raw_is_video = reader.read_i32::<LittleEndian>()?;
+                v4_fields = Some((raw_format, raw_is_video));
+
+                let format = FreeImageFormat::from(raw_format);
+                let is_video_mp4 = raw_is_video == 1;
 
                 // If format is Unknown and isVideoMp4 flag is set, treat as MP4
                 if format == FreeImageFormat::Unknown && is_video_mp4 {
@@ -186,20 +395,51 @@ impl TexReader {
         // KEY: Downgrade V4 to V3 when format is not MP4
         // This matches the C# behavior where V4 containers without MP4 format
         // use V3-style mipmap reading (no extra V4 parameters)
-        if version == TexImageContainerVersion::Version4 && image_format != FreeImageFormat::Mp4 {
+        let newer_than_v4 = version == TexImageContainerVersion::Version5;
+        if newer_than_v4 {
+            log::warn!(
+                "{} container is not fully known, reading it with the V4 layout",
+                container_magic
+            );
+        }
+        if matches!(
+            version,
+            TexImageContainerVersion::Version4 | TexImageContainerVersion::Version5
+        ) && image_format != FreeImageFormat::Mp4
+        {
             version = TexImageContainerVersion::Version3;
         }
 
         let mut container = TexImageContainer {
+            magic: container_magic,
             version: version.clone(),
             image_format,
             images: Vec::new(),
         };
-        let mipmap_format = container.mipmap_format(tex_format);
+        let mipmap_format = container.mipmap_format_for(header);
 
         // Read images - ALL versions use per-image mipmap count
-        for _ in 0..image_count {
-            let image = self.read_image(reader, &version, mipmap_format)?;
+        for index in 0..image_count {
+            let image = match self.read_image(reader, &version, mipmap_format, warnings) {
+                // Parse failures and out-of-bounds fields hint at a layout
+                // mismatch; other errors (decompression, ...) keep their kind
+                Err(e @ (Error::Io(_) | Error::InvalidData { .. } | Error::SafetyLimit { .. }))
+                    if newer_than_v4 =>
+                {
+                    let (raw_format, raw_is_video) = v4_fields.unwrap_or_default();
+                    return Err(Error::invalid_data(format!(
+                        "{} container does not match the V4 layout \
+                         (image_count={}, image_format={}, is_video_mp4={}, \
+                         mipmap layout={:?}, failed at image {}): {}",
+                        container.magic, image_count, raw_format, raw_is_video, version, index, e
+                    )));
+                }
+                Err(e) if self.lenient && !matches!(e, Error::SafetyLimit { .. }) => {
+                    warnings.push(format!("Image {} could not be read: {}", index, e));
+                    break;
+                }
+                result => result?,
+            };
             container.images.push(image);
         }
 
@@ -207,17 +447,18 @@ impl TexReader {
     }
 
     /// Read a single image with its mipmaps.
-    fn read_image<R: Read + Seek>(
+    fn read_image<R: TexSource>(
         &self,
         reader: &mut R,
         version: &TexImageContainerVersion,
         mipmap_format: MipmapFormat,
+        warnings: &mut Vec<String>,
     ) -> Result<TexImage> {
         let mipmap_count = reader.read_u32::<LittleEndian>()?;
-        if mipmap_count > MAX_MIPMAP_COUNT {
+        if mipmap_count > self.max_mipmaps {
             return Err(Error::safety_limit(format!(
                 "Mipmap count {} exceeds maximum {}",
-                mipmap_count, MAX_MIPMAP_COUNT
+                mipmap_count, self.max_mipmaps
             )));
         }
 
@@ -225,34 +466,104 @@ impl TexReader {
             mipmaps: Vec::with_capacity(mipmap_count as usize),
         };
 
-        let decompressor = MipmapDecompressor::new();
-
-        for _ in 0..mipmap_count {
-            let mut mipmap = self.read_mipmap(reader, version)?;
+        for level in 0..mipmap_count as usize {
+            let in_range = match &self.mipmap_level_filter {
+                Some(levels) => levels.contains(&level),
+                None => true,
+            };
+            let read_bytes = self.read_mipmap_bytes && in_range;
+            let mut mipmap = match self.read_mipmap(reader, version, read_bytes, warnings) {
+                // Keep the levels read so far; the next image will fail too
+                Err(e) if self.lenient && !image.mipmaps.is_empty() => {
+                    warnings.push(format!("Mipmap {} could not be read: {}", level, e));
+                    break;
+                }
+                result => result?,
+            };
             mipmap.format = mipmap_format;
+            mipmap.declared_format = mipmap_format;
+            if self.store_raw_bytes && mipmap.has_data() {
+                mipmap.raw_bytes = Some(mipmap.bytes.clone());
+            }
+            image.mipmaps.push(mipmap);
+        }
 
-            if self.decompress_mipmaps && mipmap.has_data() {
-                decompressor.decompress(&mut mipmap)?;
+        if self.decompress_mipmaps && self.lenient {
+            let decompressor = MipmapDecompressor::new();
+            for (level, mipmap) in image.mipmaps.iter_mut().enumerate() {
+                if !mipmap.has_data() {
+                    continue;
+                }
+                if let Err(e) = self.decompress_mipmap(&decompressor, mipmap) {
+                    warnings.push(format!("Mipmap {} left compressed: {}", level, e));
+                }
             }
+        } else if self.decompress_mipmaps {
+            self.decompress_all(&mut image.mipmaps)?;
+        }
 
-            image.mipmaps.push(mipmap);
+        if self.decompress_mipmaps && self.infer_true_format {
+            for mipmap in &mut image.mipmaps {
+                if mipmap.format.is_raw() && mipmap.has_data() && !mipmap.is_lz4_compressed {
+                    let pixel_count = mipmap.width as usize * mipmap.height as usize;
+                    mipmap.format = mipmap
+                        .format
+                        .infer_from_size(pixel_count, mipmap.bytes.len());
+                }
+            }
         }
 
         Ok(image)
     }
 
-    /// Read a single mipmap.
-    fn read_mipmap<R: Read + Seek>(
+    /// Decompress the mipmaps of an image, in parallel if enabled.
+    fn decompress_all(&self, mipmaps: &mut [TexMipmap]) -> Result<()> {
+        let decompressor = MipmapDecompressor::new();
+
+        #[cfg(feature = "parallel")]
+        if self.parallel_decompress {
+            use rayon::prelude::*;
+            return mipmaps
+                .par_iter_mut()
+                .filter(|mipmap| mipmap.has_data())
+                .try_for_each(|mipmap| self.decompress_mipmap(&decompressor, mipmap));
+        }
+
+        mipmaps
+            .iter_mut()
+            .filter(|mipmap| mipmap.has_data())
+            .try_for_each(|mipmap| self.decompress_mipmap(&decompressor, mipmap))
+    }
+
+    /// Decompress a mipmap, using the buffer pool if one is set.
+    fn decompress_mipmap(
+        &self,
+        decompressor: &MipmapDecompressor,
+        mipmap: &mut TexMipmap,
+    ) -> Result<()> {
+        #[cfg(feature = "buffer-pool")]
+        if let Some(pool) = self.pool {
+            return decompressor.decompress_pooled(mipmap, pool);
+        }
+        decompressor.decompress(mipmap)
+    }
+
+    /// Read a single mipmap, skipping over its bytes unless `read_bytes`.
+    fn read_mipmap<R: TexSource>(
         &self,
         reader: &mut R,
         version: &TexImageContainerVersion,
+        read_bytes: bool,
+        warnings: &mut Vec<String>,
     ) -> Result<TexMipmap> {
         match version {
-            TexImageContainerVersion::Version1 => self.read_mipmap_v1(reader),
+            TexImageContainerVersion::Version1 => self.read_mipmap_v1(reader, read_bytes, warnings),
             TexImageContainerVersion::Version2 | TexImageContainerVersion::Version3 => {
-                self.read_mipmap_v2_v3(reader)
+                self.read_mipmap_v2_v3(reader, read_bytes, warnings)
+            }
+            TexImageContainerVersion::Version4 | TexImageContainerVersion::Version5 => {
+                self.read_mipmap_v4(reader, read_bytes, warnings)
             }
-            TexImageContainerVersion::Version4 => self.read_mipmap_v4(reader),
             TexImageContainerVersion::Unknown(_) => Err(Error::UnsupportedContainerVersion {
                 version: format!("{:?}", version),
             }),
@@ -260,102 +571,119 @@ impl TexReader {
     }
 
     /// Read a V1 mipmap.
-    fn read_mipmap_v1<R: Read + Seek>(&self, reader: &mut R) -> Result<TexMipmap> {
+    fn read_mipmap_v1<R: TexSource>(
+        &self,
+        reader: &mut R,
+        read_bytes: bool,
+        warnings: &mut Vec<String>,
+    ) -> Result<TexMipmap> {
         let width = reader.read_u32::<LittleEndian>()?;
         let height = reader.read_u32::<LittleEndian>()?;
-        let result = self.read_mipmap_bytes(reader)?;
+        let result = self.read_mipmap_bytes(reader, read_bytes, warnings)?;
 
         Ok(TexMipmap {
             width,
             height,
             format: MipmapFormat::Invalid,
+            declared_format: MipmapFormat::Invalid,
             is_lz4_compressed: false,
             decompressed_bytes_count: 0,
             bytes: result.bytes,
             original_byte_count: result.byte_count,
             file_offset: result.file_offset,
+            v4_params: None,
+            raw_bytes: None,
         })
     }
 
     /// Read a V2/V3 mipmap.
-    fn read_mipmap_v2_v3<R: Read + Seek>(&self, reader: &mut R) -> Result<TexMipmap> {
+    fn read_mipmap_v2_v3<R: TexSource>(
+        &self,
+        reader: &mut R,
+        read_bytes: bool,
+        warnings: &mut Vec<String>,
+    ) -> Result<TexMipmap> {
         let width = reader.read_u32::<LittleEndian>()?;
         let height = reader.read_u32::<LittleEndian>()?;
         let is_lz4_compressed = reader.read_u32::<LittleEndian>()? == 1;
         let decompressed_bytes_count = reader.read_u32::<LittleEndian>()?;
-        let result = self.read_mipmap_bytes(reader)?;
+        let result = self.read_mipmap_bytes(reader, read_bytes, warnings)?;
 
         Ok(TexMipmap {
             width,
             height,
             format: MipmapFormat::Invalid,
+            declared_format: MipmapFormat::Invalid,
             is_lz4_compressed,
             decompressed_bytes_count,
             bytes: result.bytes,
             original_byte_count: result.byte_count,
             file_offset: result.file_offset,
+            v4_params: None,
+            raw_bytes: None,
         })
     }
 
     /// Read a V4 mipmap (has extra parameters).
-    fn read_mipmap_v4<R: Read + Seek>(&self, reader: &mut R) -> Result<TexMipmap> {
-        // V4 has some extra parameters we skip
-        let _param1 = reader.read_u32::<LittleEndian>()?;
-        let _param2 = reader.read_u32::<LittleEndian>()?;
-        let _condition_json = read_null_terminated_string(reader, 4096)?;
-        let _param3 = reader.read_u32::<LittleEndian>()?;
+    fn read_mipmap_v4<R: TexSource>(
+        &self,
+        reader: &mut R,
+        read_bytes: bool,
+        warnings: &mut Vec<String>,
+    ) -> Result<TexMipmap> {
+        // V4 has some extra parameters before the V2/V3 layout
+        let param1 = reader.read_u32::<LittleEndian>()?;
+        let param2 = reader.read_u32::<LittleEndian>()?;
+        let condition_json = read_null_terminated_string(reader, 4096)?;
+        let param3 = reader.read_u32::<LittleEndian>()?;
 
-        // Then same as V2/V3
-        self.read_mipmap_v2_v3(reader)
+        let mut mipmap = self.read_mipmap_v2_v3(reader, read_bytes, warnings)?;
+        mipmap.v4_params = Some(V4MipmapParams {
+            param1,
+            param2,
+            condition_json,
+            param3,
+        });
+        Ok(mipmap)
     }
 
-    /// Read mipmap bytes with length prefix.
-    /// Validates that byte_count doesn't exceed remaining stream length (like C# version).
-    fn read_mipmap_bytes<R: Read + Seek>(&self, reader: &mut R) -> Result<MipmapBytesResult> {
+    /// Read mipmap bytes with length prefix, or skip over them.
+    fn read_mipmap_bytes<R: TexSource>(
+        &self,
+        reader: &mut R,
+        read_bytes: bool,
+        warnings: &mut Vec<String>,
+    ) -> Result<MipmapBytesResult> {
         let byte_count = reader.read_u32::<LittleEndian>()?;
-
-        // Record the offset where data starts
-        let file_offset = reader.stream_position()?;
-
-        // Validate against stream length (matches C# behavior)
-        let stream_len = reader.seek(std::io::SeekFrom::End(0))?;
-        reader.seek(std::io::SeekFrom::Start(file_offset))?;
-
-        if file_offset + byte_count as u64 > stream_len {
-            return Err(Error::safety_limit(format!(
-                "Mipmap byte count {} exceeds remaining stream length (pos: {}, len: {})",
-                byte_count, file_offset, stream_len
-            )));
-        }
-
-        if !self.read_mipmap_bytes {
-            // Skip the bytes but record metadata
-            reader.seek(std::io::SeekFrom::Current(byte_count as i64))?;
-            return Ok(MipmapBytesResult {
-                bytes: Vec::new(),
-                byte_count,
-                file_offset,
-            });
+        let result = reader.read_payload(byte_count, read_bytes, self.lenient)?;
+        if result.byte_count < byte_count {
+            warnings.push(format!(
+                "Mipmap data at offset {} is truncated to {} of {} bytes",
+                result.file_offset, result.byte_count, byte_count
+            ));
         }
-
-        let mut bytes = vec![0u8; byte_count as usize];
-        reader.read_exact(&mut bytes)?;
-        Ok(MipmapBytesResult {
-            bytes,
-            byte_count,
-            file_offset,
-        })
+        Ok(result)
     }
 
     /// Read frame info container for animated textures.
-    fn read_frame_info_container<R: Read>(&self, reader: &mut R) -> Result<TexFrameInfoContainer> {
+    fn read_frame_info_container<R: Read>(
+        &self,
+        reader: &mut R,
+        warnings: &mut Vec<String>,
+    ) -> Result<TexFrameInfoContainer> {
         // Read magic
         let magic = read_null_terminated_string(reader, 16)?;
         if magic != "TEXS0003" && magic != "TEXS0002" && magic != "TEXS0001" {
-            return Err(Error::invalid_data(format!(
-                "Invalid frame info magic: {}",
+            if !self.lenient {
+                return Err(Error::invalid_data(format!(
+                    "Invalid frame info magic: {}",
+                    magic
+                )));
+            }
+            warnings.push(format!(
+                "Unexpected frame info magic {:?}, reading it as TEXS0003",
                 magic
-            )));
+            ));
         }
 
         let gif_width = reader.read_u32::<LittleEndian>()?;
@@ -363,10 +691,10 @@ impl TexReader {
         let _unk1 = reader.read_u32::<LittleEndian>()?;
         let frame_count = reader.read_u32::<LittleEndian>()?;
 
-        if frame_count > MAX_FRAME_COUNT {
+        if frame_count > self.max_frames {
             return Err(Error::safety_limit(format!(
                 "Frame count {} exceeds maximum {}",
-                frame_count, MAX_FRAME_COUNT
+                frame_count, self.max_frames
             )));
         }
 
@@ -398,12 +726,159 @@ impl TexReader {
     }
 }
 
-impl Default for TexReader {
+impl Default for TexReader<'_> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Input to the TEX parser, abstracting over how mipmap data is read.
+trait TexSource: Read {
+    /// Read (or skip, when `keep` is false) `byte_count` bytes of mipmap data.
+    ///
+    /// When `lenient`, data ending early is returned as is, with a smaller
+    /// `byte_count`, instead of failing.
+    fn read_payload(
+        &mut self,
+        byte_count: u32,
+        keep: bool,
+        lenient: bool,
+    ) -> Result<MipmapBytesResult>;
+}
+
+/// Seekable input: payload lengths are validated against the stream length
+/// and skipped payloads are seeked over.
+struct Seekable<'a, R>(&'a mut R);
+
+impl<R: Read> Read for Seekable<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl<R: Read + Seek> TexSource for Seekable<'_, R> {
+    /// Validates that byte_count doesn't exceed remaining stream length (like C# version).
+    fn read_payload(
+        &mut self,
+        byte_count: u32,
+        keep: bool,
+        lenient: bool,
+    ) -> Result<MipmapBytesResult> {
+        let reader = &mut *self.0;
+
+        // Record the offset where data starts
+        let file_offset = reader.stream_position()?;
+
+        // Validate against stream length (matches C# behavior)
+        let stream_len = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(file_offset))?;
+
+        let remaining = stream_len.saturating_sub(file_offset);
+        let byte_count = if byte_count as u64 <= remaining {
+            byte_count
+        } else if lenient {
+            remaining as u32
+        } else {
+            return Err(Error::safety_limit(format!(
+                "Mipmap byte count {} exceeds remaining stream length (pos: {}, len: {})",
+                byte_count, file_offset, stream_len
+            )));
+        };
+
+        if !keep {
+            // Skip the bytes but record metadata
+            reader.seek(SeekFrom::Current(byte_count as i64))?;
+            return Ok(MipmapBytesResult {
+                bytes: Vec::new(),
+                byte_count,
+                file_offset,
+            });
+        }
+
+        let mut bytes = vec![0u8; byte_count as usize];
+        reader.read_exact(&mut bytes)?;
+        Ok(MipmapBytesResult {
+            bytes,
+            byte_count,
+            file_offset,
+        })
+    }
+}
+
+/// Forward-only input: payloads are read by their length prefix, tracking
+/// the position so mipmap offsets can still be reported.
+struct Streaming<'a, R> {
+    inner: &'a mut R,
+    position: u64,
+}
+
+impl<R: Read> Read for Streaming<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl<R: Read> TexSource for Streaming<'_, R> {
+    fn read_payload(
+        &mut self,
+        byte_count: u32,
+        keep: bool,
+        lenient: bool,
+    ) -> Result<MipmapBytesResult> {
+        let file_offset = self.position;
+
+        // The length is untrusted, so grow the buffer as data arrives
+        // instead of allocating it up front
+        let mut bytes = Vec::new();
+        let mut payload = self.take(byte_count as u64);
+        let read = if keep {
+            payload.read_to_end(&mut bytes)? as u64
+        } else {
+            std::io::copy(&mut payload, &mut std::io::sink())?
+        };
+
+        if read < byte_count as u64 && !lenient {
+            return Err(Error::UnexpectedEof {
+                position: file_offset + read,
+            });
+        }
+
+        Ok(MipmapBytesResult {
+            bytes,
+            byte_count: read as u32,
+            file_offset,
+        })
+    }
+}
+
+/// Reject headers whose dimensions can't belong to a real texture.
+///
+/// A corrupt or byte-swapped (big-endian) file otherwise only fails later,
+/// with a less helpful safety-limit error from the image container.
+fn check_header_dimensions(header: &TexHeader) -> Result<()> {
+    let dimensions = [
+        header.texture_width,
+        header.texture_height,
+        header.image_width,
+        header.image_height,
+    ];
+    if dimensions.iter().all(|&side| side <= MAX_DIMENSION) {
+        return Ok(());
+    }
+
+    Err(Error::invalid_data(format!(
+        "Implausible TEX dimensions (texture {}x{}, image {}x{}, maximum {}); \
+         the file may be corrupt or not little-endian",
+        header.texture_width,
+        header.texture_height,
+        header.image_width,
+        header.image_height,
+        MAX_DIMENSION
+    )))
+}
+
 /// Read a null-terminated string with maximum length.
 fn read_null_terminated_string<R: Read>(reader: &mut R, max_length: usize) -> Result<String> {
     let mut bytes = Vec::with_capacity(max_length.min(32));
@@ -431,4 +906,488 @@ mod tests {
         let result = read_null_terminated_string(&mut cursor, 16).unwrap();
         assert_eq!(result, "TEXV0005");
     }
+
+    fn tex_with_container_magic(magic: &str) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"TEXV0005\0TEXI0001\0");
+        for value in [0u32, 0, 16, 16, 12, 10, 0] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(magic.as_bytes());
+        data.push(0);
+        data
+    }
+
+    #[test]
+    fn test_unsupported_container_fallback() {
+        let data = tex_with_container_magic("TEXB9999");
+
+        let strict = TexReader::new().read_from(&mut Cursor::new(&data));
+        assert!(matches!(
+            strict,
+            Err(Error::UnsupportedContainerVersion { .. })
+        ));
+
+        let tex = TexReader::new()
+            .with_unsupported_container_fallback(true)
+            .read_from(&mut Cursor::new(&data))
+            .unwrap();
+        assert_eq!(tex.unsupported_container.as_deref(), Some("TEXB9999"));
+        assert_eq!(tex.header.crop_dimensions(), (12, 10));
+        assert!(!tex.has_images());
+
+        let converted =
+            crate::TexToImageConverter::new().convert(&tex, crate::texture::OutputFormat::Png);
+        assert!(matches!(
+            converted,
+            Err(Error::UnsupportedContainerVersion { .. })
+        ));
+    }
+
+    fn rgba_tex(width: u32, height: u32) -> Vec<u8> {
+        let mut data = tex_with_container_magic("TEXB0003");
+        let pixels = vec![0x7Fu8; (width * height * 4) as usize];
+        for value in [1i32, -1] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [1u32, width, height, 0, 0, pixels.len() as u32] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&pixels);
+        data
+    }
+
+    #[test]
+    fn test_read_from_stream() {
+        let data = rgba_tex(2, 2);
+
+        // A byte slice is Read but not Seek
+        let tex = TexReader::new().read_from_stream(&mut &data[..]).unwrap();
+        let seeked = TexReader::new().read_from(&mut Cursor::new(&data)).unwrap();
+        let mipmap = tex.first_image().unwrap().first_mipmap().unwrap();
+        let expected = seeked.first_image().unwrap().first_mipmap().unwrap();
+        assert_eq!(mipmap.bytes, expected.bytes);
+        assert_eq!(mipmap.file_offset, expected.file_offset);
+
+        let headers = TexReader::headers_only()
+            .read_from_stream(&mut &data[..])
+            .unwrap();
+        let mipmap = headers.first_image().unwrap().first_mipmap().unwrap();
+        assert!(mipmap.bytes.is_empty());
+        assert_eq!(mipmap.original_byte_count, 16);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_decompression_matches_serial() {
+        // DXT5 texture with LZ4-compressed mipmaps of decreasing size
+        let mut data = tex_with_container_magic("TEXB0003");
+        data[18..22].copy_from_slice(&4u32.to_le_bytes());
+        for value in [1i32, -1] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        let sizes = [64u32, 32, 16, 8, 4];
+        data.extend_from_slice(&(sizes.len() as u32).to_le_bytes());
+        for (level, &size) in sizes.iter().enumerate() {
+            let blocks: Vec<u8> = (0..(size / 4) * (size / 4) * 16)
+                .map(|i| (i as usize * 7 + level) as u8)
+                .collect();
+            let compressed = lz4_flex::compress(&blocks);
+            for value in [size, size, 1, blocks.len() as u32, compressed.len() as u32] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            data.extend_from_slice(&compressed);
+        }
+
+        let serial = TexReader::new().read_from(&mut Cursor::new(&data)).unwrap();
+        let parallel = TexReader::new()
+            .with_parallel_decompression(true)
+            .read_from(&mut Cursor::new(&data))
+            .unwrap();
+
+        let serial = &serial.first_image().unwrap().mipmaps;
+        let parallel = &parallel.first_image().unwrap().mipmaps;
+        assert_eq!(serial.len(), sizes.len());
+        for (a, b) in serial.iter().zip(parallel) {
+            assert_eq!(a.format, MipmapFormat::RGBA8888);
+            assert_eq!((a.width, a.format), (b.width, b.format));
+            assert_eq!(a.bytes, b.bytes);
+        }
+    }
+
+    #[test]
+    fn test_true_format_inference() {
+        // Header declares RG88, but the 2x2 mipmap only holds R8 data
+        let mut data = tex_with_container_magic("TEXB0003");
+        data[18..22].copy_from_slice(&9u32.to_le_bytes());
+        for value in [1i32, -1] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [1u32, 2, 2, 0, 0, 4] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&[10, 20, 30, 40]);
+
+        let tex = TexReader::new().read_from(&mut Cursor::new(&data)).unwrap();
+        let mipmap = tex.first_image().unwrap().first_mipmap().unwrap();
+        assert_eq!(mipmap.format, MipmapFormat::RG88);
+        assert_eq!(mipmap.declared_format, MipmapFormat::RG88);
+
+        let tex = TexReader::new()
+            .with_true_format_inference(true)
+            .read_from(&mut Cursor::new(&data))
+            .unwrap();
+        let mipmap = tex.first_image().unwrap().first_mipmap().unwrap();
+        assert_eq!(mipmap.format, MipmapFormat::R8);
+        assert_eq!(mipmap.declared_format, MipmapFormat::RG88);
+    }
+
+    #[test]
+    fn test_read_from_stream_truncated() {
+        let data = rgba_tex(2, 2);
+        let truncated = &data[..data.len() - 3];
+
+        let result = TexReader::new().read_from_stream(&mut &truncated[..]);
+        assert!(matches!(
+            result,
+            Err(Error::UnexpectedEof { position }) if position == data.len() as u64 - 3
+        ));
+    }
+
+    #[test]
+    fn test_read_from_stream_truncated_header() {
+        let data = rgba_tex(2, 2);
+        // Both magic strings, then half of the format field
+        let truncated = &data[..20];
+
+        let result = TexReader::new().read_from_stream(&mut &truncated[..]);
+        assert!(matches!(result, Err(Error::UnexpectedEof { position: 20 })));
+    }
+
+    #[test]
+    fn test_configurable_limits() {
+        let data = rgba_tex(2, 2);
+
+        let result = TexReader::new()
+            .with_max_images(0)
+            .read_from(&mut Cursor::new(&data));
+        assert!(matches!(result, Err(Error::SafetyLimit { .. })));
+
+        let result = TexReader::new()
+            .with_max_mipmaps(0)
+            .read_from(&mut Cursor::new(&data));
+        assert!(matches!(result, Err(Error::SafetyLimit { .. })));
+
+        let tex = TexReader::new()
+            .with_max_images(1)
+            .with_max_mipmaps(1)
+            .read_from(&mut Cursor::new(&data))
+            .unwrap();
+        assert_eq!(tex.images_container.images.len(), 1);
+    }
+
+    #[test]
+    fn test_v4_without_video_keeps_magic() {
+        let mut data = tex_with_container_magic("TEXB0004");
+        for value in [1i32, -1, 0] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [1u32, 1, 1, 0, 0, 4] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&[255; 4]);
+
+        let tex = TexReader::new().read_from(&mut Cursor::new(&data)).unwrap();
+        let container = &tex.images_container;
+        assert_eq!(container.version, TexImageContainerVersion::Version3);
+        assert_eq!(container.magic, "TEXB0004");
+        assert_eq!(container.raw_version(), TexImageContainerVersion::Version4);
+        assert!(container.is_downgraded());
+    }
+
+    #[test]
+    fn test_v4_mipmap_params() {
+        let mut data = tex_with_container_magic("TEXB0004");
+        for value in [1i32, -1, 1] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&1u32.to_le_bytes());
+        for value in [3u32, 7] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(b"{\"loop\":true}\0");
+        data.extend_from_slice(&9u32.to_le_bytes());
+        for value in [16u32, 16, 0, 0, 12] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(b"\0\0\0\x08ftypisom");
+
+        let tex = TexReader::new().read_from(&mut Cursor::new(&data)).unwrap();
+        assert_eq!(
+            tex.images_container.version,
+            TexImageContainerVersion::Version4
+        );
+        let mipmap = tex.first_image().unwrap().first_mipmap().unwrap();
+        let params = mipmap.v4_params.as_ref().unwrap();
+        assert_eq!((params.param1, params.param2, params.param3), (3, 7, 9));
+        assert_eq!(params.condition_json, "{\"loop\":true}");
+
+        // Earlier containers don't have the parameters
+        let tex = TexReader::new()
+            .read_from(&mut Cursor::new(&rgba_tex(2, 2)))
+            .unwrap();
+        let mipmap = tex.first_image().unwrap().first_mipmap().unwrap();
+        assert!(mipmap.v4_params.is_none());
+    }
+
+    #[test]
+    fn test_v5_container_uses_v4_layout() {
+        let mut data = tex_with_container_magic("TEXB0005");
+        let pixels = [0x7Fu8; 16];
+        for value in [1i32, -1, 0] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [1u32, 2, 2, 0, 0, pixels.len() as u32] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&pixels);
+
+        let tex = TexReader::new().read_from(&mut Cursor::new(&data)).unwrap();
+        assert_eq!(tex.images_container.magic, "TEXB0005");
+        assert_eq!(tex.first_image().unwrap().first_mipmap().unwrap().width, 2);
+
+        // A layout mismatch reports the fields that were read
+        let truncated = &data[..data.len() - 8];
+        let message = TexReader::new()
+            .read_from(&mut Cursor::new(truncated))
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("TEXB0005"), "{}", message);
+        assert!(message.contains("image_count=1"), "{}", message);
+        assert!(message.contains("is_video_mp4=0"), "{}", message);
+
+        // Other errors are not re-wrapped
+        let mut bad_lz4 = tex_with_container_magic("TEXB0005");
+        for value in [1i32, -1, 0] {
+            bad_lz4.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [1u32, 2, 2, 1, 16, 4] {
+            bad_lz4.extend_from_slice(&value.to_le_bytes());
+        }
+        bad_lz4.extend_from_slice(&[0xFF; 4]);
+        assert!(matches!(
+            TexReader::new().read_from(&mut Cursor::new(&bad_lz4)),
+            Err(Error::Lz4Decompression { .. } | Error::Lz4SizeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_v2_video_flag() {
+        let mut data = tex_with_container_magic("TEXB0002");
+        data[22] = TexFlags::IS_VIDEO_TEXTURE.bits() as u8;
+        let mp4 = b"\0\0\0\x0cftypisom\0\0\0\0";
+        data.extend_from_slice(&1i32.to_le_bytes());
+        for value in [1u32, 16, 16, 0, 0, mp4.len() as u32] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(mp4);
+
+        let tex = TexReader::new().read_from(&mut Cursor::new(&data)).unwrap();
+        assert!(tex.is_video());
+        assert_eq!(tex.images_container.image_format, FreeImageFormat::Unknown);
+        let mipmap = tex.first_image().unwrap().first_mipmap().unwrap();
+        assert_eq!(mipmap.format, MipmapFormat::VideoMp4);
+
+        let converter = crate::TexToImageConverter::new();
+        let format = converter.recommended_format(&tex);
+        assert_eq!(format, crate::texture::OutputFormat::Mp4);
+        let result = converter.convert(&tex, format).unwrap();
+        assert_eq!(result.bytes, mp4);
+    }
+
+    #[test]
+    fn test_mipmap_level_filter() {
+        let mut data = tex_with_container_magic("TEXB0003");
+        for value in [1i32, -1] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&3u32.to_le_bytes());
+        for size in [4u32, 2, 1] {
+            let pixels = vec![size as u8; (size * size * 4) as usize];
+            for value in [size, size, 0, 0, pixels.len() as u32] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            data.extend_from_slice(&pixels);
+        }
+
+        let read_levels = |levels: Range<usize>| {
+            let tex = TexReader::new()
+                .with_mipmap_level_filter(levels)
+                .read_from(&mut Cursor::new(&data))
+                .unwrap();
+            tex.first_image()
+                .unwrap()
+                .mipmaps
+                .iter()
+                .map(|mipmap| (mipmap.width, mipmap.bytes.len()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(read_levels(0..1), [(4, 64), (2, 0), (1, 0)]);
+        assert_eq!(read_levels(2..3), [(4, 0), (2, 0), (1, 4)]);
+        assert_eq!(read_levels(0..usize::MAX), [(4, 64), (2, 16), (1, 4)]);
+    }
+
+    #[test]
+    fn test_headers_only_offsets_slice_data() {
+        let payload = [1u8, 2, 3, 4, 5, 6, 7, 8];
+
+        // V1 mipmaps have no LZ4 fields
+        let mut v1 = tex_with_container_magic("TEXB0001");
+        for value in [1u32, 1, 2, 1, payload.len() as u32] {
+            v1.extend_from_slice(&value.to_le_bytes());
+        }
+        v1.extend_from_slice(&payload);
+
+        // V4 video mipmaps carry extra parameters before the V2/V3 layout
+        let mut v4 = tex_with_container_magic("TEXB0004");
+        for value in [1i32, -1, 1] {
+            v4.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [1u32, 0, 0] {
+            v4.extend_from_slice(&value.to_le_bytes());
+        }
+        v4.push(0); // empty condition JSON
+        v4.extend_from_slice(&0u32.to_le_bytes());
+        for value in [2u32, 1, 0, 0, payload.len() as u32] {
+            v4.extend_from_slice(&value.to_le_bytes());
+        }
+        v4.extend_from_slice(&payload);
+
+        for data in [v1, v4, rgba_tex(1, 2)] {
+            let tex = TexReader::headers_only()
+                .read_from(&mut Cursor::new(&data))
+                .unwrap();
+            let mipmap = tex.first_image().unwrap().first_mipmap().unwrap();
+            assert!(mipmap.bytes.is_empty());
+            let start = mipmap.file_offset as usize;
+            let end = start + mipmap.original_byte_count as usize;
+            assert_eq!(end, data.len());
+            assert_eq!(data[start..end].len(), 8);
+        }
+    }
+
+    #[test]
+    fn test_lenient_truncated_mipmap() {
+        let data = rgba_tex(2, 2);
+        let truncated = &data[..data.len() - 3];
+
+        let strict = TexReader::new().read_from(&mut Cursor::new(truncated));
+        assert!(matches!(strict, Err(Error::SafetyLimit { .. })));
+
+        let lenient = TexReader::new().with_lenient_parsing(true);
+        let tex = lenient.read_from(&mut Cursor::new(truncated)).unwrap();
+        let mipmap = tex.first_image().unwrap().first_mipmap().unwrap();
+        assert_eq!(mipmap.bytes.len(), 13);
+        assert_eq!(mipmap.original_byte_count, 13);
+        assert_eq!(tex.warnings.len(), 1);
+        assert!(tex.warnings[0].contains("truncated"), "{:?}", tex.warnings);
+
+        let streamed = lenient.read_from_stream(&mut &truncated[..]).unwrap();
+        assert_eq!(streamed.warnings, tex.warnings);
+    }
+
+    #[test]
+    fn test_lenient_frame_info_and_decompression() {
+        // GIF texture with a bad LZ4 mipmap and an unknown frame info magic
+        let mut data = tex_with_container_magic("TEXB0003");
+        data[22..26].copy_from_slice(&TexFlags::IS_GIF.bits().to_le_bytes());
+        for value in [1i32, -1] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [1u32, 2, 2, 1, 16, 4] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&[0xFF; 4]);
+        data.extend_from_slice(b"TEXS9999\0");
+        for value in [2u32, 2, 0, 0] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+
+        assert!(TexReader::new().read_from(&mut Cursor::new(&data)).is_err());
+
+        let tex = TexReader::new()
+            .with_lenient_parsing(true)
+            .read_from(&mut Cursor::new(&data))
+            .unwrap();
+        let mipmap = tex.first_image().unwrap().first_mipmap().unwrap();
+        assert!(mipmap.is_lz4_compressed);
+        assert_eq!(mipmap.bytes, [0xFF; 4]);
+        assert_eq!(tex.frame_info_container.as_ref().unwrap().gif_width, 2);
+        assert_eq!(tex.warnings.len(), 2, "{:?}", tex.warnings);
+        assert!(tex.warnings[0].contains("left compressed"));
+        assert!(tex.warnings[1].contains("TEXS9999"));
+    }
+
+    #[test]
+    fn test_unknown_format_warning() {
+        let mut data = rgba_tex(2, 2);
+        data[18..22].copy_from_slice(&42u32.to_le_bytes());
+
+        let tex = TexReader::new().read_from(&mut Cursor::new(&data)).unwrap();
+        assert_eq!(tex.warnings, ["Unknown texture format 42"]);
+
+        let tex = TexReader::new()
+            .read_from(&mut Cursor::new(&rgba_tex(2, 2)))
+            .unwrap();
+        assert!(tex.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_implausible_dimensions() {
+        let mut data = rgba_tex(2, 2);
+        // Texture width written big-endian
+        data[26..30].copy_from_slice(&16u32.to_be_bytes());
+
+        let result = TexReader::new().read_from(&mut Cursor::new(&data));
+        assert!(
+            matches!(&result, Err(Error::InvalidData { message }) if message.contains("little-endian")),
+            "{:?}",
+            result
+        );
+
+        data[26..30].copy_from_slice(&MAX_DIMENSION.to_le_bytes());
+        assert!(TexReader::new().read_from(&mut Cursor::new(&data)).is_ok());
+    }
+
+    #[test]
+    fn test_store_raw_bytes() {
+        let pixels: Vec<u8> = (0..16).collect();
+        let compressed = lz4_flex::compress(&pixels);
+        let mut data = tex_with_container_magic("TEXB0003");
+        for value in [1i32, -1] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [1u32, 2, 2, 1, 16, compressed.len() as u32] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&compressed);
+
+        let tex = TexReader::new()
+            .with_raw_bytes(true)
+            .read_from(&mut Cursor::new(&data))
+            .unwrap();
+        let mipmap = tex.first_image().unwrap().first_mipmap().unwrap();
+        assert_eq!(mipmap.bytes, pixels);
+        assert_eq!(mipmap.raw_bytes.as_deref(), Some(&compressed[..]));
+
+        let tex = TexReader::new().read_from(&mut Cursor::new(&data)).unwrap();
+        assert!(tex
+            .first_image()
+            .unwrap()
+            .first_mipmap()
+            .unwrap()
+            .raw_bytes
+            .is_none());
+    }
 }
@@ -0,0 +1,345 @@
+//! Pixel-level image transforms used during conversion.
+
+use image::{imageops::FilterType, DynamicImage, Rgb, RgbImage, Rgba, RgbaImage};
+
+use super::Channel;
+
+/// Rotate an image clockwise by an arbitrary angle (in radians).
+///
+/// The canvas grows to fit the rotated bounds. Pixels are sampled with
+/// bilinear interpolation on premultiplied alpha, and the corners uncovered by
+/// the rotation are left fully transparent.
+pub(crate) fn rotate_bilinear(image: &DynamicImage, angle: f64) -> DynamicImage {
+    let source = image.to_rgba8();
+    let (src_w, src_h) = source.dimensions();
+    let (sin, cos) = angle.sin_cos();
+
+    let dst_w = (src_w as f64 * cos.abs() + src_h as f64 * sin.abs()).round() as u32;
+    let dst_h = (src_w as f64 * sin.abs() + src_h as f64 * cos.abs()).round() as u32;
+    let mut output = RgbaImage::new(dst_w.max(1), dst_h.max(1));
+
+    let src_cx = src_w as f64 / 2.0;
+    let src_cy = src_h as f64 / 2.0;
+    let dst_cx = output.width() as f64 / 2.0;
+    let dst_cy = output.height() as f64 / 2.0;
+
+    for (x, y, pixel) in output.enumerate_pixels_mut() {
+        // Map the destination pixel centre back into the source image
+        let dx = x as f64 + 0.5 - dst_cx;
+        let dy = y as f64 + 0.5 - dst_cy;
+        let sx = cos * dx + sin * dy + src_cx - 0.5;
+        let sy = -sin * dx + cos * dy + src_cy - 0.5;
+
+        *pixel = sample_bilinear(&source, sx, sy);
+    }
+
+    DynamicImage::ImageRgba8(output)
+}
+
+/// Composite an image over an opaque gray checkerboard.
+///
+/// `checker_size` is the edge length of each square in pixels.
+pub(crate) fn composite_checkerboard(image: &RgbaImage, checker_size: u32) -> RgbaImage {
+    let checker_size = checker_size.max(1);
+    let mut output = RgbaImage::new(image.width(), image.height());
+
+    for (x, y, pixel) in image.enumerate_pixels() {
+        let light = ((x / checker_size) + (y / checker_size)) & 1 == 0;
+        let background = if light { CHECKER_LIGHT } else { CHECKER_DARK } as u32;
+
+        let [r, g, b, a] = pixel.0;
+        let alpha = a as u32;
+        let blend = |c: u8| ((c as u32 * alpha + background * (255 - alpha) + 127) / 255) as u8;
+        output.put_pixel(x, y, Rgba([blend(r), blend(g), blend(b), 255]));
+    }
+
+    output
+}
+
+/// Composite an image over a solid color, dropping the alpha channel.
+///
+/// The background's own alpha is ignored; the result is fully opaque.
+pub(crate) fn flatten_onto(image: &RgbaImage, background: [u8; 4]) -> RgbImage {
+    let mut output = RgbImage::new(image.width(), image.height());
+
+    for (x, y, pixel) in image.enumerate_pixels() {
+        let [r, g, b, a] = pixel.0;
+        let alpha = a as u32;
+        let blend =
+            |c: u8, bg: u8| ((c as u32 * alpha + bg as u32 * (255 - alpha) + 127) / 255) as u8;
+        output.put_pixel(
+            x,
+            y,
+            Rgb([
+                blend(r, background[0]),
+                blend(g, background[1]),
+                blend(b, background[2]),
+            ]),
+        );
+    }
+
+    output
+}
+
+/// Scale the color channels of an image by its alpha, converting straight
+/// alpha to premultiplied alpha.
+pub(crate) fn premultiply_alpha(image: &mut RgbaImage) {
+    for pixel in image.pixels_mut() {
+        let alpha = pixel.0[3] as u32;
+        for channel in &mut pixel.0[..3] {
+            *channel = ((*channel as u32 * alpha + 127) / 255) as u8;
+        }
+    }
+}
+
+/// Build a per-channel lookup table applying gamma, then a brightness offset.
+///
+/// Gamma maps `c` to `255 * (c / 255)^(1 / gamma)`; non-positive or
+/// non-finite gamma values are ignored.
+pub(crate) fn tone_lut(gamma: Option<f32>, brightness: Option<i16>) -> [u8; 256] {
+    let exponent = gamma
+        .filter(|g| g.is_finite() && *g > 0.0)
+        .map(|g| 1.0 / g as f64);
+    let offset = brightness.unwrap_or(0) as f64;
+
+    let mut lut = [0u8; 256];
+    for (value, entry) in lut.iter_mut().enumerate() {
+        let mut v = value as f64;
+        if let Some(exponent) = exponent {
+            v = 255.0 * (v / 255.0).powf(exponent);
+        }
+        *entry = (v + offset).round().clamp(0.0, 255.0) as u8;
+    }
+    lut
+}
+
+/// Map the color channels of an image through a lookup table, leaving alpha.
+pub(crate) fn apply_color_lut(image: &mut RgbaImage, lut: &[u8; 256]) {
+    for pixel in image.pixels_mut() {
+        for channel in &mut pixel.0[..3] {
+            *channel = lut[*channel as usize];
+        }
+    }
+}
+
+/// Rearrange the channels of an image; output channel `i` takes its value
+/// from `swizzle[i]`.
+pub(crate) fn swizzle_channels(image: &mut RgbaImage, swizzle: [Channel; 4]) {
+    for pixel in image.pixels_mut() {
+        let source = pixel.0;
+        pixel.0 = swizzle.map(|channel| match channel {
+            Channel::R => source[0],
+            Channel::G => source[1],
+            Channel::B => source[2],
+            Channel::A => source[3],
+            Channel::Zero => 0,
+            Channel::One => 255,
+        });
+    }
+}
+
+/// Expand two-channel (X, Y) normal map data to RGBA with a reconstructed Z.
+///
+/// X and Y are remapped from `[0, 255]` to `[-1, 1]`; Z is the positive root
+/// of `1 - X^2 - Y^2`, clamped to zero for non-unit inputs. Returns `None` if
+/// `data` does not hold exactly two bytes per pixel.
+pub(crate) fn reconstruct_normal_z(width: u32, height: u32, data: &[u8]) -> Option<RgbaImage> {
+    if data.len() != width as usize * height as usize * 2 {
+        return None;
+    }
+
+    let mut output = Vec::with_capacity(data.len() * 2);
+    for rg in data.chunks_exact(2) {
+        let x = rg[0] as f32 / 255.0 * 2.0 - 1.0;
+        let y = rg[1] as f32 / 255.0 * 2.0 - 1.0;
+        let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+        let b = ((z + 1.0) * 0.5 * 255.0).round() as u8;
+        output.extend_from_slice(&[rg[0], rg[1], b, 255]);
+    }
+
+    RgbaImage::from_raw(width, height, output)
+}
+
+/// Invert the green channel, converting normal maps between the DirectX
+/// and OpenGL conventions.
+pub(crate) fn invert_green(image: &mut RgbaImage) {
+    for pixel in image.pixels_mut() {
+        pixel.0[1] = 255 - pixel.0[1];
+    }
+}
+
+/// Tile images into a grid with `columns` cells per row.
+///
+/// Every cell is sized to the largest image; smaller or differently shaped
+/// images are resized with `filter` to fill it. Cells are separated by
+/// `padding` transparent pixels. Returns the sheet and the cell width and
+/// height.
+pub(crate) fn tile_images(
+    images: &[RgbaImage],
+    columns: u32,
+    padding: u32,
+    filter: FilterType,
+) -> (RgbaImage, u32, u32) {
+    let cell_width = images.iter().map(|i| i.width()).max().unwrap_or(0);
+    let cell_height = images.iter().map(|i| i.height()).max().unwrap_or(0);
+    let columns = columns.clamp(1, (images.len() as u32).max(1));
+    let rows = (images.len() as u32).div_ceil(columns);
+
+    let span = |cells: u32, cell: u32| (cells * cell + cells.saturating_sub(1) * padding).max(1);
+    let mut sheet = RgbaImage::new(span(columns, cell_width), span(rows, cell_height));
+
+    for (index, image) in images.iter().enumerate() {
+        let column = index as u32 % columns;
+        let row = index as u32 / columns;
+        let x = column * (cell_width + padding);
+        let y = row * (cell_height + padding);
+
+        if image.dimensions() == (cell_width, cell_height) {
+            image::imageops::replace(&mut sheet, image, x as i64, y as i64);
+        } else {
+            let resized = image::imageops::resize(image, cell_width, cell_height, filter);
+            image::imageops::replace(&mut sheet, &resized, x as i64, y as i64);
+        }
+    }
+
+    (sheet, cell_width, cell_height)
+}
+
+/// Gray level of the light checkerboard squares.
+const CHECKER_LIGHT: u8 = 204;
+/// Gray level of the dark checkerboard squares.
+const CHECKER_DARK: u8 = 153;
+
+/// Sample an image at fractional coordinates, treating out-of-bounds pixels as transparent.
+fn sample_bilinear(image: &RgbaImage, x: f64, y: f64) -> Rgba<u8> {
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let fx = x - x0;
+    let fy = y - y0;
+
+    let mut acc = [0.0f64; 4];
+    for (ox, oy, weight) in [
+        (0, 0, (1.0 - fx) * (1.0 - fy)),
+        (1, 0, fx * (1.0 - fy)),
+        (0, 1, (1.0 - fx) * fy),
+        (1, 1, fx * fy),
+    ] {
+        let px = x0 as i64 + ox;
+        let py = y0 as i64 + oy;
+        if weight == 0.0
+            || px < 0
+            || py < 0
+            || px >= image.width() as i64
+            || py >= image.height() as i64
+        {
+            continue;
+        }
+
+        let [r, g, b, a] = image.get_pixel(px as u32, py as u32).0;
+        let alpha = a as f64 / 255.0;
+        acc[0] += r as f64 * alpha * weight;
+        acc[1] += g as f64 * alpha * weight;
+        acc[2] += b as f64 * alpha * weight;
+        acc[3] += a as f64 * weight;
+    }
+
+    if acc[3] <= 0.0 {
+        return Rgba([0, 0, 0, 0]);
+    }
+
+    // Un-premultiply
+    let alpha = acc[3] / 255.0;
+    Rgba([
+        (acc[0] / alpha).round().clamp(0.0, 255.0) as u8,
+        (acc[1] / alpha).round().clamp(0.0, 255.0) as u8,
+        (acc[2] / alpha).round().clamp(0.0, 255.0) as u8,
+        acc[3].round().clamp(0.0, 255.0) as u8,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_composite_checkerboard() {
+        let mut image = RgbaImage::from_pixel(4, 4, Rgba([0, 0, 0, 0]));
+        image.put_pixel(3, 3, Rgba([255, 0, 0, 255]));
+
+        let composited = composite_checkerboard(&image, 2);
+        assert_eq!(composited.get_pixel(0, 0).0, [204, 204, 204, 255]);
+        assert_eq!(composited.get_pixel(2, 0).0, [153, 153, 153, 255]);
+        assert_eq!(composited.get_pixel(3, 3).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_tone_lut() {
+        let identity = tone_lut(None, None);
+        assert!(identity.iter().enumerate().all(|(i, v)| *v as usize == i));
+
+        let darker = tone_lut(Some(0.5), Some(-10));
+        assert_eq!(darker[0], 0);
+        assert_eq!(darker[255], 245);
+        assert_eq!(darker[128], 54);
+
+        let mut image = RgbaImage::from_pixel(1, 1, Rgba([10, 20, 30, 40]));
+        apply_color_lut(&mut image, &tone_lut(None, Some(100)));
+        assert_eq!(image.get_pixel(0, 0).0, [110, 120, 130, 40]);
+    }
+
+    #[test]
+    fn test_flatten_onto() {
+        let mut image = RgbaImage::from_pixel(2, 1, Rgba([0, 0, 0, 0]));
+        image.put_pixel(1, 0, Rgba([255, 255, 255, 128]));
+
+        let flattened = flatten_onto(&image, [0, 0, 255, 0]);
+        assert_eq!(flattened.get_pixel(0, 0).0, [0, 0, 255]);
+        assert_eq!(flattened.get_pixel(1, 0).0, [128, 128, 255]);
+    }
+
+    #[test]
+    fn test_reconstruct_normal_z() {
+        let image = reconstruct_normal_z(2, 1, &[128, 128, 255, 128]).unwrap();
+        assert!(image.get_pixel(0, 0).0[2] >= 254);
+        // Fully tilted along X leaves no Z component
+        assert_eq!(image.get_pixel(1, 0).0, [255, 128, 128, 255]);
+        assert!(reconstruct_normal_z(2, 2, &[0; 3]).is_none());
+    }
+
+    #[test]
+    fn test_tile_images_resizes_to_largest() {
+        let images = vec![
+            RgbaImage::from_pixel(4, 2, Rgba([255; 4])),
+            RgbaImage::from_pixel(2, 2, Rgba([255; 4])),
+            RgbaImage::from_pixel(4, 2, Rgba([255; 4])),
+        ];
+
+        let (sheet, cell_width, cell_height) = tile_images(&images, 2, 1, FilterType::Lanczos3);
+        assert_eq!((cell_width, cell_height), (4, 2));
+        assert_eq!(sheet.dimensions(), (9, 5));
+        assert_eq!(sheet.get_pixel(8, 1).0, [255; 4]);
+        assert_eq!(sheet.get_pixel(4, 0).0[3], 0);
+        assert_eq!(sheet.get_pixel(8, 4).0[3], 0);
+    }
+
+    #[test]
+    fn test_rotate_bilinear_quarter_turn() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 2, Rgba([255; 4])));
+        let rotated = rotate_bilinear(&image, std::f64::consts::FRAC_PI_2);
+        assert_eq!((rotated.width(), rotated.height()), (2, 4));
+        assert_eq!(rotated.to_rgba8().get_pixel(1, 2).0, [255; 4]);
+    }
+
+    #[test]
+    fn test_rotate_bilinear_transparent_corners() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(20, 20, Rgba([255; 4])));
+        let rotated = rotate_bilinear(&image, 30f64.to_radians());
+
+        // Bounding box of a 20x20 square rotated by 30 degrees
+        assert_eq!((rotated.width(), rotated.height()), (27, 27));
+
+        let rgba = rotated.to_rgba8();
+        assert_eq!(rgba.get_pixel(0, 0).0[3], 0);
+        assert_eq!(rgba.get_pixel(13, 13).0, [255; 4]);
+    }
+}
@@ -0,0 +1,206 @@
+//! Animated WebP encoding.
+//!
+//! The `image` crate only encodes still WebP images, so each frame is encoded
+//! on its own (lossless VP8L) and the resulting bitstreams are wrapped in an
+//! animated RIFF container (`VP8X` + `ANIM` + one `ANMF` per frame).
+
+use image::{codecs::webp::WebPEncoder, ExtendedColorType, Frame, ImageEncoder};
+
+use crate::error::{Error, Result};
+
+/// VP8X flag: the file contains an animation.
+const VP8X_ANIMATION: u8 = 0x02;
+/// VP8X flag: the file contains alpha.
+const VP8X_ALPHA: u8 = 0x10;
+/// ANMF flag: do not blend the frame with the previous canvas.
+const ANMF_NO_BLEND: u8 = 0x02;
+
+/// Encode frames as a looping animated WebP.
+///
+/// Frame durations come from each frame's delay. Each frame is placed at its
+/// own offset, rounded down to an even position as WebP requires, on a
+/// canvas large enough to hold every frame.
+pub(crate) fn encode_animated(frames: &[Frame]) -> Result<Vec<u8>> {
+    if frames.is_empty() {
+        return Err(Error::invalid_data(
+            "Cannot encode an animation without frames",
+        ));
+    }
+
+    // WebP stores dimensions minus one, so an empty frame can't be represented
+    if frames
+        .iter()
+        .any(|f| f.buffer().width() == 0 || f.buffer().height() == 0)
+    {
+        return Err(Error::invalid_data(
+            "Cannot encode an animation frame with zero width or height",
+        ));
+    }
+
+    let canvas_width = frames
+        .iter()
+        .map(|f| f.left() / 2 * 2 + f.buffer().width())
+        .max()
+        .unwrap_or(1);
+    let canvas_height = frames
+        .iter()
+        .map(|f| f.top() / 2 * 2 + f.buffer().height())
+        .max()
+        .unwrap_or(1);
+
+    let mut body = Vec::new();
+    body.extend_from_slice(b"WEBP");
+
+    // VP8X: feature flags and canvas size
+    let mut vp8x = vec![VP8X_ANIMATION | VP8X_ALPHA, 0, 0, 0];
+    vp8x.extend_from_slice(&u24(canvas_width - 1));
+    vp8x.extend_from_slice(&u24(canvas_height - 1));
+    write_chunk(&mut body, b"VP8X", &vp8x);
+
+    // ANIM: transparent background, loop forever
+    write_chunk(&mut body, b"ANIM", &[0, 0, 0, 0, 0, 0]);
+
+    for frame in frames {
+        let buffer = frame.buffer();
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        let duration_ms = (numer / denom.max(1)).min(0xFF_FFFF);
+
+        let mut anmf = Vec::new();
+        anmf.extend_from_slice(&u24(frame.left() / 2));
+        anmf.extend_from_slice(&u24(frame.top() / 2));
+        anmf.extend_from_slice(&u24(buffer.width() - 1));
+        anmf.extend_from_slice(&u24(buffer.height() - 1));
+        anmf.extend_from_slice(&u24(duration_ms));
+        anmf.push(ANMF_NO_BLEND);
+        anmf.extend_from_slice(&encode_frame_bitstream(frame)?);
+        write_chunk(&mut body, b"ANMF", &anmf);
+    }
+
+    let mut output = Vec::with_capacity(body.len() + 8);
+    output.extend_from_slice(b"RIFF");
+    output.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    output.extend_from_slice(&body);
+    Ok(output)
+}
+
+/// Encode a single frame and return its image chunks (everything after the RIFF header).
+fn encode_frame_bitstream(frame: &Frame) -> Result<Vec<u8>> {
+    let buffer = frame.buffer();
+    let mut still = Vec::new();
+    WebPEncoder::new_lossless(&mut still).write_image(
+        buffer.as_raw(),
+        buffer.width(),
+        buffer.height(),
+        ExtendedColorType::Rgba8,
+    )?;
+
+    // Strip "RIFF" + size + "WEBP"
+    if still.len() < 12 || &still[8..12] != b"WEBP" {
+        return Err(Error::invalid_data(
+            "WebP encoder produced an invalid frame",
+        ));
+    }
+    Ok(still.split_off(12))
+}
+
+/// Write a RIFF chunk, padding odd-sized payloads.
+fn write_chunk(output: &mut Vec<u8>, fourcc: &[u8; 4], payload: &[u8]) {
+    output.extend_from_slice(fourcc);
+    output.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    output.extend_from_slice(payload);
+    if payload.len() % 2 == 1 {
+        output.push(0);
+    }
+}
+
+/// Little-endian 24-bit integer.
+fn u24(value: u32) -> [u8; 3] {
+    let bytes = value.to_le_bytes();
+    [bytes[0], bytes[1], bytes[2]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{
+        codecs::webp::WebPDecoder, AnimationDecoder, Delay, ImageDecoder, Rgba, RgbaImage,
+    };
+    use std::io::Cursor;
+
+    #[test]
+    fn test_encode_animated_round_trip() {
+        let frames = vec![
+            Frame::from_parts(
+                RgbaImage::from_pixel(6, 4, Rgba([255, 0, 0, 255])),
+                0,
+                0,
+                Delay::from_numer_denom_ms(100, 1),
+            ),
+            Frame::from_parts(
+                RgbaImage::from_pixel(6, 4, Rgba([0, 0, 255, 128])),
+                0,
+                0,
+                Delay::from_numer_denom_ms(250, 1),
+            ),
+        ];
+
+        let bytes = encode_animated(&frames).unwrap();
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(&bytes[8..12], b"WEBP");
+
+        let decoder = WebPDecoder::new(Cursor::new(&bytes)).unwrap();
+        assert!(decoder.has_animation());
+        let decoded = decoder.into_frames().collect_frames().unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[1].delay().numer_denom_ms(), (250, 1));
+        assert_eq!(decoded[1].buffer().get_pixel(0, 0).0, [0, 0, 255, 128]);
+    }
+
+    #[test]
+    fn test_encode_animated_frame_offsets() {
+        let frames = vec![
+            Frame::from_parts(
+                RgbaImage::from_pixel(4, 4, Rgba([255, 0, 0, 255])),
+                0,
+                0,
+                Delay::from_numer_denom_ms(100, 1),
+            ),
+            Frame::from_parts(
+                RgbaImage::from_pixel(2, 2, Rgba([0, 0, 255, 255])),
+                4,
+                2,
+                Delay::from_numer_denom_ms(100, 1),
+            ),
+        ];
+
+        let bytes = encode_animated(&frames).unwrap();
+        let decoder = WebPDecoder::new(Cursor::new(&bytes)).unwrap();
+        assert_eq!(decoder.dimensions(), (6, 4));
+        let decoded = decoder.into_frames().collect_frames().unwrap();
+        assert_eq!(decoded[1].buffer().get_pixel(4, 2).0, [0, 0, 255, 255]);
+        assert_eq!(decoded[1].buffer().get_pixel(0, 0).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_encode_animated_rejects_empty_frame() {
+        let frames = vec![
+            Frame::from_parts(
+                RgbaImage::from_pixel(6, 4, Rgba([255, 0, 0, 255])),
+                0,
+                0,
+                Delay::from_numer_denom_ms(100, 1),
+            ),
+            Frame::from_parts(
+                RgbaImage::new(0, 4),
+                0,
+                0,
+                Delay::from_numer_denom_ms(100, 1),
+            ),
+        ];
+
+        assert!(matches!(
+            encode_animated(&frames),
+            Err(Error::InvalidData { .. })
+        ));
+    }
+}
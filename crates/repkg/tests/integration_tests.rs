@@ -1,7 +1,7 @@
 //! Integration tests using real Wallpaper Engine PKG and TEX files.
 
-use repkg::package::PackageReader;
-use repkg::texture::{OutputFormat, TexReader, TexToImageConverter};
+use repkg::package::{PackageReader, PackageWriter, SceneReferences};
+use repkg::texture::{CompressionType, OutputFormat, TexReader, TexToImageConverter};
 use repkg_core::{MipmapFormat, TexFlags, TexFormat, TexImageContainerVersion};
 use std::fs;
 use std::io::Cursor;
@@ -85,6 +85,30 @@ fn test_pkg_entry_types() {
     assert_eq!(shader_count, 4, "Expected 4 shader files");
 }
 
+#[test]
+fn test_pkg_glob_entries() {
+    let pkg_path = fixtures_dir().join("scene.pkg");
+    if !pkg_path.exists() {
+        return;
+    }
+
+    let bytes = fs::read(&pkg_path).expect("Failed to read PKG file");
+    let package = PackageReader::info_only()
+        .read_from(&mut Cursor::new(&bytes))
+        .expect("Failed to parse PKG");
+
+    let textures = package.glob("materials/**/*.tex");
+    let paths: Vec<&str> = textures.iter().map(|e| e.full_path.as_str()).collect();
+    assert!(paths.contains(&"materials/Reze poster.tex"));
+    assert!(paths.contains(&"materials/masks/waterwaves_mask_b95b17e8.tex"));
+
+    let expected = package
+        .find_entries(|e| e.full_path.starts_with("materials/") && e.full_path.ends_with(".tex"));
+    assert_eq!(textures.len(), expected.len());
+
+    assert_eq!(package.glob("scene.json").len(), 1);
+}
+
 #[test]
 fn test_pkg_extract_entry() {
     let pkg_path = fixtures_dir().join("scene.pkg");
@@ -112,6 +136,57 @@ fn test_pkg_extract_entry() {
     let _: serde_json::Value = serde_json::from_str(&json_str).expect("Invalid JSON");
 }
 
+#[test]
+fn test_scene_references_present() {
+    let pkg_path = fixtures_dir().join("scene.pkg");
+    if !pkg_path.exists() {
+        return;
+    }
+
+    let bytes = fs::read(&pkg_path).expect("Failed to read PKG file");
+    let package = PackageReader::new()
+        .read_from(&mut Cursor::new(&bytes))
+        .expect("Failed to parse PKG");
+
+    let scene = package
+        .entry_bytes("scene.json")
+        .expect("scene.json not found");
+    let scene = std::str::from_utf8(scene).expect("Invalid UTF-8");
+    let missing = package
+        .missing_references(scene)
+        .expect("Failed to parse scene.json");
+    assert!(missing.is_empty(), "missing assets: {:?}", missing);
+}
+
+#[test]
+fn test_pkg_write_round_trip() {
+    let pkg_path = fixtures_dir().join("scene.pkg");
+    if !pkg_path.exists() {
+        return;
+    }
+
+    let bytes = fs::read(&pkg_path).expect("Failed to read PKG file");
+    let package = PackageReader::new()
+        .read_from(&mut Cursor::new(&bytes))
+        .expect("Failed to parse PKG");
+
+    let mut written = Vec::new();
+    PackageWriter::new(package.magic.clone())
+        .write_to(&mut written, &package.entries)
+        .expect("Failed to write PKG");
+
+    let reread = PackageReader::new()
+        .read_from(&mut Cursor::new(&written))
+        .expect("Failed to re-read PKG");
+
+    assert_eq!(reread.magic, package.magic);
+    assert_eq!(reread.entries.len(), package.entries.len());
+    for (original, copy) in package.entries.iter().zip(&reread.entries) {
+        assert_eq!(original.full_path, copy.full_path);
+        assert_eq!(original.bytes, copy.bytes);
+    }
+}
+
 // ============================================================================
 // TEX Tests - Embedded PNG Image
 // ============================================================================
@@ -149,7 +224,7 @@ fn test_read_tex_embedded_png() {
 
     // Verify mipmaps (embedded PNG has multiple mipmap levels)
     let image = tex.first_image().expect("No image");
-    assert!(image.mipmaps.len() >= 1);
+    assert!(!image.mipmaps.is_empty());
 
     let mipmap = image.first_mipmap().expect("No mipmap");
     assert_eq!(mipmap.format, MipmapFormat::ImagePNG);
@@ -191,6 +266,56 @@ fn test_convert_tex_embedded_png_to_png() {
     assert_eq!(img.height(), 2160);
 }
 
+#[test]
+fn test_convert_mipmap_level() {
+    let tex_path = fixtures_dir().join("image.tex");
+    if !tex_path.exists() {
+        return;
+    }
+
+    let bytes = fs::read(&tex_path).expect("Failed to read TEX file");
+    let tex = TexReader::new()
+        .read_from(&mut Cursor::new(&bytes))
+        .expect("Failed to parse TEX");
+
+    let converter = TexToImageConverter::new();
+    let level0 = converter
+        .convert_mipmap(&tex, 0, OutputFormat::Png)
+        .expect("Failed to convert level 0");
+    let level1 = converter
+        .convert_mipmap(&tex, 1, OutputFormat::Png)
+        .expect("Failed to convert level 1");
+
+    assert!(level1.bytes.len() < level0.bytes.len());
+
+    let img0 = image::load_from_memory(&level0.bytes).expect("Failed to decode level 0");
+    let img1 = image::load_from_memory(&level1.bytes).expect("Failed to decode level 1");
+    assert!(img1.width() < img0.width());
+    assert!(img1.height() < img0.height());
+}
+
+#[test]
+fn test_read_only_mipmap_level_0() {
+    let tex_path = fixtures_dir().join("image.tex");
+    if !tex_path.exists() {
+        return;
+    }
+
+    let bytes = fs::read(&tex_path).expect("Failed to read TEX file");
+    let tex = TexReader::new()
+        .with_mipmap_level_filter(0..1)
+        .read_from(&mut Cursor::new(&bytes))
+        .expect("Failed to parse TEX");
+
+    let image = tex.first_image().expect("No image");
+    assert!(image.mipmap_count() > 1);
+    assert!(image.mipmaps[0].has_data());
+    for mipmap in &image.mipmaps[1..] {
+        assert!(mipmap.bytes.is_empty());
+        assert!(mipmap.original_byte_count > 0);
+    }
+}
+
 // ============================================================================
 // TEX Tests - Raw R8 Grayscale Mask
 // ============================================================================
@@ -274,6 +399,29 @@ fn test_convert_tex_r8_mask_to_png() {
 // Format Conversion Tests
 // ============================================================================
 
+#[test]
+fn test_png_best_compression_not_larger() {
+    let tex_path = fixtures_dir().join("mask.tex");
+    if !tex_path.exists() {
+        return;
+    }
+
+    let bytes = fs::read(&tex_path).expect("Failed to read TEX file");
+    let tex = TexReader::new()
+        .read_from(&mut Cursor::new(&bytes))
+        .expect("Failed to parse TEX");
+
+    let default = TexToImageConverter::new()
+        .convert(&tex, OutputFormat::Png)
+        .expect("Failed to convert with default compression");
+    let best = TexToImageConverter::new()
+        .with_png_compression(CompressionType::Best)
+        .convert(&tex, OutputFormat::Png)
+        .expect("Failed to convert with best compression");
+
+    assert!(best.bytes.len() <= default.bytes.len());
+}
+
 #[test]
 fn test_convert_to_jpeg() {
     let tex_path = fixtures_dir().join("image.tex");
@@ -332,6 +480,34 @@ fn test_convert_to_webp() {
     assert_eq!(&result.bytes[8..12], b"WEBP");
 }
 
+#[test]
+fn test_convert_to_qoi() {
+    let tex_path = fixtures_dir().join("image.tex");
+    if !tex_path.exists() {
+        return;
+    }
+
+    let bytes = fs::read(&tex_path).expect("Failed to read TEX file");
+    let reader = TexReader::new();
+    let tex = reader
+        .read_from(&mut Cursor::new(&bytes))
+        .expect("Failed to parse TEX");
+
+    let converter = TexToImageConverter::new();
+    let result = converter
+        .convert(&tex, OutputFormat::Qoi)
+        .expect("Failed to convert to QOI");
+
+    assert_eq!(result.format, OutputFormat::Qoi);
+    assert_eq!(&result.bytes[0..4], b"qoif");
+
+    // Verify dimensions
+    let img = image::load_from_memory_with_format(&result.bytes, image::ImageFormat::Qoi)
+        .expect("Failed to decode QOI");
+    assert_eq!(img.width(), 3840);
+    assert_eq!(img.height(), 2160);
+}
+
 // ============================================================================
 // Edge Cases
 // ============================================================================
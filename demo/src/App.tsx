@@ -57,7 +57,7 @@ function AppContent() {
       setProgress({ message: 'Parsing file...', percent: 50 });
 
       if (fileType === 'pkg') {
-        const pkgInfo = wasmModule.parse_pkg(bytes);
+        const pkgInfo = wasmModule.parse_pkg_info(bytes);
         logPkgParse(pkgInfo.magic, pkgInfo.entry_count, pkgInfo.entries);
         setProgress({ message: 'Done!', percent: 100 });
         setLoadedFile({
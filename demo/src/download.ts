@@ -51,6 +51,8 @@ export function getMimeType(format: string): string {
     bmp: 'image/bmp',
     tiff: 'image/tiff',
     tga: 'image/x-targa',
+    qoi: 'image/qoi',
+    dds: 'image/vnd-ms.dds',
     mp4: 'video/mp4',
   };
   return mimeTypes[format.toLowerCase()] || 'application/octet-stream';
@@ -3,12 +3,17 @@ import { logBanner, logWasmInit, handleWasmLog } from './logger';
 // Types for WASM module
 export interface PkgEntryInfo {
   path: string;
+  /** Offset of the data from the end of the header (`PkgInfo.header_size`) */
+  offset: number;
   size: number;
   entry_type: string;
 }
 
 export interface PkgInfo {
   magic: string;
+  version: number;
+  unrecognized_magic: boolean;
+  header_size: number;
   entry_count: number;
   entries: PkgEntryInfo[];
 }
@@ -18,10 +23,21 @@ export interface TexInfo {
   height: number;
   texture_width: number;
   texture_height: number;
+  output_width: number;
+  output_height: number;
   format: string;
   is_gif: boolean;
   is_video: boolean;
   mipmap_count: number;
+  mipmaps: MipmapInfo[];
+}
+
+export interface MipmapInfo {
+  width: number;
+  height: number;
+  format: string;
+  byte_count: number;
+  is_lz4_compressed: boolean;
 }
 
 export interface ExtractedFile {
@@ -33,6 +49,8 @@ export interface ConvertResult {
   data: Uint8Array;
   format: string;
   mime_type: string;
+  width: number;
+  height: number;
   take_data(): Uint8Array;
 }
 
@@ -42,15 +60,72 @@ export interface VideoDataInfo {
   data_size: number;
 }
 
+export interface MipmapDataInfo {
+  offset: number;
+  size: number;
+  is_lz4_compressed: boolean;
+  decompressed_size: number;
+  width: number;
+  height: number;
+  format: string;
+}
+
+export interface RawRgba {
+  readonly width: number;
+  readonly height: number;
+  readonly data: Uint8Array;
+  take_data(): Uint8Array;
+}
+
+/** Error thrown by WASM functions. */
+export interface WasmError extends Error {
+  /** Machine-readable error code, e.g. `invalid_tex_magic` */
+  kind: string;
+  suggestion?: string;
+}
+
+export type BatchResult =
+  | { ok: true; data: Uint8Array }
+  | { ok: false; error: string; kind: string };
+
+export type PkgTextureResult =
+  | { path: string; data: Uint8Array; format: string; mime_type: string }
+  | { path: string; error: string; kind: string };
+
+export interface ConversionHandle {
+  readonly cancelled: boolean;
+  cancel(): void;
+  run(shouldCancel?: () => boolean): Uint8Array;
+}
+
 export interface WasmModule {
   parse_pkg(bytes: Uint8Array): PkgInfo;
+  parse_pkg_info(bytes: Uint8Array): PkgInfo;
   extract_pkg_entry(bytes: Uint8Array, path: string): Uint8Array;
-  extract_all_pkg(bytes: Uint8Array): ExtractedFile[];
+  extract_all_pkg(
+    bytes: Uint8Array,
+    progress?: (index: number, total: number, path: string) => void
+  ): ExtractedFile[];
   extract_selected_pkg(bytes: Uint8Array, paths: string[]): ExtractedFile[];
   parse_tex(bytes: Uint8Array): TexInfo;
-  convert_tex(bytes: Uint8Array, format: string): Uint8Array;
-  convert_tex_auto(bytes: Uint8Array): ConvertResult;
+  convert_tex(bytes: Uint8Array, format: string, quality?: number): Uint8Array;
+  convert_tex_auto(bytes: Uint8Array, quality?: number): ConvertResult;
+  recommended_format_for_tex(bytes: Uint8Array): string;
+  convert_tex_mipmap(bytes: Uint8Array, level: number, format: string): Uint8Array;
+  convert_tex_cancellable(bytes: Uint8Array, format: string): ConversionHandle;
+  convert_tex_batch(
+    buffers: Uint8Array[],
+    format: string,
+    quality?: number
+  ): BatchResult[];
+  convert_pkg_textures(bytes: Uint8Array, format: string): PkgTextureResult[];
+  tex_to_raw_rgba(bytes: Uint8Array): RawRgba;
   get_video_data_location(bytes: Uint8Array): VideoDataInfo;
+  get_mipmap_data_location(
+    bytes: Uint8Array,
+    image_index: number,
+    mipmap_index: number
+  ): MipmapDataInfo;
 }
 
 let wasmModule: WasmModule | null = null;